@@ -0,0 +1,259 @@
+//! The `batch` command: query many servers read from a file (or stdin) at once.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::time::Duration;
+
+use gamedig::{
+    errors::QueryStage,
+    games::{query_many, GameRegistry, QueryTarget},
+    protocols::types::{CommonResponse, ExtraRequestSettings, TimeoutSettings},
+};
+
+use crate::error::{Error, Result};
+use crate::target::{format_address, parse_lines, Target};
+use crate::{find_game, output_value, resolve_ip_or_domain, OutputFormat, OutputMode};
+
+/// Read the targets: a JSON array of `{"game", "ip", "port"}` objects, or
+/// [lines](parse_lines).
+pub fn parse_targets(input: &str) -> Result<Vec<(String, Result<Target>)>> {
+    if input.trim_start().starts_with('[') {
+        let targets: Vec<Target> = serde_json::from_str(input)?;
+        return Ok(targets
+            .into_iter()
+            .map(|target| (target.game.clone(), Ok(target)))
+            .collect());
+    }
+
+    Ok(parse_lines(input))
+}
+
+/// Why a target failed.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct BatchError {
+    /// The [gamedig error kind](gamedig::errors::GDErrorKind), or the CLI
+    /// error (`InvalidTarget`, `UnknownGame` or `InvalidHostname`).
+    pub kind: String,
+    pub message: String,
+    /// The [stage of the query](gamedig::errors::QueryStage) that failed, if
+    /// known.
+    pub stage: Option<QueryStage>,
+}
+
+impl From<Error> for BatchError {
+    fn from(error: Error) -> Self {
+        let kind = match &error {
+            Error::Gamedig(error) => {
+                return Self {
+                    kind: format!("{:?}", error.kind),
+                    message: crate::gamedig_error_reason(error),
+                    stage: error.stage,
+                };
+            }
+            Error::InvalidTarget(_) => "InvalidTarget",
+            Error::UnknownGame(_) => "UnknownGame",
+            Error::InvalidHostname(_) => "InvalidHostname",
+            _ => "Other",
+        };
+
+        Self {
+            kind: kind.to_string(),
+            message: error.to_string(),
+            stage: None,
+        }
+    }
+}
+
+/// One output record per target, either with the response or with the error.
+#[derive(Debug, serde::Serialize)]
+pub struct BatchRecord<'a, T> {
+    /// Position of the target in the input.
+    pub index: usize,
+    pub game: &'a str,
+    pub address: String,
+    pub result: Option<T>,
+    pub error: Option<BatchError>,
+}
+
+/// Query all the targets of the input (a file or stdin) concurrently, printing
+/// one record per target as they finish (in the given format, JSON making it
+/// NDJSON).
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    registry: &GameRegistry,
+    input: Option<&Path>,
+    output_mode: OutputMode,
+    format: OutputFormat,
+    concurrency: usize,
+    deadline: Duration,
+    timeout_settings: Option<TimeoutSettings>,
+    extra_options: Option<ExtraRequestSettings>,
+) -> Result<()> {
+    let input = match input {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+    let targets = parse_targets(&input)?;
+
+    // Hostnames are only resolved once.
+    let mut resolved: HashMap<String, (IpAddr, Option<ExtraRequestSettings>)> = HashMap::new();
+    let mut queued = Vec::new();
+    let mut queries = Vec::new();
+
+    for (index, (game_id, target)) in targets.into_iter().enumerate() {
+        let target = match target {
+            Ok(target) => target,
+            Err(error) => {
+                output_record(
+                    &format,
+                    &output_mode,
+                    index,
+                    &game_id,
+                    String::new(),
+                    Err(error),
+                )?;
+                continue;
+            }
+        };
+
+        let query = find_game(registry, &target.game).and_then(|game| {
+            let (ip, extra_settings) = match resolved.get(&target.ip) {
+                Some(resolved) => resolved.clone(),
+                None => {
+                    let mut extra_settings = extra_options.clone();
+                    let ip = resolve_ip_or_domain(&target.ip, &mut extra_settings)?;
+                    resolved.insert(target.ip.clone(), (ip, extra_settings.clone()));
+                    (ip, extra_settings)
+                }
+            };
+
+            Ok(QueryTarget {
+                game: game.clone(),
+                address: SocketAddr::new(ip, target.port.unwrap_or(game.default_port)),
+                timeout_settings,
+                extra_settings,
+            })
+        });
+
+        match query {
+            Ok(query) => {
+                queries.push(query);
+                queued.push((index, target));
+            }
+            Err(error) => {
+                output_record(
+                    &format,
+                    &output_mode,
+                    index,
+                    &game_id,
+                    format_address(&target.ip, target.port),
+                    Err(error),
+                )?;
+            }
+        }
+    }
+
+    for (query_index, result) in query_many(queries, concurrency, deadline) {
+        let (index, target) = &queued[query_index];
+        output_record(
+            &format,
+            &output_mode,
+            *index,
+            &target.game,
+            format_address(&target.ip, target.port),
+            result.map_err(Error::from),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The formats that can't output the records (only responses), or not as a
+/// stream of them (every CSV record having its own columns and header).
+pub const UNSUPPORTED_FORMATS: &[OutputFormat] = &[
+    #[cfg(feature = "csv")]
+    OutputFormat::Csv,
+    #[cfg(feature = "table")]
+    OutputFormat::Table,
+    OutputFormat::Influx,
+    OutputFormat::OpenMetrics,
+];
+
+/// Output the record of a target, failing if it can't be written.
+fn output_record(
+    format: &OutputFormat,
+    output_mode: &OutputMode,
+    index: usize,
+    game: &str,
+    address: String,
+    result: Result<Box<dyn CommonResponse>>,
+) -> Result<()> {
+    let (response, error) = match result {
+        Ok(response) => (Some(response), None),
+        Err(error) => (None, Some(BatchError::from(error))),
+    };
+
+    match output_mode {
+        OutputMode::Generic => {
+            output_value(
+                format,
+                BatchRecord {
+                    index,
+                    game,
+                    address,
+                    result: response.as_ref().map(|response| response.as_json()),
+                    error,
+                },
+            )
+        }
+        OutputMode::ProtocolSpecific => {
+            output_value(
+                format,
+                BatchRecord {
+                    index,
+                    game,
+                    address,
+                    result: response.as_ref().map(|response| response.as_original()),
+                    error,
+                },
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(game: &str, ip: &str, port: Option<u16>) -> Target {
+        Target {
+            game: game.to_string(),
+            ip: ip.to_string(),
+            port,
+        }
+    }
+
+    #[test]
+    fn parse_inputs() {
+        let targets = parse_targets("# servers\nteamfortress2 127.0.0.1\n\nbad\n").unwrap();
+        assert_eq!(targets.len(), 2);
+        assert!(
+            matches!(&targets[0], (game, Ok(t)) if game == "teamfortress2" && *t == target("teamfortress2", "127.0.0.1", None))
+        );
+        assert!(matches!(&targets[1], (game, Err(Error::InvalidTarget(_))) if game == "bad"));
+
+        let targets = parse_targets(r#"[{"game": "minecraft", "ip": "::1", "port": 25566}]"#).unwrap();
+        assert_eq!(targets.len(), 1);
+        let target = targets[0].1.as_ref().unwrap();
+        assert_eq!(target.game, "minecraft");
+        assert_eq!(format_address(&target.ip, target.port), "[::1]:25566");
+
+        assert!(parse_targets("[{}]").is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//! The `csv` output format (RFC 4180): values are flattened to columns, the
+//! players of a response being one row each.
+
+use std::fmt;
+
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Serialize;
+use serde_json::Value;
+
+/// The name of the array whose elements are rows.
+const PLAYERS: &str = "players";
+
+/// Flattened fields of a value, in order.
+pub type Fields = Vec<(String, String)>;
+
+/// A JSON value that keeps its object fields in order (the order of the fields
+/// of the serialized structs), which [Value] doesn't.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Null,
+    /// A boolean or a number, as JSON.
+    Literal(String),
+    String(String),
+    Array(Vec<Node>),
+    Object(Vec<(String, Node)>),
+}
+
+impl Node {
+    fn to_json(&self) -> String {
+        match self {
+            Self::Null => "null".to_string(),
+            Self::Literal(literal) => literal.clone(),
+            Self::String(string) => Value::from(string.as_str()).to_string(),
+            Self::Array(array) => {
+                format!(
+                    "[{}]",
+                    array
+                        .iter()
+                        .map(Self::to_json)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                )
+            }
+            Self::Object(object) => {
+                let fields: Vec<String> = object
+                    .iter()
+                    .map(|(key, value)| format!("{}:{}", Value::from(key.as_str()), value.to_json()))
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NodeVisitor)
+    }
+}
+
+struct NodeVisitor;
+
+impl<'de> Visitor<'de> for NodeVisitor {
+    type Value = Node;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result { formatter.write_str("a JSON value") }
+
+    fn visit_unit<E>(self) -> Result<Node, E> { Ok(Node::Null) }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Node, E> { Ok(Node::Literal(value.to_string())) }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Node, E> { Ok(Node::Literal(value.to_string())) }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Node, E> { Ok(Node::Literal(value.to_string())) }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Node, E> { Ok(Node::Literal(Value::from(value).to_string())) }
+
+    fn visit_str<E>(self, value: &str) -> Result<Node, E> { Ok(Node::String(value.to_string())) }
+
+    fn visit_string<E>(self, value: String) -> Result<Node, E> { Ok(Node::String(value)) }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Node, A::Error> {
+        let mut array = Vec::new();
+        while let Some(element) = seq.next_element()? {
+            array.push(element);
+        }
+        Ok(Node::Array(array))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Node, A::Error> {
+        let mut object = Vec::new();
+        while let Some(entry) = map.next_entry()? {
+            object.push(entry);
+        }
+        Ok(Node::Object(object))
+    }
+}
+
+/// Quote a field if it has to be.
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Flatten a value into fields, nested objects fields being named
+/// `parent.child` and arrays being kept as JSON, except the players array (the
+/// first one found) which is taken out.
+fn flatten(node: &Node, name: String, fields: &mut Fields, players: &mut Option<Vec<Node>>) {
+    match node {
+        Node::Object(object) => flatten_object(object, &name, fields, players),
+        Node::Array(array) if players.is_none() && name.rsplit('.').next() == Some(PLAYERS) => {
+            *players = Some(array.clone());
+        }
+        Node::Null => fields.push((name, String::new())),
+        Node::String(string) | Node::Literal(string) => fields.push((name, string.clone())),
+        Node::Array(_) => fields.push((name, node.to_json())),
+    }
+}
+
+fn flatten_object(object: &[(String, Node)], prefix: &str, fields: &mut Fields, players: &mut Option<Vec<Node>>) {
+    for (key, value) in object {
+        let name = match prefix {
+            "" => key.clone(),
+            _ => format!("{prefix}.{key}"),
+        };
+        flatten(value, name, fields, players);
+    }
+}
+
+/// Render rows of fields, the columns being all the field names in order of
+/// appearance (a row missing one leaving it empty).
+pub fn render_rows(rows: &[Fields]) -> String {
+    let mut columns: Vec<&str> = Vec::new();
+    for (name, _) in rows.iter().flatten() {
+        if !columns.contains(&name.as_str()) {
+            columns.push(name);
+        }
+    }
+
+    let mut lines = vec![columns
+        .iter()
+        .map(|column| escape(column))
+        .collect::<Vec<_>>()
+        .join(",")];
+
+    for row in rows {
+        lines.push(
+            columns
+                .iter()
+                .map(|column| {
+                    row.iter()
+                        .find(|(name, _)| name == column)
+                        .map(|(_, value)| escape(value))
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+
+    lines.join("\n")
+}
+
+/// Render a value as CSV: one row per player (its fields being named
+/// `player.<field>`) with the other fields repeated on each, or a single row if
+/// there are no players, the columns being in the order of the fields.
+pub fn render<T: Serialize>(value: &T) -> serde_json::Result<String> {
+    // Through JSON, as it is written in the order of the fields.
+    let node = serde_json::from_str(&serde_json::to_string(value)?)?;
+
+    Ok(render_node(&node))
+}
+
+fn render_node(node: &Node) -> String {
+    let mut fields = Fields::new();
+    let mut players = None;
+    flatten(node, String::new(), &mut fields, &mut players);
+
+    let players = players.unwrap_or_default();
+    if players.is_empty() {
+        return render_rows(&[fields]);
+    }
+
+    let rows: Vec<Fields> = players
+        .iter()
+        .map(|player| {
+            let mut row = fields.clone();
+            flatten(
+                player,
+                "player".to_string(),
+                &mut row,
+                &mut Some(Vec::new()),
+            );
+            row
+        })
+        .collect();
+
+    render_rows(&rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_json(json: &str) -> String { render_node(&serde_json::from_str(json).unwrap()) }
+
+    #[test]
+    fn players_rows() {
+        let response = r#"{
+            "name": "A \"cool\", server",
+            "map": "cp_foundry",
+            "players_online": 2,
+            "has_password": null,
+            "players": [
+                {"name": "Alice", "score": 12},
+                {"name": "Bob", "score": 7, "team": 2}
+            ]
+        }"#;
+
+        assert_eq!(
+            render_json(response),
+            "name,map,players_online,has_password,player.name,player.score,player.team\n\"A \"\"cool\"\", \
+             server\",cp_foundry,2,,Alice,12,\n\"A \"\"cool\"\", server\",cp_foundry,2,,Bob,7,2"
+        );
+    }
+
+    #[test]
+    fn fields_order() {
+        #[derive(Serialize)]
+        struct Response {
+            zone: &'static str,
+            address: &'static str,
+        }
+
+        assert_eq!(
+            render(&Response {
+                zone: "eu",
+                address: "127.0.0.1"
+            })
+            .unwrap(),
+            "zone,address\neu,127.0.0.1"
+        );
+    }
+
+    #[test]
+    fn nested() {
+        // Like a protocol specific response.
+        let response = r#"{
+            "Valve": {
+                "info": {"name": "Server", "tags": ["a", {"z": 1.0, "y": true}]},
+                "players": []
+            }
+        }"#;
+
+        assert_eq!(
+            render_json(response),
+            "Valve.info.name,Valve.info.tags\nServer,\"[\"\"a\"\",{\"\"z\"\":1.0,\"\"y\"\":true}]\""
+        );
+    }
+}
@@ -11,7 +11,12 @@ pub enum Error {
     #[error("Gamedig Error: {0}")]
     Gamedig(#[from] gamedig::errors::GDError),
 
-    #[cfg(any(feature = "json", feature = "xml"))]
+    #[cfg(any(
+        feature = "json",
+        feature = "xml",
+        feature = "csv",
+        feature = "games_file"
+    ))]
     #[error("Serde Error: {0}")]
     Serde(#[from] serde_json::Error),
 
@@ -23,9 +28,23 @@ pub enum Error {
     #[error("Xml Error: {0}")]
     Xml(#[from] quick_xml::Error),
 
+    #[cfg(feature = "games_file")]
+    #[error("Toml Error: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[cfg(feature = "games_file")]
+    #[error("Invalid game definitions:\n{}", .0.join("\n"))]
+    InvalidGameDefinitions(Vec<String>),
+
+    #[error("Invalid target: {0}")]
+    InvalidTarget(String),
+
     #[error("Unknown Game: {0}")]
     UnknownGame(String),
 
     #[error("Invalid hostname: {0}")]
     InvalidHostname(String),
+
+    #[error("Unsupported output format: {}", crate::format_name(.0))]
+    UnsupportedFormat(crate::OutputFormat),
 }
@@ -0,0 +1,260 @@
+//! The `exporter` command: serve the state of servers as Prometheus metrics,
+//! the servers being queried on every scrape.
+
+use std::net::SocketAddr;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use gamedig::{
+    games::{query_with_timeout_and_extra_settings, Game, GameRegistry},
+    metrics::{self, write_gauge, Gauge, PLAYERS_BOTS, PLAYERS_MAX, PLAYERS_ONLINE},
+    protocols::types::{ExtraRequestSettings, TimeoutSettings},
+};
+
+use crate::error::{Error, Result};
+use crate::http::{self, Request, Response};
+use crate::target::{format_address, parse_lines, Target};
+use crate::{find_game, resolve_ip_or_domain};
+
+/// The content type of the Prometheus text format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// A server to export.
+struct Export {
+    game_id: String,
+    game: Game,
+    target: Target,
+}
+
+impl Export {
+    /// The address label (`host:port`), with the default port of the game if
+    /// none was given.
+    fn address(&self) -> String {
+        format_address(
+            &self.target.ip,
+            Some(self.target.port.unwrap_or(self.game.default_port)),
+        )
+    }
+}
+
+/// What a query of a server returned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    pub players_online: u32,
+    pub players_maximum: u32,
+    pub players_bots: Option<u32>,
+    pub has_password: Option<bool>,
+}
+
+/// The outcome of the query of a server during a scrape.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub game: String,
+    pub address: String,
+    pub duration: Duration,
+    /// None if the query failed.
+    pub status: Option<Status>,
+}
+
+/// How to get the value of a gauge from a sample (None to skip it).
+type GaugeValue = fn(&Sample) -> Option<f64>;
+
+const GAUGES: [(Gauge, GaugeValue); 6] = [
+    (
+        Gauge {
+            name: "up",
+            help: "Whether the query of the server succeeded.",
+        },
+        |sample| Some(if sample.status.is_some() { 1. } else { 0. }),
+    ),
+    (
+        Gauge {
+            name: "query_duration_seconds",
+            help: "How long the query of the server took.",
+        },
+        |sample| Some(sample.duration.as_secs_f64()),
+    ),
+    (PLAYERS_ONLINE, |sample| {
+        Some(sample.status.as_ref()?.players_online.into())
+    }),
+    (PLAYERS_MAX, |sample| {
+        Some(sample.status.as_ref()?.players_maximum.into())
+    }),
+    (PLAYERS_BOTS, |sample| {
+        sample.status.as_ref()?.players_bots.map(f64::from)
+    }),
+    (
+        Gauge {
+            name: "password_protected",
+            help: "Whether the server requires a password.",
+        },
+        |sample| {
+            sample
+                .status
+                .as_ref()?
+                .has_password
+                .map(|has_password| if has_password { 1. } else { 0. })
+        },
+    ),
+];
+
+/// Render the samples in the Prometheus text format.
+pub fn render(samples: &[Sample]) -> String {
+    let mut text = String::new();
+
+    for (gauge, value) in &GAUGES {
+        let gauge_samples: Vec<metrics::Sample> = samples
+            .iter()
+            .filter_map(|sample| {
+                let labels = vec![
+                    ("game", sample.game.as_str()),
+                    ("address", sample.address.as_str()),
+                ];
+                Some((labels, value(sample)?))
+            })
+            .collect();
+
+        write_gauge(&mut text, gauge, &gauge_samples);
+    }
+
+    text
+}
+
+/// Query all the servers at once.
+fn scrape(
+    exports: &[Export],
+    timeout_settings: Option<TimeoutSettings>,
+    extra_options: &Option<ExtraRequestSettings>,
+) -> Vec<Sample> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = exports
+            .iter()
+            .map(|export| {
+                scope.spawn(move || {
+                    let started = Instant::now();
+                    let status = (|| {
+                        let mut extra_settings = extra_options.clone();
+                        let ip = resolve_ip_or_domain(&export.target.ip, &mut extra_settings)?;
+                        let response = query_with_timeout_and_extra_settings(
+                            &export.game,
+                            &ip,
+                            export.target.port,
+                            timeout_settings,
+                            extra_settings,
+                        )?;
+
+                        Ok::<_, Error>(Status {
+                            players_online: response.players_online(),
+                            players_maximum: response.players_maximum(),
+                            players_bots: response.players_bots(),
+                            has_password: response.has_password(),
+                        })
+                    })();
+
+                    Sample {
+                        game: export.game_id.clone(),
+                        address: export.address(),
+                        duration: started.elapsed(),
+                        status: status.ok(),
+                    }
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok())
+            .collect()
+    })
+}
+
+/// Serve the metrics of the servers of the targets file on `/metrics`.
+pub fn run(
+    registry: &GameRegistry,
+    targets: &Path,
+    listen: SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+    extra_options: Option<ExtraRequestSettings>,
+) -> Result<()> {
+    // Invalid targets are reported before serving anything.
+    let exports = parse_lines(&std::fs::read_to_string(targets)?)
+        .into_iter()
+        .map(|(_, target)| {
+            let target = target?;
+            let game = find_game(registry, &target.game)?;
+            Ok(Export {
+                game_id: registry
+                    .resolve(&target.game)
+                    .unwrap_or(&target.game)
+                    .to_string(),
+                game: game.clone(),
+                target,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    eprintln!(
+        "Exporting {} servers on http://{listen}/metrics",
+        exports.len()
+    );
+
+    http::serve(listen, move |request: &Request| {
+        match request.path.as_str() {
+            "/metrics" => {
+                Response::new(
+                    200,
+                    CONTENT_TYPE,
+                    render(&scrape(&exports, timeout_settings, &extra_options)),
+                )
+            }
+            "/" => Response::text(200, "gamedig-cli exporter, the metrics are on /metrics\n"),
+            _ => Response::text(404, "Not found\n"),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rendered() {
+        let samples = [
+            Sample {
+                game: "teamfortress2".to_string(),
+                address: "127.0.0.1:27015".to_string(),
+                duration: Duration::from_millis(25),
+                status: Some(Status {
+                    players_online: 12,
+                    players_maximum: 24,
+                    players_bots: None,
+                    has_password: Some(false),
+                }),
+            },
+            Sample {
+                game: "minecraft".to_string(),
+                address: "mc.\"example\".com:25565".to_string(),
+                duration: Duration::from_secs(2),
+                status: None,
+            },
+        ];
+
+        let text = render(&samples);
+        for line in [
+            "# TYPE gamedig_up gauge",
+            "gamedig_up{game=\"teamfortress2\",address=\"127.0.0.1:27015\"} 1",
+            "gamedig_up{game=\"minecraft\",address=\"mc.\\\"example\\\".com:25565\"} 0",
+            "gamedig_query_duration_seconds{game=\"teamfortress2\",address=\"127.0.0.1:27015\"} 0.025",
+            "gamedig_players_online{game=\"teamfortress2\",address=\"127.0.0.1:27015\"} 12",
+            "gamedig_players_max{game=\"teamfortress2\",address=\"127.0.0.1:27015\"} 24",
+            "gamedig_password_protected{game=\"teamfortress2\",address=\"127.0.0.1:27015\"} 0",
+        ] {
+            assert!(text.lines().any(|l| l == line), "missing {line}");
+        }
+
+        // Unknown values and failed queries have no samples.
+        assert!(!text.contains("gamedig_players_bots{"));
+        assert!(!text.contains("gamedig_players_online{game=\"minecraft\""));
+    }
+}
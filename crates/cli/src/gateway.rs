@@ -0,0 +1,301 @@
+//! The `gateway` command: serve server queries over HTTP as JSON, for the
+//! clients that can't send raw UDP or TCP themselves (such as web pages).
+//!
+//! * `GET /query/{game}/{host}[:{port}]` queries a server, the query string
+//!   overriding the settings of the command
+//!   (`read-timeout=2&gather-players=try`).
+//! * `GET /games` lists the games (optionally `?protocol=valve`).
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use gamedig::{
+    errors::GDErrorKind,
+    games::{GameRegistry, QueryCache},
+    protocols::types::{CommonResponse, ExtraRequestSettings, GatherToggle, TimeoutSettings},
+};
+
+use crate::batch::BatchError;
+use crate::error::{Error, Result};
+use crate::http::{self, percent_decode, Request, Response};
+use crate::target::Target;
+use crate::{find_game, gamedig_error_reason, list_games, resolve_ip_or_domain, OutputMode, ProtocolFamily};
+
+const JSON: &str = "application/json";
+
+/// How to query a server, from the command and the query string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuerySettings {
+    pub timeout_settings: Option<TimeoutSettings>,
+    pub extra_options: Option<ExtraRequestSettings>,
+    pub output_mode: OutputMode,
+}
+
+impl QuerySettings {
+    /// These settings with the parameters of a query string over them
+    /// (`read-timeout`, `gather-players`...: the names of the query options
+    /// of the CLI), the error being the message for the client.
+    pub fn with_params(&self, params: &[(String, String)]) -> std::result::Result<Self, String> {
+        let mut settings = self.clone();
+
+        let timeouts = settings.timeout_settings.unwrap_or_default();
+        let (mut read, mut write, mut connect, mut retries) = (
+            timeouts.get_read(),
+            timeouts.get_write(),
+            timeouts.get_connect(),
+            timeouts.get_retries(),
+        );
+        let mut timeouts_changed = false;
+        let mut extra = settings.extra_options.clone().unwrap_or_default();
+
+        for (name, value) in params {
+            let invalid = || format!("Invalid value for {name}: {value:?}");
+            let seconds = || {
+                value
+                    .parse()
+                    .ok()
+                    .filter(|seconds| *seconds > 0)
+                    .map(Duration::from_secs)
+                    .ok_or_else(invalid)
+            };
+            let toggle = || GatherToggle::from_str(value, true).map_err(|_| invalid());
+
+            match name.as_str() {
+                "connect-timeout" => connect = Some(seconds()?),
+                "read-timeout" => read = Some(seconds()?),
+                "write-timeout" => write = Some(seconds()?),
+                "retries" => retries = value.parse().map_err(|_| invalid())?,
+                "hostname" => extra.hostname = Some(value.clone()),
+                "protocol-version" => extra.protocol_version = Some(value.parse().map_err(|_| invalid())?),
+                "gather-players" => extra.gather_players = Some(toggle()?),
+                "gather-rules" => extra.gather_rules = Some(toggle()?),
+                "check-app-id" => extra.check_app_id = Some(value.parse().map_err(|_| invalid())?),
+                "output-mode" => settings.output_mode = OutputMode::from_str(value, true).map_err(|_| invalid())?,
+                _ => return Err(format!("Unknown parameter: {name}")),
+            }
+
+            timeouts_changed |= name.ends_with("-timeout") || name == "retries";
+        }
+
+        if timeouts_changed {
+            settings.timeout_settings =
+                Some(TimeoutSettings::new(read, write, connect, retries).map_err(|e| gamedig_error_reason(&e))?);
+        }
+        if extra != ExtraRequestSettings::default() {
+            settings.extra_options = Some(extra);
+        }
+
+        Ok(settings)
+    }
+}
+
+/// A JSON error response, with the same fields as the errors of `batch`.
+fn error_response(error: Error) -> Response {
+    let status = match &error {
+        Error::UnknownGame(_) => 404,
+        Error::InvalidTarget(_) | Error::InvalidHostname(_) => 400,
+        Error::Gamedig(error) if error.kind == GDErrorKind::InvalidInput => 400,
+        // The server couldn't be queried.
+        Error::Gamedig(_) => 502,
+        _ => 500,
+    };
+
+    json_error(status, BatchError::from(error))
+}
+
+/// A JSON error response for an invalid parameter of the query string.
+fn invalid_parameter(message: String) -> Response {
+    json_error(
+        400,
+        BatchError {
+            kind: "InvalidParameter".to_string(),
+            message,
+            stage: None,
+        },
+    )
+}
+
+fn json_error(status: u16, error: BatchError) -> Response {
+    Response::new(status, JSON, serde_json::to_vec(&error).unwrap_or_default())
+}
+
+/// Query a server (from the path after `/query/`).
+fn query(registry: &GameRegistry, cache: &QueryCache, path: &str, settings: QuerySettings) -> Result<Response> {
+    let (game, address) = path.split_once('/').unwrap_or((path, ""));
+    let (Some(game), Some(address)) = (percent_decode(game), percent_decode(address)) else {
+        return Err(Error::InvalidTarget(path.to_string()));
+    };
+    let target = Target::parse_line(&format!("{game} {address}"))?;
+
+    let game = find_game(registry, &target.game)?;
+    let mut extra_options = settings.extra_options;
+    let ip = resolve_ip_or_domain(&target.ip, &mut extra_options)?;
+    let response = cache.query(
+        game,
+        &ip,
+        target.port,
+        settings.timeout_settings,
+        extra_options,
+    )?;
+
+    let body = match settings.output_mode {
+        OutputMode::Generic => serde_json::to_vec(&response.as_json())?,
+        OutputMode::ProtocolSpecific => serde_json::to_vec(&response.as_original())?,
+    };
+
+    Ok(Response::new(200, JSON, body))
+}
+
+/// Handle a request.
+fn handle(registry: &GameRegistry, cache: &QueryCache, defaults: &QuerySettings, request: &Request) -> Response {
+    let Some(params) = request.params() else {
+        return invalid_parameter(format!("Invalid query string: {}", request.query));
+    };
+
+    if request.path == "/games" {
+        let protocol = match params.as_slice() {
+            [] => None,
+            [(name, value)] if name == "protocol" => {
+                let Ok(protocol) = ProtocolFamily::from_str(value, true) else {
+                    return invalid_parameter(format!("Unknown protocol family: {value}"));
+                };
+                Some(protocol)
+            }
+            _ => return invalid_parameter("Only the protocol parameter is supported".to_string()),
+        };
+
+        return match serde_json::to_vec(&list_games(registry, protocol)) {
+            Ok(body) => Response::new(200, JSON, body),
+            Err(error) => error_response(error.into()),
+        };
+    }
+
+    if let Some(path) = request.path.strip_prefix("/query/") {
+        let settings = match defaults.with_params(&params) {
+            Ok(settings) => settings,
+            Err(message) => return invalid_parameter(message),
+        };
+
+        return query(registry, cache, path, settings).unwrap_or_else(error_response);
+    }
+
+    match request.path.as_str() {
+        "/" => {
+            Response::text(
+                200,
+                "gamedig-cli gateway, query servers on /query/{game}/{host}[:{port}] and list the games on /games\n",
+            )
+        }
+        _ => Response::text(404, "Not found\n"),
+    }
+}
+
+/// Serve the queries of servers (and the list of the games) on an address.
+pub fn run(
+    registry: GameRegistry,
+    listen: SocketAddr,
+    cache_ttl: Duration,
+    failure_cache_ttl: Duration,
+    allow_origin: Option<String>,
+    defaults: QuerySettings,
+) -> Result<()> {
+    let cache = QueryCache::new()
+        .set_ttl(cache_ttl)
+        .set_failure_ttl(failure_cache_ttl);
+
+    eprintln!("Serving queries on http://{listen}/query/{{game}}/{{host}}[:{{port}}]");
+
+    http::serve(listen, move |request: &Request| {
+        let response = handle(&registry, &cache, &defaults, request);
+        match &allow_origin {
+            Some(origin) => response.with_header("Access-Control-Allow-Origin", origin.clone()),
+            None => response,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(query: &str) -> Vec<(String, String)> {
+        Request::parse(&format!("GET /?{query} HTTP/1.1"))
+            .unwrap()
+            .params()
+            .unwrap()
+    }
+
+    fn defaults() -> QuerySettings {
+        QuerySettings {
+            timeout_settings: None,
+            extra_options: None,
+            output_mode: OutputMode::Generic,
+        }
+    }
+
+    #[test]
+    fn settings_from_params() {
+        assert_eq!(defaults().with_params(&[]), Ok(defaults()));
+
+        let settings = defaults()
+            .with_params(&params(
+                "read-timeout=2&retries=1&gather-players=try&check-app-id=false&output-mode=protocol-specific",
+            ))
+            .unwrap();
+        let timeouts = settings.timeout_settings.unwrap();
+        assert_eq!(timeouts.get_read(), Some(Duration::from_secs(2)));
+        assert_eq!(timeouts.get_write(), TimeoutSettings::default().get_write());
+        assert_eq!(timeouts.get_retries(), 1);
+        assert_eq!(
+            settings.extra_options,
+            Some(
+                ExtraRequestSettings::default()
+                    .set_gather_players(GatherToggle::Try)
+                    .set_check_app_id(false)
+            )
+        );
+        assert_eq!(settings.output_mode, OutputMode::ProtocolSpecific);
+
+        for query in [
+            "read-timeout=0",
+            "read-timeout=soon",
+            "gather-rules=always",
+            "output-mode=xml",
+            "port=27015",
+        ] {
+            assert!(defaults().with_params(&params(query)).is_err(), "{query}");
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let registry = GameRegistry::new();
+        let cache = QueryCache::new();
+        let get = |target: &str| {
+            handle(
+                &registry,
+                &cache,
+                &defaults(),
+                &Request::parse(&format!("GET {target} HTTP/1.1")).unwrap(),
+            )
+        };
+
+        assert_eq!(get("/query/notagame/127.0.0.1").status, 404);
+        assert_eq!(get("/query/teamfortress2/127.0.0.1:notaport").status, 400);
+        assert_eq!(get("/query/teamfortress2").status, 400);
+        assert_eq!(
+            get("/query/teamfortress2/127.0.0.1?read-timeout=0").status,
+            400
+        );
+        assert_eq!(get("/games?protocol=notaprotocol").status, 400);
+        assert_eq!(get("/nothing").status, 404);
+
+        let games: serde_json::Value = serde_json::from_slice(&get("/games?protocol=unreal2").body).unwrap();
+        assert!(games["games"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|game| game["id"] == "unrealtournament2004"));
+    }
+}
@@ -0,0 +1,319 @@
+//! A minimal HTTP/1.1 server (one thread per connection, no keep-alive) for
+//! the commands that serve their results.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+
+/// Maximum size of a request head (request line and headers).
+const MAX_HEAD_SIZE: usize = 8 * 1024;
+
+/// How long a client has to send its request (and to receive the response).
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of connections handled at the same time, the next ones
+/// being answered with a 503 right away.
+const MAX_CONNECTIONS: usize = 64;
+
+/// A request, only its line being kept (the headers and body are ignored).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    /// The query string (after `?`), empty if there is none.
+    pub query: String,
+}
+
+impl Request {
+    /// Parse a request line (`GET /path?query HTTP/1.1`).
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next()) else {
+            return None;
+        };
+
+        if !version.starts_with("HTTP/") || !target.starts_with('/') {
+            return None;
+        }
+
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        Some(Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: query.to_string(),
+        })
+    }
+
+    /// The decoded parameters of the query string, in order (None if one
+    /// can't be decoded).
+    #[cfg(feature = "json")]
+    pub fn params(&self) -> Option<Vec<(String, String)>> {
+        self.query
+            .split('&')
+            .filter(|param| !param.is_empty())
+            .map(|param| {
+                let (name, value) = param.split_once('=').unwrap_or((param, ""));
+                Some((
+                    percent_decode(&name.replace('+', " "))?,
+                    percent_decode(&value.replace('+', " "))?,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Decode the `%XX` escapes of a path segment or query parameter (None if an
+/// escape is invalid or the result isn't UTF-8).
+#[cfg(feature = "json")]
+pub fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(.. 2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2 ..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+/// A response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    /// Extra headers (name and value).
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(status: u16, content_type: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            content_type,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Add a header.
+    #[cfg(feature = "json")]
+    pub fn with_header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// A plain text response.
+    pub fn text(status: u16, body: impl Into<Vec<u8>>) -> Self { Self::new(status, "text/plain; charset=utf-8", body) }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            431 => "Request Header Fields Too Large",
+            500 => "Internal Server Error",
+            502 => "Bad Gateway",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "",
+        }
+    }
+}
+
+/// Serve requests on an address until the process is stopped, every
+/// connection being handled on its own thread (up to [MAX_CONNECTIONS]).
+pub fn serve(address: SocketAddr, handler: impl Fn(&Request) -> Response + Send + Sync + 'static) -> Result<()> {
+    let listener = TcpListener::bind(address)?;
+    let handler = Arc::new(handler);
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    for stream in listener.incoming() {
+        let Ok(mut stream) = stream else {
+            continue;
+        };
+
+        if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            connections.fetch_sub(1, Ordering::SeqCst);
+            let _ = stream.set_write_timeout(Some(READ_TIMEOUT)).and_then(|_| {
+                write_response(
+                    &mut stream,
+                    &Response::text(503, "Too many connections\n"),
+                    true,
+                )
+            });
+            continue;
+        }
+
+        let handler = handler.clone();
+        let connections = connections.clone();
+        thread::spawn(move || {
+            // The client going away isn't an error of ours.
+            let _ = handle_connection(stream, handler.as_ref());
+            connections.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+
+    Ok(())
+}
+
+/// A stream that can only be read until a deadline, so that a client can't
+/// keep a connection by sending its request slowly.
+struct DeadlineReader {
+    stream: TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+/// Read a request head (up to the empty line ending it, or the end of the
+/// stream), None if it is larger than [MAX_HEAD_SIZE].
+fn read_head(reader: impl BufRead) -> io::Result<Option<String>> {
+    let mut reader = reader.take(MAX_HEAD_SIZE as u64);
+    let mut head = String::new();
+
+    loop {
+        let read = reader.read_line(&mut head)?;
+        if head.ends_with("\r\n\r\n") || head.ends_with("\n\n") {
+            return Ok(Some(head));
+        }
+        if read == 0 {
+            return Ok((reader.limit() > 0).then_some(head));
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, handler: &dyn Fn(&Request) -> Response) -> io::Result<()> {
+    stream.set_write_timeout(Some(READ_TIMEOUT))?;
+
+    let head = read_head(BufReader::new(DeadlineReader {
+        stream: stream.try_clone()?,
+        deadline: Instant::now() + READ_TIMEOUT,
+    }))?;
+
+    let Some(head) = head else {
+        return write_response(
+            &mut stream,
+            &Response::text(431, "Request head too large\n"),
+            true,
+        );
+    };
+
+    let request = head.lines().next().and_then(Request::parse);
+    let response = match &request {
+        None => Response::text(400, "Bad request\n"),
+        Some(request) if request.method != "GET" && request.method != "HEAD" => {
+            Response::text(405, "Only GET requests are supported\n")
+        }
+        Some(request) => handler(request),
+    };
+
+    write_response(
+        &mut stream,
+        &response,
+        request.map_or(true, |request| request.method != "HEAD"),
+    )
+}
+
+fn write_response(stream: &mut TcpStream, response: &Response, with_body: bool) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.reason(),
+        response.content_type,
+        response.body.len()
+    )?;
+    for (name, value) in &response.headers {
+        write!(stream, "{name}: {value}\r\n")?;
+    }
+    write!(stream, "\r\n")?;
+    if with_body {
+        stream.write_all(&response.body)?;
+    }
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_lines() {
+        assert_eq!(
+            Request::parse("GET /metrics?target=1 HTTP/1.1"),
+            Some(Request {
+                method: "GET".to_string(),
+                path: "/metrics".to_string(),
+                query: "target=1".to_string(),
+            })
+        );
+        assert_eq!(Request::parse("GET / HTTP/1.0").unwrap().query, "");
+        assert_eq!(Request::parse("GET /"), None);
+        assert_eq!(Request::parse("GET metrics HTTP/1.1"), None);
+    }
+
+    #[test]
+    fn heads() {
+        assert_eq!(
+            read_head(&b"GET / HTTP/1.1\r\nHost: a\r\n\r\nbody"[..]).unwrap(),
+            Some("GET / HTTP/1.1\r\nHost: a\r\n\r\n".to_string())
+        );
+        assert_eq!(
+            read_head(&b"GET / HTTP/1.1\n"[..]).unwrap(),
+            Some("GET / HTTP/1.1\n".to_string())
+        );
+
+        // Without a newline.
+        assert_eq!(read_head(&[b'a'; MAX_HEAD_SIZE + 1][..]).unwrap(), None);
+        let mut head = b"GET / HTTP/1.1\r\n".to_vec();
+        head.extend(b"X-Padding: a\r\n".repeat(MAX_HEAD_SIZE / 10));
+        assert_eq!(read_head(&head[..]).unwrap(), None);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn query_params() {
+        let request = Request::parse("GET /query?read-timeout=2&hostname=a+b%2Fc&flag HTTP/1.1").unwrap();
+        assert_eq!(
+            request.params(),
+            Some(vec![
+                ("read-timeout".to_string(), "2".to_string()),
+                ("hostname".to_string(), "a b/c".to_string()),
+                ("flag".to_string(), String::new()),
+            ])
+        );
+
+        assert_eq!(
+            percent_decode("%5B%3A%3A1%5D:27015"),
+            Some("[::1]:27015".to_string())
+        );
+        assert_eq!(percent_decode("%E3%83%9C"), Some("ボ".to_string()));
+        assert_eq!(percent_decode("%2"), None);
+        assert_eq!(percent_decode("%zz"), None);
+        assert_eq!(percent_decode("%FF"), None);
+    }
+}
@@ -1,12 +1,25 @@
 use std::net::{IpAddr, ToSocketAddrs};
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
 use gamedig::{
     games::*,
-    protocols::types::{CommonResponse, ExtraRequestSettings, TimeoutSettings},
+    metrics::{to_influx_line, to_openmetrics, Series},
+    protocols::types::{CommonResponse, ExtraRequestSettings, Protocol, TimeoutSettings},
 };
 
+#[cfg(feature = "json")]
+mod batch;
+#[cfg(feature = "csv")]
+mod csv;
 mod error;
+mod exporter;
+#[cfg(feature = "json")]
+mod gateway;
+mod http;
+#[cfg(feature = "table")]
+mod table;
+mod target;
+mod watch;
 
 use self::error::{Error, Result};
 
@@ -33,6 +46,16 @@ const GAMEDIG_HEADER: &str = r"
 struct Cli {
     #[command(subcommand)]
     action: Action,
+
+    /// Optional game definitions file (JSON, or TOML if its extension is
+    /// `.toml`) whose entries are added over the library ones.
+    ///
+    /// The file is a map of game ids to game definitions, for example (in
+    /// JSON): `{"mygame": {"name": "My Game", "default_port": 27015,
+    /// "protocol": {"Valve": {"Source": [123456, null]}}}}`.
+    #[cfg(feature = "games_file")]
+    #[arg(long, global = true)]
+    games_file: Option<std::path::PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -73,6 +96,16 @@ enum Action {
         #[arg(short, long)]
         capture: Option<std::path::PathBuf>,
 
+        /// Query the server again every given number of seconds (until
+        /// interrupted), highlighting the changes (players joining or leaving,
+        /// map, version, password and players online changes) between polls.
+        ///
+        /// When the output isn't a terminal the response is only output once,
+        /// every change being then output as an event line (in JSON with the
+        /// JSON formats).
+        #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+        watch: Option<u64>,
+
         /// Optional timeout settings for the server query
         #[command(flatten, next_help_heading = "Timeouts")]
         timeout_settings: Option<TimeoutSettings>,
@@ -82,12 +115,191 @@ enum Action {
         extra_options: Option<ExtraRequestSettings>,
     },
 
+    /// Query many servers concurrently, read from a file (or stdin), printing
+    /// one record per server as they finish
+    #[cfg(feature = "json")]
+    Batch {
+        /// File with the servers to query, one `game host[:port]` per line (or
+        /// a JSON array of `{"game", "ip", "port"}` objects), stdin if
+        /// not given.
+        input: Option<std::path::PathBuf>,
+
+        /// Specifies the output format of the records (`json` and `ndjson`
+        /// making it NDJSON)
+        #[arg(short, long, default_value = "json", value_enum)]
+        format: OutputFormat,
+
+        /// Which response variant to use when outputting
+        #[arg(short, long, default_value = "generic")]
+        output_mode: OutputMode,
+
+        /// Maximum number of servers queried at the same time.
+        #[arg(short = 'j', long, default_value = "32")]
+        concurrency: usize,
+
+        /// Seconds after which no more queries are started, the unfinished
+        /// servers being reported as failed.
+        #[arg(long, default_value = "300")]
+        deadline: u64,
+
+        /// Optional timeout settings for every server query
+        #[command(flatten, next_help_heading = "Timeouts")]
+        timeout_settings: Option<TimeoutSettings>,
+
+        /// Optional extra settings for every server query
+        #[command(flatten, next_help_heading = "Query options")]
+        extra_options: Option<ExtraRequestSettings>,
+    },
+
+    /// Serve Prometheus metrics (players, query success and duration...) of
+    /// servers on `/metrics`, the servers being queried on every scrape
+    Exporter {
+        /// File with the servers to export, one `game host[:port]` per line.
+        targets: std::path::PathBuf,
+
+        /// Address to listen on.
+        #[arg(short, long, default_value = "127.0.0.1:9725")]
+        listen: std::net::SocketAddr,
+
+        /// Optional timeout settings for every server query
+        #[command(flatten, next_help_heading = "Timeouts")]
+        timeout_settings: Option<TimeoutSettings>,
+
+        /// Optional extra settings for every server query
+        #[command(flatten, next_help_heading = "Query options")]
+        extra_options: Option<ExtraRequestSettings>,
+    },
+
+    /// Serve server queries over HTTP as JSON (`GET
+    /// /query/{game}/{host}[:{port}]` and `GET /games`), for the clients
+    /// that can't query servers themselves
+    #[cfg(feature = "json")]
+    Gateway {
+        /// Address to listen on.
+        #[arg(short, long, default_value = "127.0.0.1:9726")]
+        listen: std::net::SocketAddr,
+
+        /// Seconds for which the response of a query is reused for the same
+        /// query (0 to always query the server, identical queries made at the
+        /// same time still sharing one).
+        #[arg(long, value_name = "SECONDS", default_value = "5")]
+        cache_ttl: u64,
+
+        /// Seconds for which a failed query is reused for the same query.
+        #[arg(long, value_name = "SECONDS", default_value = "1")]
+        failure_cache_ttl: u64,
+
+        /// Value of the `Access-Control-Allow-Origin` header (such as `*`),
+        /// to let web pages of other origins use the gateway.
+        #[arg(long)]
+        allow_origin: Option<String>,
+
+        /// Which response variant to return by default (the `output-mode`
+        /// parameter overriding it)
+        #[arg(short, long, default_value = "generic")]
+        output_mode: OutputMode,
+
+        /// Optional timeout settings for every server query (the parameters of
+        /// the same names overriding them)
+        #[command(flatten, next_help_heading = "Timeouts")]
+        timeout_settings: Option<TimeoutSettings>,
+
+        /// Optional extra settings for every server query (the parameters of
+        /// the same names overriding them)
+        #[command(flatten, next_help_heading = "Query options")]
+        extra_options: Option<ExtraRequestSettings>,
+    },
+
+    /// List the supported games (id, name, default port and protocol)
+    ListGames {
+        /// Only list the games of this protocol family
+        #[arg(short, long, value_enum)]
+        protocol: Option<ProtocolFamily>,
+
+        /// Specifies the output format, if not set the games are printed one
+        /// per line
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+
+    /// Look up the game definitions
+    Games {
+        #[command(subcommand)]
+        action: GamesAction,
+    },
+
     /// Check out the source code
     Source,
     /// Display the MIT License information
     License,
 }
 
+#[derive(Subcommand, Debug)]
+enum GamesAction {
+    /// Search the games by id, name or alias (best matches first), or by Steam
+    /// app id
+    Search {
+        /// Text to search for, case and punctuation being ignored.
+        #[arg(required_unless_present = "app_id")]
+        query: Option<String>,
+
+        /// Find the games that use this Steam app id instead (either as their
+        /// main or dedicated server app id).
+        #[arg(long, conflicts_with = "query")]
+        app_id: Option<u32>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ProtocolFamily {
+    Valve,
+    Gamespy,
+    Quake,
+    Unreal2,
+    Epic,
+    /// Game specific protocols
+    Proprietary,
+}
+
+impl ProtocolFamily {
+    fn of(protocol: &Protocol) -> Self {
+        match protocol {
+            Protocol::Valve(_) => Self::Valve,
+            Protocol::Gamespy(_) => Self::Gamespy,
+            Protocol::Quake(_) => Self::Quake,
+            Protocol::Unreal2 => Self::Unreal2,
+            Protocol::PROPRIETARY(_) => Self::Proprietary,
+            // Epic, which only exists with the library's tls feature.
+            #[allow(unreachable_patterns)]
+            _ => Self::Epic,
+        }
+    }
+}
+
+/// A game definition as listed by the `list-games` command.
+#[cfg_attr(
+    any(feature = "json", feature = "xml", feature = "bson", feature = "csv"),
+    derive(serde::Serialize)
+)]
+#[derive(Debug)]
+struct GameListing<'a> {
+    id: &'a str,
+    name: &'a str,
+    default_port: u16,
+    protocol: &'a Protocol,
+}
+
+/// The games listed by the `list-games` command (in a struct as BSON documents
+/// can't be arrays).
+#[cfg_attr(
+    any(feature = "json", feature = "xml", feature = "bson", feature = "csv"),
+    derive(serde::Serialize)
+)]
+#[derive(Debug)]
+struct GameList<'a> {
+    games: Vec<GameListing<'a>>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, ValueEnum)]
 enum OutputMode {
     /// A generalised response that maps common fields from all game types to
@@ -108,6 +320,9 @@ enum OutputFormat {
     /// RFC 8259
     #[cfg(feature = "json")]
     Json,
+    /// Newline delimited JSON, one value (such as a listed game) per line
+    #[cfg(feature = "json")]
+    Ndjson,
     /// Parser tries to be mostly XML 1.1 (RFC 7303) compliant
     #[cfg(feature = "xml")]
     Xml,
@@ -117,24 +332,149 @@ enum OutputFormat {
     /// RFC 4648 section 4
     #[cfg(feature = "bson")]
     BsonBase64,
+    /// RFC 4180, the players being flattened to rows (the server fields being
+    /// repeated on each)
+    #[cfg(feature = "csv")]
+    Csv,
+    /// Human readable table, fitted to the terminal width
+    #[cfg(feature = "table")]
+    Table,
+    /// InfluxDB line protocol, tagged by game, host and map (query responses
+    /// only)
+    Influx,
+    /// OpenMetrics text exposition, labelled by game, host and map (query
+    /// responses only)
+    #[value(name = "openmetrics")]
+    OpenMetrics,
 }
 
-/// Attempt to find a game from the [library game definitions](GAMES) based on
-/// its unique identifier.
+/// Attempt to find a game from a [game registry](GameRegistry) (which starts
+/// from the [library game definitions](GAMES)) based on its unique identifier
+/// or one of its [aliases](ALIASES).
 ///
 /// # Arguments
+/// * `registry` - The game definitions to search in.
 /// * `game_id` - A string slice containing the unique game identifier.
 ///
 /// # Returns
-/// * Result<&'static [Game]> - On sucess returns a reference to the game
-///   definition; on failure returns a [Error::UnknownGame] error.
-fn find_game(game_id: &str) -> Result<&'static Game> {
-    // Attempt to retrieve the game from the predefined game list
-    GAMES
-        .get(game_id)
+/// * Result<&[Game]> - On sucess returns a reference to the game definition; on
+///   failure returns a [Error::UnknownGame] error.
+fn find_game<'a>(registry: &'a GameRegistry, game_id: &str) -> Result<&'a Game> {
+    registry
+        .resolve(game_id)
+        .and_then(|id| registry.get(id))
         .ok_or_else(|| Error::UnknownGame(game_id.to_string()))
 }
 
+/// Print the ids and names of the games found by a search, either by text or
+/// by Steam app id.
+///
+/// # Arguments
+/// * `registry` - The game definitions to search in.
+/// * `query` - The text to search for.
+/// * `app_id` - The Steam app id to search for, used over the query.
+///
+/// # Returns
+/// * `Result<()>` - On success prints the games; on failure (no game found)
+///   returns an [Error::UnknownGame] error.
+fn search_games(registry: &GameRegistry, query: Option<&str>, app_id: Option<u32>) -> Result<()> {
+    let (ids, searched) = match (app_id, query) {
+        (Some(app_id), _) => (registry.find_by_app_id(app_id), format!("app id {app_id}")),
+        (None, query) => {
+            let query = query.unwrap_or_default();
+            (registry.search(query), query.to_string())
+        }
+    };
+
+    if ids.is_empty() {
+        return Err(Error::UnknownGame(searched));
+    }
+
+    for id in ids {
+        println!("{}\t{}", id, registry.get(id).unwrap().name);
+    }
+
+    Ok(())
+}
+
+/// List the games (sorted by id), optionally only the ones of a protocol
+/// family.
+///
+/// # Arguments
+/// * `registry` - The game definitions to list.
+/// * `protocol` - The protocol family to filter by.
+fn list_games(registry: &GameRegistry, protocol: Option<ProtocolFamily>) -> GameList<'_> {
+    GameList {
+        games: registry
+            .iter()
+            .filter(|(_, game)| {
+                protocol.map_or(true, |protocol| {
+                    ProtocolFamily::of(&game.protocol) == protocol
+                })
+            })
+            .map(|(id, game)| {
+                GameListing {
+                    id,
+                    name: &game.name,
+                    default_port: game.default_port,
+                    protocol: &game.protocol,
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Load the game definitions, the library ones with the ones of the games file
+/// (if any) over them.
+///
+/// # Arguments
+/// * `games_file` - The path of the games file.
+///
+/// # Returns
+/// * `Result<GameRegistry>` - On success returns the game definitions; on
+///   failure returns the error of reading the file, or an
+///   [Error::InvalidGameDefinitions] error listing every invalid entry.
+#[cfg(feature = "games_file")]
+fn load_games(games_file: Option<&std::path::Path>) -> Result<GameRegistry> {
+    let mut registry = GameRegistry::new();
+    let Some(path) = games_file else {
+        return Ok(registry);
+    };
+
+    let content = std::fs::read_to_string(path)?;
+    let definitions: serde_json::Value = if path
+        .extension()
+        .map_or(false, |extension| extension.eq_ignore_ascii_case("toml"))
+    {
+        toml::from_str(&content)?
+    } else {
+        serde_json::from_str(&content)?
+    };
+
+    let errors = registry.register_definitions(definitions)?;
+    if !errors.is_empty() {
+        return Err(Error::InvalidGameDefinitions(
+            errors
+                .into_iter()
+                .map(|(id, error)| format!("{id}: {}", gamedig_error_reason(&error)))
+                .collect(),
+        ));
+    }
+
+    Ok(registry)
+}
+
+/// The reason of a library error: its source, or its kind if it has none.
+///
+/// # Arguments
+/// * `error` - The library error.
+fn gamedig_error_reason(error: &gamedig::errors::GDError) -> String {
+    error
+        .source
+        .as_ref()
+        .map_or_else(|| format!("{:?}", error.kind), |source| source.to_string())
+}
+
 /// Resolve an IP address by either parsing an IP address or doing a DNS lookup.
 /// In the case of DNS lookup update extra request options with the hostname.
 ///
@@ -192,55 +532,190 @@ fn set_hostname_if_missing(host: &str, extra_options: &mut Option<ExtraRequestSe
     }
 }
 
+/// A value that can be output in every enabled format.
+#[cfg(any(feature = "json", feature = "xml", feature = "bson", feature = "csv"))]
+trait Output: std::fmt::Debug + serde::Serialize {}
+#[cfg(any(feature = "json", feature = "xml", feature = "bson", feature = "csv"))]
+impl<T: std::fmt::Debug + serde::Serialize> Output for T {}
+
+/// A value that can be output in every enabled format.
+#[cfg(not(any(feature = "json", feature = "xml", feature = "bson", feature = "csv")))]
+trait Output: std::fmt::Debug {}
+#[cfg(not(any(feature = "json", feature = "xml", feature = "bson", feature = "csv")))]
+impl<T: std::fmt::Debug> Output for T {}
+
 /// Output the result of a query to stdout.
 ///
 /// # Arguments
-/// * `args` - A reference to the command line options.
+/// * `output_mode` - Which response variant to output.
+/// * `format` - The output format.
+/// * `series` - The game and host of the server (for the time series formats).
 /// * `result` - A reference to the result of the query.
-fn output_result<T: CommonResponse + ?Sized>(output_mode: OutputMode, format: OutputFormat, result: &T) {
+fn output_result(output_mode: OutputMode, format: OutputFormat, series: Series, result: &dyn CommonResponse) {
+    // These formats only render the common response, whatever the output mode.
+    match format {
+        #[cfg(feature = "table")]
+        OutputFormat::Table => {
+            println!(
+                "{}",
+                table::render_response(result, table::terminal_width())
+            );
+            return;
+        }
+        OutputFormat::Influx => {
+            println!(
+                "{}",
+                to_influx_line(series, result, Some(std::time::SystemTime::now()))
+            );
+            return;
+        }
+        OutputFormat::OpenMetrics => {
+            print!("{}", to_openmetrics(&[(series, result)]));
+            return;
+        }
+        _ => {}
+    }
+
+    let _ = match output_mode {
+        OutputMode::Generic => output_value(&format, result.as_json()),
+        OutputMode::ProtocolSpecific => output_value(&format, result.as_original()),
+    };
+}
+
+/// Output a value to stdout.
+///
+/// # Arguments
+/// * `format` - The output format.
+/// * `value` - The value to output.
+fn output_value<T: Output>(format: &OutputFormat, value: T) -> Result<()> {
     match format {
         OutputFormat::Debug => {
-            match output_mode {
-                OutputMode::Generic => output_result_debug(result.as_json()),
-                OutputMode::ProtocolSpecific => output_result_debug(result.as_original()),
-            };
+            output_result_debug(value);
+            Ok(())
         }
         #[cfg(feature = "json")]
-        OutputFormat::JsonPretty => {
-            let _ = match output_mode {
-                OutputMode::Generic => output_result_json_pretty(result.as_json()),
-                OutputMode::ProtocolSpecific => output_result_json_pretty(result.as_original()),
-            };
-        }
+        OutputFormat::JsonPretty => output_result_json_pretty(value),
         #[cfg(feature = "json")]
-        OutputFormat::Json => {
-            let _ = match output_mode {
-                OutputMode::Generic => output_result_json(result.as_json()),
-                OutputMode::ProtocolSpecific => output_result_json(result.as_original()),
-            };
-        }
+        OutputFormat::Json | OutputFormat::Ndjson => output_result_json(value),
         #[cfg(feature = "xml")]
-        OutputFormat::Xml => {
-            let _ = match output_mode {
-                OutputMode::Generic => output_result_xml(result.as_json()),
-                OutputMode::ProtocolSpecific => output_result_xml(result.as_original()),
-            };
-        }
+        OutputFormat::Xml => output_result_xml(value),
         #[cfg(feature = "bson")]
-        OutputFormat::BsonHex => {
-            let _ = match output_mode {
-                OutputMode::Generic => output_result_bson_hex(result.as_json()),
-                OutputMode::ProtocolSpecific => output_result_bson_hex(result.as_original()),
-            };
-        }
+        OutputFormat::BsonHex => output_result_bson_hex(value),
         #[cfg(feature = "bson")]
-        OutputFormat::BsonBase64 => {
-            let _ = match output_mode {
-                OutputMode::Generic => output_result_bson_base64(result.as_json()),
-                OutputMode::ProtocolSpecific => output_result_bson_base64(result.as_original()),
-            };
+        OutputFormat::BsonBase64 => output_result_bson_base64(value),
+        #[cfg(feature = "csv")]
+        OutputFormat::Csv => output_result_csv(value),
+        // Only responses can be output in these formats (see check_format).
+        #[cfg(feature = "table")]
+        OutputFormat::Table => Err(Error::UnsupportedFormat(format.clone())),
+        OutputFormat::Influx | OutputFormat::OpenMetrics => Err(Error::UnsupportedFormat(format.clone())),
+    }
+}
+
+/// Exit with a usage error if a command can't output its values in a format.
+///
+/// # Arguments
+/// * `command` - The name of the command.
+/// * `format` - The output format.
+/// * `unsupported` - The formats that the command doesn't support.
+fn check_format(command: &str, format: &OutputFormat, unsupported: &[OutputFormat]) {
+    if unsupported.contains(format) {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "the {} format can't be used with {command}",
+                    format_name(format)
+                ),
+            )
+            .exit();
+    }
+}
+
+/// The name of a format, as given on the command line.
+fn format_name(format: &OutputFormat) -> String {
+    format.to_possible_value().map_or_else(
+        || format!("{format:?}"),
+        |value| value.get_name().to_string(),
+    )
+}
+
+/// Output the listed games to stdout, one per line (id, name, default port and
+/// protocol) when no format is given.
+///
+/// # Arguments
+/// * `format` - The output format.
+/// * `list` - The listed games.
+fn output_games(format: Option<OutputFormat>, list: &GameList) -> Result<()> {
+    let Some(format) = format else {
+        for game in &list.games {
+            println!(
+                "{}\t{}\t{}\t{:?}",
+                game.id, game.name, game.default_port, game.protocol
+            );
         }
+
+        return Ok(());
+    };
+
+    #[cfg(feature = "table")]
+    if format == OutputFormat::Table {
+        let rows: Vec<Vec<String>> = list
+            .games
+            .iter()
+            .map(|game| {
+                vec![
+                    game.id.to_string(),
+                    game.name.to_string(),
+                    game.default_port.to_string(),
+                    format!("{:?}", game.protocol),
+                ]
+            })
+            .collect();
+
+        println!(
+            "{}",
+            table::render(
+                &[
+                    table::Column::left("Id"),
+                    table::Column::left("Name").flexible(),
+                    table::Column::right("Port"),
+                    table::Column::left("Protocol"),
+                ],
+                &rows,
+                table::terminal_width()
+            )
+        );
+        return Ok(());
     }
+
+    #[cfg(feature = "json")]
+    if format == OutputFormat::Ndjson {
+        for game in &list.games {
+            output_result_json(game)?;
+        }
+        return Ok(());
+    }
+
+    #[cfg(feature = "csv")]
+    if format == OutputFormat::Csv {
+        let rows: Vec<csv::Fields> = list
+            .games
+            .iter()
+            .map(|game| {
+                vec![
+                    ("id".to_string(), game.id.to_string()),
+                    ("name".to_string(), game.name.to_string()),
+                    ("default_port".to_string(), game.default_port.to_string()),
+                    ("protocol".to_string(), format!("{:?}", game.protocol)),
+                ]
+            })
+            .collect();
+        println!("{}", csv::render_rows(&rows));
+        return Ok(());
+    }
+
+    output_value(&format, list)
 }
 
 /// Output the result using debug formatting.
@@ -262,6 +737,17 @@ fn output_result_json<T: serde::Serialize>(result: T) -> Result<()> {
     Ok(())
 }
 
+/// Output the result as CSV.
+///
+/// # Arguments
+/// * `result` - A serde serializable result.
+#[cfg(feature = "csv")]
+fn output_result_csv<T: serde::Serialize>(result: T) -> Result<()> {
+    println!("{}", csv::render(&result)?);
+
+    Ok(())
+}
+
 /// Output the result as a pretty printed JSON object.
 ///
 /// # Arguments
@@ -421,6 +907,10 @@ fn output_result_bson_base64<T: serde::Serialize>(result: T) -> Result<()> {
 
 fn main() -> Result<()> {
     let args = Cli::parse();
+    #[cfg(feature = "games_file")]
+    let registry = load_games(args.games_file.as_deref())?;
+    #[cfg(not(feature = "games_file"))]
+    let registry = GameRegistry::new();
 
     match args.action {
         Action::Query {
@@ -431,20 +921,98 @@ fn main() -> Result<()> {
             output_mode,
             #[cfg(feature = "packet_capture")]
             capture,
+            watch,
             timeout_settings,
             extra_options,
         } => {
             // Process the query command
-            let game = find_game(&game)?;
+            let game_id = registry.resolve(&game).unwrap_or(&game);
+            let game = find_game(&registry, &game)?;
+            let address = target::format_address(&ip, Some(port.unwrap_or(game.default_port)));
+            let series = Series::new(game_id, &address);
             let mut extra_options = extra_options;
             let ip = resolve_ip_or_domain(&ip, &mut extra_options)?;
 
             #[cfg(feature = "packet_capture")]
             gamedig::capture::setup_capture(capture);
 
-            let result = query_with_timeout_and_extra_settings(game, &ip, port, timeout_settings, extra_options)?;
-            output_result(output_mode, format, result.as_ref());
+            if let Some(interval) = watch {
+                watch::run(
+                    std::time::Duration::from_secs(interval),
+                    output_mode,
+                    format,
+                    series,
+                    || query_with_timeout_and_extra_settings(game, &ip, port, timeout_settings, extra_options.clone()),
+                )?;
+            } else {
+                let result = query_with_timeout_and_extra_settings(game, &ip, port, timeout_settings, extra_options)?;
+                output_result(output_mode, format, series, result.as_ref());
+            }
+        }
+        #[cfg(feature = "json")]
+        Action::Batch {
+            input,
+            format,
+            output_mode,
+            concurrency,
+            deadline,
+            timeout_settings,
+            extra_options,
+        } => {
+            check_format("batch", &format, batch::UNSUPPORTED_FORMATS);
+            batch::run(
+                &registry,
+                input.as_deref(),
+                output_mode,
+                format,
+                concurrency,
+                std::time::Duration::from_secs(deadline),
+                timeout_settings,
+                extra_options,
+            )?
+        }
+        Action::Exporter {
+            targets,
+            listen,
+            timeout_settings,
+            extra_options,
+        } => exporter::run(&registry, &targets, listen, timeout_settings, extra_options)?,
+        #[cfg(feature = "json")]
+        Action::Gateway {
+            listen,
+            cache_ttl,
+            failure_cache_ttl,
+            allow_origin,
+            output_mode,
+            timeout_settings,
+            extra_options,
+        } => {
+            gateway::run(
+                registry,
+                listen,
+                std::time::Duration::from_secs(cache_ttl),
+                std::time::Duration::from_secs(failure_cache_ttl),
+                allow_origin,
+                gateway::QuerySettings {
+                    timeout_settings,
+                    extra_options,
+                    output_mode,
+                },
+            )?
+        }
+        Action::ListGames { protocol, format } => {
+            if let Some(format) = &format {
+                check_format(
+                    "list-games",
+                    format,
+                    &[OutputFormat::Influx, OutputFormat::OpenMetrics],
+                );
+            }
+            output_games(format, &list_games(&registry, protocol))?
         }
+        Action::Games {
+            action: GamesAction::Search { query, app_id },
+        } => search_games(&registry, query.as_deref(), app_id)?,
         Action::Source => {
             println!("{}", GAMEDIG_HEADER);
 
@@ -0,0 +1,324 @@
+//! The `table` output format: human readable, aligned and fitted to the
+//! terminal width.
+
+use std::time::Duration;
+
+use gamedig::protocols::types::{CommonPlayer, CommonResponse};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Space between two columns.
+const GAP: &str = "  ";
+
+/// A column of a table.
+pub struct Column {
+    pub header: &'static str,
+    /// Right aligned (numbers) or left aligned.
+    pub right: bool,
+    /// Whether the column is shrunk (its values being truncated) when the table
+    /// is wider than the maximum width.
+    pub flexible: bool,
+}
+
+impl Column {
+    pub const fn left(header: &'static str) -> Self {
+        Self {
+            header,
+            right: false,
+            flexible: false,
+        }
+    }
+
+    pub const fn right(header: &'static str) -> Self {
+        Self {
+            header,
+            right: true,
+            flexible: false,
+        }
+    }
+
+    pub const fn flexible(self) -> Self {
+        Self {
+            flexible: true,
+            ..self
+        }
+    }
+}
+
+/// The width of the terminal, None if the output isn't one.
+pub fn terminal_width() -> Option<usize> {
+    terminal_size::terminal_size().map(|(terminal_size::Width(width), _)| usize::from(width))
+}
+
+/// Remove the control characters (that would break the layout) of a value.
+fn sanitize(value: &str) -> String { value.chars().filter(|c| !c.is_control()).collect() }
+
+/// Truncate a value to a display width, ending it with an ellipsis if it was
+/// truncated.
+fn truncate(value: &str, width: usize) -> String {
+    if value.width() <= width {
+        return value.to_string();
+    }
+
+    let mut truncated = String::new();
+    let mut truncated_width = 0;
+    for c in value.chars() {
+        let c_width = c.width().unwrap_or(0);
+        if truncated_width + c_width + 1 > width {
+            break;
+        }
+        truncated.push(c);
+        truncated_width += c_width;
+    }
+
+    if width > 0 {
+        truncated.push('…');
+    }
+    truncated
+}
+
+/// Pad a value to a display width.
+fn pad(value: &str, width: usize, right: bool) -> String {
+    let padding = " ".repeat(width.saturating_sub(value.width()));
+    if right {
+        padding + value
+    } else {
+        value.to_string() + &padding
+    }
+}
+
+/// Render rows under the column headers, the flexible columns being shrunk if
+/// the table is wider than `max_width`.
+pub fn render(columns: &[Column], rows: &[Vec<String>], max_width: Option<usize>) -> String {
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|value| sanitize(value)).collect())
+        .collect();
+
+    let mut widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            rows.iter()
+                .filter_map(|row| row.get(index))
+                .map(|value| value.width())
+                .chain(std::iter::once(column.header.width()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    if let Some(max_width) = max_width {
+        let total = widths.iter().sum::<usize>() + GAP.len() * columns.len().saturating_sub(1);
+        let mut excess = total.saturating_sub(max_width);
+
+        for (index, column) in columns.iter().enumerate() {
+            if !column.flexible || excess == 0 {
+                continue;
+            }
+
+            // Keep at least the header.
+            let shrink = excess.min(widths[index].saturating_sub(column.header.width()));
+            widths[index] -= shrink;
+            excess -= shrink;
+        }
+    }
+
+    let render_row = |values: Vec<&str>| {
+        let cells: Vec<String> = columns
+            .iter()
+            .zip(&widths)
+            .zip(values)
+            .map(|((column, width), value)| pad(&truncate(value, *width), *width, column.right))
+            .collect();
+
+        cells.join(GAP).trim_end().to_string()
+    };
+
+    let mut lines = vec![
+        render_row(columns.iter().map(|column| column.header).collect()),
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join(GAP),
+    ];
+    lines.extend(
+        rows.iter()
+            .map(|row| render_row(row.iter().map(String::as_str).collect())),
+    );
+
+    lines.join("\n")
+}
+
+/// Format a duration as `h:mm:ss`.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Render a response: a header block (name, map, players, version and
+/// password) followed by the players table (with the columns that the players
+/// have values for).
+pub fn render_response<T: CommonResponse + ?Sized>(response: &T, max_width: Option<usize>) -> String {
+    let or_unknown = |value: Option<&str>| sanitize(value.unwrap_or("-"));
+
+    let mut players_count = format!(
+        "{}/{}",
+        response.players_online(),
+        response.players_maximum()
+    );
+    if let Some(bots) = response.players_bots().filter(|bots| *bots > 0) {
+        players_count += &format!(" ({bots} bots)");
+    }
+
+    let password = match response.has_password() {
+        Some(true) => "yes",
+        Some(false) => "no",
+        None => "-",
+    };
+
+    let header = [
+        ("Name", or_unknown(response.name())),
+        ("Map", or_unknown(response.map())),
+        ("Players", players_count),
+        ("Version", or_unknown(response.game_version())),
+        ("Password", password.to_string()),
+    ];
+
+    let mut lines: Vec<String> = header
+        .iter()
+        .map(|(label, value)| {
+            let label = format!("{label}:");
+            let line = format!("{} {}", pad(&label, 9, false), value);
+            match max_width {
+                Some(max_width) => truncate(&line, max_width),
+                None => line,
+            }
+        })
+        .collect();
+
+    let players = response.players().unwrap_or_default();
+    if !players.is_empty() {
+        lines.push(String::new());
+        lines.push(render_players(&players, max_width));
+    }
+
+    lines.join("\n")
+}
+
+/// An optional column of the players table.
+type PlayerColumn = (Column, fn(&dyn CommonPlayer) -> Option<String>);
+
+/// Render the players table.
+fn render_players(players: &[&dyn CommonPlayer], max_width: Option<usize>) -> String {
+    let optional_columns: [PlayerColumn; 6] = [
+        (Column::right("Score"), |player| {
+            player.score().map(|score| score.to_string())
+        }),
+        (Column::right("Deaths"), |player| {
+            player.deaths().map(|deaths| deaths.to_string())
+        }),
+        (Column::right("Team"), |player| {
+            player.team().map(|team| team.to_string())
+        }),
+        (Column::right("Ping"), |player| {
+            player.ping().map(|ping| format!("{ping} ms"))
+        }),
+        (Column::right("Time"), |player| {
+            player.time_connected().map(format_duration)
+        }),
+        (Column::left("Bot"), |player| {
+            player
+                .is_bot()
+                .map(|is_bot| if is_bot { "yes" } else { "no" }.to_string())
+        }),
+    ];
+
+    // Only the columns that at least one player has a value for.
+    let optional_columns: Vec<PlayerColumn> = optional_columns
+        .into_iter()
+        .filter(|(_, value)| players.iter().any(|player| value(*player).is_some()))
+        .collect();
+
+    let rows: Vec<Vec<String>> = players
+        .iter()
+        .map(|player| {
+            std::iter::once(player.name().to_string())
+                .chain(
+                    optional_columns
+                        .iter()
+                        .map(|(_, value)| value(*player).unwrap_or_else(|| "-".to_string())),
+                )
+                .collect()
+        })
+        .collect();
+
+    let columns: Vec<Column> = std::iter::once(Column::left("Player").flexible())
+        .chain(optional_columns.into_iter().map(|(column, _)| column))
+        .collect();
+
+    render(&columns, &rows, max_width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned() {
+        let rows = vec![
+            vec!["Alice".to_string(), "12".to_string()],
+            vec!["Bob".to_string(), "7".to_string()],
+        ];
+        assert_eq!(
+            render(
+                &[Column::left("Player"), Column::right("Score")],
+                &rows,
+                None
+            ),
+            "Player  Score\n------  -----\nAlice      12\nBob         7"
+        );
+    }
+
+    #[test]
+    fn unicode() {
+        // Wide characters take two columns.
+        let rows = vec![vec!["プレイヤー".to_string(), "1".to_string()]];
+        assert_eq!(
+            render(
+                &[Column::left("Player"), Column::right("Score")],
+                &rows,
+                None
+            ),
+            "Player      Score\n----------  -----\nプレイヤー      1"
+        );
+    }
+
+    #[test]
+    fn fitted() {
+        let rows = vec![vec![
+            "A very long player name\u{7}".to_string(),
+            "1".to_string(),
+        ]];
+        let table = render(
+            &[Column::left("Player").flexible(), Column::right("Score")],
+            &rows,
+            Some(20),
+        );
+        assert_eq!(
+            table,
+            "Player         Score\n-------------  -----\nA very long …      1"
+        );
+        assert!(table.lines().all(|line| line.width() <= 20));
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(Duration::from_secs(3725)), "1:02:05");
+    }
+}
@@ -0,0 +1,121 @@
+//! Servers to query, as given by the user.
+
+use crate::error::{Error, Result};
+
+/// A server to query, as read from the input.
+#[cfg_attr(feature = "json", derive(serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    /// Game id (or alias).
+    pub game: String,
+    /// Hostname or IP address of the server.
+    pub ip: String,
+    /// Query port, the default one of the game if not set.
+    pub port: Option<u16>,
+}
+
+impl Target {
+    /// Parse a `game host[:port]` line, IPv6 addresses with a port being
+    /// enclosed in brackets (`[::1]:27015`).
+    pub fn parse_line(line: &str) -> Result<Self> {
+        let invalid = || Error::InvalidTarget(line.to_string());
+
+        let mut parts = line.split_whitespace();
+        let (Some(game), Some(address), None) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+
+        let (ip, port) = if let Some(bracketed) = address.strip_prefix('[') {
+            let (ip, rest) = bracketed.split_once(']').ok_or_else(invalid)?;
+            match rest {
+                "" => (ip, None),
+                _ => (ip, Some(rest.strip_prefix(':').ok_or_else(invalid)?)),
+            }
+        } else {
+            match address.split_once(':') {
+                // A bare IPv6 address.
+                Some((_, port)) if port.contains(':') => (address, None),
+                Some((ip, port)) => (ip, Some(port)),
+                None => (address, None),
+            }
+        };
+
+        Ok(Self {
+            game: game.to_string(),
+            ip: ip.to_string(),
+            port: port
+                .map(|port| port.parse().map_err(|_| invalid()))
+                .transpose()?,
+        })
+    }
+}
+
+/// Format an address as `host[:port]`, IPv6 addresses with a port being
+/// enclosed in brackets.
+pub fn format_address(host: &str, port: Option<u16>) -> String {
+    match (port, host.contains(':')) {
+        (Some(port), true) => format!("[{host}]:{port}"),
+        (Some(port), false) => format!("{host}:{port}"),
+        (None, _) => host.to_string(),
+    }
+}
+
+/// Read one `game host[:port]` target per line, empty lines and the ones
+/// starting with `#` being skipped, the lines that can't be parsed being
+/// returned as errors (along with their first word, the game).
+pub fn parse_lines(input: &str) -> Vec<(String, Result<Target>)> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let game = line.split_whitespace().next().unwrap_or_default();
+            (game.to_string(), Target::parse_line(line))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(game: &str, ip: &str, port: Option<u16>) -> Target {
+        Target {
+            game: game.to_string(),
+            ip: ip.to_string(),
+            port,
+        }
+    }
+
+    #[test]
+    fn parse_lines() {
+        assert_eq!(
+            Target::parse_line("teamfortress2 127.0.0.1:27016").unwrap(),
+            target("teamfortress2", "127.0.0.1", Some(27016))
+        );
+        assert_eq!(
+            Target::parse_line("minecraft  example.com").unwrap(),
+            target("minecraft", "example.com", None)
+        );
+        assert_eq!(
+            Target::parse_line("tf2 [::1]:27015").unwrap(),
+            target("tf2", "::1", Some(27015))
+        );
+        assert_eq!(
+            Target::parse_line("tf2 ::1").unwrap(),
+            target("tf2", "::1", None)
+        );
+
+        for line in [
+            "teamfortress2",
+            "teamfortress2 127.0.0.1 27015",
+            "teamfortress2 127.0.0.1:port",
+            "teamfortress2 [::1",
+        ] {
+            assert!(matches!(
+                Target::parse_line(line),
+                Err(Error::InvalidTarget(_))
+            ));
+        }
+    }
+}
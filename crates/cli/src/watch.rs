@@ -0,0 +1,165 @@
+//! The `query --watch` mode: query a server on a timer, showing what changed.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use gamedig::games::{ServerChange, ServerTracker};
+use gamedig::metrics::Series;
+use gamedig::protocols::types::CommonResponse;
+use is_terminal::IsTerminal;
+
+use crate::error::Result;
+use crate::{output_result, OutputFormat, OutputMode};
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+/// The terminal color of a change.
+fn color(change: &ServerChange) -> &'static str {
+    match change {
+        ServerChange::PlayerJoined { .. } | ServerChange::CameBack => GREEN,
+        ServerChange::PlayerLeft { .. } | ServerChange::WentOffline { .. } => RED,
+        ServerChange::PlayersOnlineChanged { from, to } if to > from => GREEN,
+        ServerChange::PlayersOnlineChanged { .. } => RED,
+        ServerChange::MapChanged { .. }
+        | ServerChange::VersionChanged { .. }
+        | ServerChange::PasswordChanged { .. } => YELLOW,
+    }
+}
+
+/// Output a change as an event line, in JSON if that is the output format.
+fn output_event(format: &OutputFormat, change: &ServerChange) {
+    #[cfg(feature = "json")]
+    if matches!(
+        format,
+        OutputFormat::Json | OutputFormat::JsonPretty | OutputFormat::Ndjson
+    ) {
+        if let Ok(line) = serde_json::to_string(change) {
+            println!("{line}");
+        }
+        return;
+    }
+
+    let _ = format;
+    println!("{change}");
+}
+
+/// Query the server every `interval` until interrupted.
+///
+/// On a terminal the output is redrawn after every poll along with the
+/// (highlighted) changes since the previous one, otherwise the response is
+/// only output once and then every change is output as an event line (except
+/// with the InfluxDB line protocol, every response then being output as a
+/// point). Failed queries are reported without stopping, the server going
+/// offline and coming back being changes too.
+pub fn run(
+    interval: Duration,
+    output_mode: OutputMode,
+    format: OutputFormat,
+    series: Series,
+    query: impl Fn() -> gamedig::GDResult<Box<dyn CommonResponse>>,
+) -> Result<()> {
+    let is_terminal = std::io::stdout().is_terminal();
+    let mut tracker = ServerTracker::new();
+    let mut response_shown = false;
+
+    loop {
+        let started = Instant::now();
+
+        match query() {
+            Ok(response) => {
+                let changes = tracker.record(Ok(response.as_ref()));
+
+                if is_terminal {
+                    // Clear the screen and move the cursor to its top.
+                    print!("\x1b[2J\x1b[H");
+                    println!("Every {}s (Ctrl+C to stop)\n", interval.as_secs_f32());
+                    output_result(
+                        output_mode.clone(),
+                        format.clone(),
+                        series,
+                        response.as_ref(),
+                    );
+
+                    if !changes.is_empty() {
+                        println!("\nChanges since the last poll:");
+                        for change in &changes {
+                            println!("{}{change}{RESET}", color(change));
+                        }
+                    }
+                } else if format == OutputFormat::Influx {
+                    output_result(
+                        output_mode.clone(),
+                        format.clone(),
+                        series,
+                        response.as_ref(),
+                    );
+                } else {
+                    if !response_shown {
+                        output_result(
+                            output_mode.clone(),
+                            format.clone(),
+                            series,
+                            response.as_ref(),
+                        );
+                    }
+
+                    for change in &changes {
+                        output_event(&format, change);
+                    }
+                }
+
+                response_shown = true;
+            }
+            Err(error) => {
+                let changes = tracker.record(Err(&error));
+
+                if is_terminal {
+                    for change in &changes {
+                        println!("\n{}{change}{RESET}", color(change));
+                    }
+                } else if format != OutputFormat::Influx {
+                    for change in &changes {
+                        output_event(&format, change);
+                    }
+                }
+
+                eprintln!("Query failed: {}", crate::gamedig_error_reason(&error));
+            }
+        }
+
+        thread::sleep(interval.saturating_sub(started.elapsed()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors() {
+        assert_eq!(
+            color(&ServerChange::PlayersOnlineChanged { from: 1, to: 2 }),
+            GREEN
+        );
+        assert_eq!(
+            color(&ServerChange::PlayersOnlineChanged { from: 2, to: 1 }),
+            RED
+        );
+        assert_eq!(color(&ServerChange::CameBack), GREEN);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_events() {
+        assert_eq!(
+            serde_json::to_string(&ServerChange::PlayerJoined {
+                name: "Alice".to_string()
+            })
+            .unwrap(),
+            r#"{"event":"player_joined","name":"Alice"}"#
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Compare node-gamedig's games (lib/games.js as JSON, from the given file or
+//! stdin) to ours, `--candidates` also prints definitions for the games we are
+//! missing.
+
+use gamedig::GAMES;
+use gamedig_id_tests::diff::{candidate_definition, diff_games, node_protocol_name, NodeGames};
+
+fn main() {
+    let mut candidates = false;
+    let mut file = None;
+    for arg in std::env::args_os().skip(1) {
+        if arg == "--candidates" {
+            candidates = true;
+        } else {
+            file = Some(arg);
+        }
+    }
+
+    let node: NodeGames = file.map_or_else(
+        || serde_json::from_reader(std::io::stdin().lock()).unwrap(),
+        |file| {
+            let file = std::fs::OpenOptions::new().read(true).open(file).unwrap();
+
+            serde_json::from_reader(file).unwrap()
+        },
+    );
+
+    let diff = diff_games(&node, GAMES.entries().map(|(id, game)| (*id, game)));
+
+    println!("Missing here ({}):", diff.missing_in_rust.len());
+    for id in &diff.missing_in_rust {
+        println!("\t{}\t{}", id, node[id].name);
+    }
+
+    println!("Missing in node ({}):", diff.missing_in_node.len());
+    for id in &diff.missing_in_node {
+        println!("\t{}\t{}", id, GAMES[id.as_str()].name);
+    }
+
+    println!("Different ports ({}):", diff.ports.len());
+    for mismatch in &diff.ports {
+        println!(
+            "\t{}\there: {}\tnode: {:?} (query: {:?})",
+            mismatch.id, mismatch.rust, mismatch.node_port, mismatch.node_query_port
+        );
+    }
+
+    println!("Different protocols ({}):", diff.protocols.len());
+    for mismatch in &diff.protocols {
+        println!(
+            "\t{}\there: {} ({:?})\tnode: {}",
+            mismatch.id,
+            node_protocol_name(&mismatch.rust),
+            mismatch.rust,
+            mismatch.node
+        );
+    }
+
+    if candidates {
+        println!("Candidate definitions:");
+        for id in &diff.missing_in_rust {
+            println!("{}", candidate_definition(id, &node[id]));
+        }
+    }
+}
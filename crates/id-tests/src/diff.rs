@@ -0,0 +1,333 @@
+//! Compare node-gamedig's games list (lib/games.js as JSON) to our [GAMES].
+//!
+//! [GAMES]: gamedig::GAMES
+
+use std::collections::{HashMap, HashSet};
+
+use gamedig::protocols::gamespy::GameSpyVersion;
+use gamedig::protocols::quake::QuakeVersion;
+use gamedig::protocols::types::ProprietaryProtocol;
+use gamedig::protocols::Protocol;
+use gamedig::Game;
+
+/// A game from node-gamedig's list, only the fields that are compared are kept.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct NodeGame {
+    pub name: String,
+    #[serde(default)]
+    pub options: NodeOptions,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize)]
+pub struct NodeOptions {
+    /// The game port.
+    pub port: Option<u16>,
+    /// The query port, when it is a fixed one.
+    pub port_query: Option<u16>,
+    /// The query port offset from the game port.
+    pub port_query_offset: Option<i32>,
+    pub protocol: Option<String>,
+}
+
+impl NodeGame {
+    /// The port node queries by default, either the query one or the game one.
+    pub fn query_port(&self) -> Option<u16> {
+        if self.options.port_query.is_some() {
+            return self.options.port_query;
+        }
+
+        let port = self.options.port?;
+        match self.options.port_query_offset {
+            Some(offset) => u16::try_from(i32::from(port) + offset).ok(),
+            None => Some(port),
+        }
+    }
+
+    fn protocol(&self) -> &str { self.options.protocol.as_deref().unwrap_or_default() }
+}
+
+/// Format for input games (the same as used in node-gamedig/lib/games.js).
+pub type NodeGames = HashMap<String, NodeGame>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortMismatch {
+    pub id: String,
+    pub rust: u16,
+    pub node_port: Option<u16>,
+    pub node_query_port: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolMismatch {
+    pub id: String,
+    pub rust: Protocol,
+    pub node: String,
+}
+
+/// The differences between the two lists, every list being sorted by id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GamesDiff {
+    /// Games node has but we don't.
+    pub missing_in_rust: Vec<String>,
+    /// Games we have but node doesn't.
+    pub missing_in_node: Vec<String>,
+    pub ports: Vec<PortMismatch>,
+    pub protocols: Vec<ProtocolMismatch>,
+}
+
+impl GamesDiff {
+    pub fn is_empty(&self) -> bool {
+        self.missing_in_rust.is_empty()
+            && self.missing_in_node.is_empty()
+            && self.ports.is_empty()
+            && self.protocols.is_empty()
+    }
+}
+
+/// The name node uses for one of our protocols (The Ship being queried as a
+/// Valve game there).
+pub fn node_protocol_name(protocol: &Protocol) -> &'static str {
+    match protocol {
+        Protocol::Gamespy(GameSpyVersion::One) => "gamespy1",
+        Protocol::Gamespy(GameSpyVersion::Two) => "gamespy2",
+        Protocol::Gamespy(GameSpyVersion::Three) => "gamespy3",
+        Protocol::Quake(QuakeVersion::One) => "quake1",
+        Protocol::Quake(QuakeVersion::Two) => "quake2",
+        Protocol::Quake(QuakeVersion::Three) => "quake3",
+        Protocol::Valve(_) | Protocol::PROPRIETARY(ProprietaryProtocol::TheShip) => "valve",
+        Protocol::Unreal2 => "unreal2",
+        Protocol::PROPRIETARY(ProprietaryProtocol::Minecraft(_)) => "minecraft",
+        Protocol::PROPRIETARY(ProprietaryProtocol::FFOW) => "ffow",
+        Protocol::PROPRIETARY(ProprietaryProtocol::JC2M) => "jc2mp",
+        Protocol::PROPRIETARY(ProprietaryProtocol::Savage2) => "savage2",
+        Protocol::PROPRIETARY(ProprietaryProtocol::Eco) => "eco",
+        Protocol::PROPRIETARY(ProprietaryProtocol::Mindustry) => "mindustry",
+        // Epic, which only exists with gamedig's tls feature.
+        #[allow(unreachable_patterns)]
+        _ => "epic",
+    }
+}
+
+/// Compare node's games to ours.
+///
+/// A port is only reported when ours is neither node's game port nor its query
+/// one, as our definitions use either depending on the game.
+pub fn diff_games<'a>(node: &NodeGames, rust: impl IntoIterator<Item = (&'a str, &'a Game)>) -> GamesDiff {
+    let mut diff = GamesDiff::default();
+    let mut seen = HashSet::new();
+
+    for (id, game) in rust {
+        let Some(node_game) = node.get(id) else {
+            diff.missing_in_node.push(id.to_owned());
+            continue;
+        };
+        seen.insert(id);
+
+        let node_query_port = node_game.query_port();
+        if node_game.options.port != Some(game.default_port) && node_query_port != Some(game.default_port) {
+            diff.ports.push(PortMismatch {
+                id: id.to_owned(),
+                rust: game.default_port,
+                node_port: node_game.options.port,
+                node_query_port,
+            });
+        }
+
+        if node_protocol_name(&game.protocol) != node_game.protocol() {
+            diff.protocols.push(ProtocolMismatch {
+                id: id.to_owned(),
+                rust: game.protocol.clone(),
+                node: node_game.protocol().to_owned(),
+            });
+        }
+    }
+
+    diff.missing_in_rust = node
+        .keys()
+        .filter(|id| !seen.contains(id.as_str()))
+        .cloned()
+        .collect();
+    diff.missing_in_rust.sort_unstable();
+    diff.missing_in_node.sort_unstable();
+    diff.ports.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+    diff.protocols.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+
+    diff
+}
+
+/// The `Protocol` expression of a definition for one of node's protocols, None
+/// if we don't support it.
+fn protocol_expression(node_protocol: &str) -> Option<&'static str> {
+    Some(match node_protocol {
+        "gamespy1" => "Protocol::Gamespy(GameSpyVersion::One)",
+        "gamespy2" => "Protocol::Gamespy(GameSpyVersion::Two)",
+        "gamespy3" => "Protocol::Gamespy(GameSpyVersion::Three)",
+        "quake1" => "Protocol::Quake(QuakeVersion::One)",
+        "quake2" => "Protocol::Quake(QuakeVersion::Two)",
+        "quake3" => "Protocol::Quake(QuakeVersion::Three)",
+        // Node doesn't know the app ids.
+        "valve" => "Protocol::Valve(Engine::Source(None))",
+        "unreal2" => "Protocol::Unreal2",
+        "minecraft" => "Protocol::PROPRIETARY(ProprietaryProtocol::Minecraft(None))",
+        "ffow" => "Protocol::PROPRIETARY(ProprietaryProtocol::FFOW)",
+        "jc2mp" => "Protocol::PROPRIETARY(ProprietaryProtocol::JC2M)",
+        "savage2" => "Protocol::PROPRIETARY(ProprietaryProtocol::Savage2)",
+        "eco" => "Protocol::PROPRIETARY(ProprietaryProtocol::Eco)",
+        "mindustry" => "Protocol::PROPRIETARY(ProprietaryProtocol::Mindustry)",
+        _ => return None,
+    })
+}
+
+/// A candidate definition line (as in games/definitions.rs) for one of node's
+/// games, using its query port, or a comment saying why there can't be one.
+pub fn candidate_definition(id: &str, game: &NodeGame) -> String {
+    let (Some(protocol), Some(port)) = (protocol_expression(game.protocol()), game.query_port()) else {
+        return format!(
+            "// {:?} ({:?}): unsupported protocol {:?} or no port",
+            id,
+            game.name,
+            game.protocol()
+        );
+    };
+
+    format!(
+        "{:?} => game!({:?}, {}, {}),",
+        id, game.name, port, protocol
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gamedig::protocols::types::ExtraRequestSettings;
+    use gamedig::protocols::valve::Engine;
+
+    fn node_game(name: &str, port: u16, port_query: Option<u16>, protocol: &str) -> NodeGame {
+        NodeGame {
+            name: name.to_owned(),
+            options: NodeOptions {
+                port: Some(port),
+                port_query,
+                port_query_offset: None,
+                protocol: Some(protocol.to_owned()),
+            },
+        }
+    }
+
+    fn rust_game(name: &'static str, port: u16, protocol: Protocol) -> Game {
+        Game {
+            name: name.into(),
+            default_port: port,
+            protocol,
+            request_settings: ExtraRequestSettings::default(),
+        }
+    }
+
+    #[test]
+    fn query_port() {
+        let mut game = node_game("Test", 7777, None, "valve");
+        assert_eq!(game.query_port(), Some(7777));
+
+        game.options.port_query_offset = Some(-1);
+        assert_eq!(game.query_port(), Some(7776));
+
+        game.options.port_query = Some(27015);
+        assert_eq!(game.query_port(), Some(27015));
+    }
+
+    #[test]
+    fn parse_node_games() {
+        let games: NodeGames = serde_json::from_str(
+            r#"{
+                "tf2": {
+                    "name": "Team Fortress 2",
+                    "release_year": 2007,
+                    "options": { "port": 27015, "protocol": "valve" },
+                    "extra": { "doc_notes": "" }
+                },
+                "bf1942": { "name": "Battlefield 1942", "options": { "port": 14567, "port_query": 23000, "protocol": "gamespy1" } }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            games["tf2"],
+            node_game("Team Fortress 2", 27015, None, "valve")
+        );
+        assert_eq!(games["bf1942"].query_port(), Some(23000));
+    }
+
+    #[test]
+    fn diff() {
+        let node: NodeGames = [
+            ("same", node_game("Same", 27015, None, "valve")),
+            (
+                "queryport",
+                node_game("Query Port", 14567, Some(23000), "gamespy1"),
+            ),
+            ("port", node_game("Port", 7777, None, "unreal2")),
+            ("protocol", node_game("Protocol", 27960, None, "quake2")),
+            ("nodeonly", node_game("Node Only", 27015, None, "valve")),
+        ]
+        .into_iter()
+        .map(|(id, game)| (id.to_owned(), game))
+        .collect();
+
+        let rust = [
+            (
+                "same",
+                rust_game("Same", 27015, Protocol::Valve(Engine::new(1))),
+            ),
+            (
+                "queryport",
+                rust_game("Query Port", 23000, Protocol::Gamespy(GameSpyVersion::One)),
+            ),
+            ("port", rust_game("Port", 7778, Protocol::Unreal2)),
+            (
+                "protocol",
+                rust_game("Protocol", 27960, Protocol::Quake(QuakeVersion::Three)),
+            ),
+            ("rustonly", rust_game("Rust Only", 27015, Protocol::Unreal2)),
+        ];
+
+        let diff = diff_games(&node, rust.iter().map(|(id, game)| (*id, game)));
+        assert_eq!(diff.missing_in_rust, ["nodeonly"]);
+        assert_eq!(diff.missing_in_node, ["rustonly"]);
+        assert_eq!(
+            diff.ports,
+            [PortMismatch {
+                id: "port".to_owned(),
+                rust: 7778,
+                node_port: Some(7777),
+                node_query_port: Some(7777),
+            }]
+        );
+        assert_eq!(
+            diff.protocols,
+            [ProtocolMismatch {
+                id: "protocol".to_owned(),
+                rust: Protocol::Quake(QuakeVersion::Three),
+                node: "quake2".to_owned(),
+            }]
+        );
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn candidates() {
+        assert_eq!(
+            candidate_definition(
+                "bf1942",
+                &node_game("Battlefield 1942", 14567, Some(23000), "gamespy1")
+            ),
+            r#""bf1942" => game!("Battlefield 1942", 23000, Protocol::Gamespy(GameSpyVersion::One)),"#
+        );
+        assert_eq!(
+            candidate_definition(
+                "samp",
+                &node_game("San Andreas Multiplayer", 7777, None, "samp")
+            ),
+            r#"// "samp" ("San Andreas Multiplayer"): unsupported protocol "samp" or no port"#
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+#[cfg(feature = "diff")]
+pub mod diff;
 mod utils;
 use utils::{extract_bracketed_suffix, split_on_switch_between_alpha_numeric};
 
@@ -244,7 +246,7 @@ pub struct GameNameParsed<'a> {
     year: Option<u16>,
 }
 
-pub fn extract_game_parts_from_name(game: &str) -> GameNameParsed {
+pub fn extract_game_parts_from_name(game: &str) -> GameNameParsed<'_> {
     // Separate game name into words
     // NOTE: we have to leave "-" in to prevent hyphenated prefixes being parsed as
     // numerals
@@ -132,14 +132,13 @@ impl<'a, B: ByteOrder> Buffer<'a, B> {
     /// # Type Parameters
     ///
     /// * `D` - The type of string decoder to use. This type must implement the
-    /// `StringDecoder` trait with the same byte order as the buffer.
+    ///   `StringDecoder` trait with the same byte order as the buffer.
     ///
     /// # Arguments
     ///
     /// * `until` - An optional delimiter. If provided, the method will read
-    ///   until this
-    /// delimiter is encountered. If not provided, the method will read until
-    /// the default delimiter of the decoder.
+    ///   until this delimiter is encountered. If not provided, the method will
+    ///   read until the default delimiter of the decoder.
     ///
     /// # Errors
     ///
@@ -157,6 +156,10 @@ impl<'a, B: ByteOrder> Buffer<'a, B> {
         // cursor, and the delimiter.
         let result = D::decode_string(data_slice, &mut self.cursor, delimiter)?;
 
+        // A string that isn't terminated by its delimiter ends at the end of the
+        // data, don't let the cursor go past it.
+        self.cursor = self.cursor.min(self.data.len());
+
         // If decoding was successful, return the decoded string. The cursor
         // position has been updated within the decode_string call to reflect
         // the new position after reading.
@@ -590,6 +593,16 @@ mod tests {
         assert_eq!(cursor, 6);
     }
 
+    #[test]
+    fn test_read_unterminated_string() {
+        let data: &[u8] = b"Hello";
+        let mut buffer = Buffer::<LittleEndian>::new(data);
+
+        assert_eq!(buffer.read_string::<Utf8Decoder>(None).unwrap(), "Hello");
+        assert_eq!(buffer.remaining_length(), 0);
+        assert!(buffer.read::<u8>().is_err());
+    }
+
     #[test]
     fn test_buffer_underflow_error() {
         let data: &[u8] = &[1, 2];
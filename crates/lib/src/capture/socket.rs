@@ -28,6 +28,34 @@ pub(crate) fn set_writer(writer: Box<dyn Writer + Send + Sync>) {
     *lock = Some(writer);
 }
 
+/// Captures a packet of a UDP socket that talks to many remote hosts (see
+/// [UdpMultiSocket](crate::socket::UdpMultiSocket)).
+///
+/// # Arguments
+/// * `direction` - Whether the packet is sent or received.
+/// * `remote_address` - The address the packet is sent to or received from.
+/// * `local_address` - The address of the socket.
+/// * `data` - The payload of the packet.
+pub(crate) fn capture_udp(
+    direction: Direction,
+    remote_address: &SocketAddr,
+    local_address: &SocketAddr,
+    data: &[u8],
+) -> GDResult<()> {
+    let info = CapturePacket {
+        direction,
+        protocol: Protocol::Udp,
+        remote_address,
+        local_address,
+    };
+
+    if let Some(writer) = CAPTURE_WRITER.lock().unwrap().as_mut() {
+        writer.write(&info, data)?;
+    }
+
+    Ok(())
+}
+
 /// A trait representing a provider of a network protocol.
 pub(crate) trait ProtocolProvider {
     /// Returns the protocol used by the provider.
@@ -160,6 +188,14 @@ impl<I: Socket, P: ProtocolProvider> Socket for WrappedCaptureSocket<I, P> {
         self.inner.apply_timeout(timeout_settings)
     }
 
+    /// Returns the round-trip time of the first request of the wrapped socket.
+    ///
+    /// Delegates the operation to the inner socket implementation.
+    ///
+    /// # Returns
+    /// The round-trip time, None if nothing was received yet.
+    fn ping(&self) -> Option<std::time::Duration> { self.inner.ping() }
+
     /// Returns the remote port of the wrapped socket.
     ///
     /// Delegates the operation to the inner socket implementation.
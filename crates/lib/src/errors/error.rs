@@ -1,6 +1,8 @@
-use crate::GDErrorKind;
+use crate::protocols::types::Protocol;
+use crate::{GDErrorKind, QueryStage};
 use std::error::Error;
 use std::fmt::Formatter;
+use std::net::SocketAddr;
 use std::{backtrace, fmt};
 
 pub(crate) type ErrorSource = Box<dyn Error + 'static + Send + Sync>;
@@ -32,10 +34,19 @@ pub(crate) type ErrorSource = Box<dyn Error + 'static + Send + Sync>;
 /// use gamedig::{GDError, GDErrorKind};
 /// let _: GDError = GDError::new(GDErrorKind::PacketBad, Some("Reason the packet was bad".into()));
 /// ```
+///
+/// The queries add what they know of their context (the address of the
+/// server, the protocol and the stage of the query) to their errors.
 pub struct GDError {
     pub kind: GDErrorKind,
     pub source: Option<ErrorSource>,
     pub backtrace: Option<backtrace::Backtrace>,
+    /// The address of the queried server.
+    pub address: Option<SocketAddr>,
+    /// The protocol of the query (boxed to keep errors small).
+    pub protocol: Option<Box<Protocol>>,
+    /// The stage of the query the error happened at.
+    pub stage: Option<QueryStage>,
 }
 
 impl From<GDErrorKind> for GDError {
@@ -45,6 +56,9 @@ impl From<GDErrorKind> for GDError {
             kind: value,
             source: None,
             backtrace,
+            address: None,
+            protocol: None,
+            stage: None,
         }
     }
 }
@@ -60,6 +74,15 @@ impl Error for GDError {
 impl fmt::Debug for GDError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         writeln!(f, "GDError{{ kind={:?}", self.kind)?;
+        if let Some(address) = &self.address {
+            writeln!(f, "  address={address}")?;
+        }
+        if let Some(protocol) = &self.protocol {
+            writeln!(f, "  protocol={protocol:?}")?;
+        }
+        if let Some(stage) = &self.stage {
+            writeln!(f, "  stage={stage:?}")?;
+        }
         if let Some(source) = &self.source {
             writeln!(f, "  source={source:?}")?;
         }
@@ -84,6 +107,9 @@ impl GDError {
             kind,
             source,
             backtrace,
+            address: None,
+            protocol: None,
+            stage: None,
         }
     }
 
@@ -91,6 +117,45 @@ impl GDError {
     pub fn from_error<E: Into<ErrorSource>>(kind: GDErrorKind, source: E) -> Self {
         Self::new(kind, Some(source.into()))
     }
+
+    /// Set the address of the queried server, if it isn't set yet (the
+    /// innermost context being kept).
+    pub fn with_address(mut self, address: SocketAddr) -> Self {
+        self.address = self.address.or(Some(address));
+        self
+    }
+
+    /// Set the protocol of the query, if it isn't set yet.
+    pub fn with_protocol(mut self, protocol: Protocol) -> Self {
+        self.protocol = self.protocol.or_else(|| Some(Box::new(protocol)));
+        self
+    }
+
+    /// Set the stage of the query the error happened at, if it isn't set yet.
+    pub fn with_stage(mut self, stage: QueryStage) -> Self {
+        self.stage = self.stage.or(Some(stage));
+        self
+    }
+}
+
+/// Serialized as its kind, the message of its source and its context (without
+/// the backtrace).
+#[cfg(feature = "serde")]
+impl serde::Serialize for GDError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("GDError", 5)?;
+        state.serialize_field("kind", &self.kind)?;
+        state.serialize_field(
+            "source",
+            &self.source.as_ref().map(|source| source.to_string()),
+        )?;
+        state.serialize_field("address", &self.address)?;
+        state.serialize_field("protocol", &self.protocol)?;
+        state.serialize_field("stage", &self.stage)?;
+        state.end()
+    }
 }
 
 #[cfg(test)]
@@ -140,4 +205,45 @@ mod tests {
         assert_eq!(error_from_into.kind, GDErrorKind::InvalidInput);
         assert!(error_from_into.source.is_none());
     }
+
+    // Test the context of GDError (the innermost one being kept)
+    #[test]
+    fn test_context() {
+        let error = GDErrorKind::PacketReceive
+            .context("timed out")
+            .with_stage(QueryStage::Players)
+            .with_stage(QueryStage::Info)
+            .with_address(SocketAddr::new([127, 0, 0, 1].into(), 27015))
+            .with_protocol(Protocol::Unreal2);
+        assert_eq!(error.stage, Some(QueryStage::Players));
+        assert_eq!(error.protocol, Some(Box::new(Protocol::Unreal2)));
+
+        let mut lines = format!("{error}");
+        lines.truncate(lines.find("  backtrace").unwrap());
+        assert_eq!(
+            lines,
+            "GDError{ kind=PacketReceive\n  address=127.0.0.1:27015\n  protocol=Unreal2\n  stage=Players\n  \
+             source=\"timed out\"\n"
+        );
+    }
+
+    // Test serializing GDError
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        let error = GDErrorKind::PacketReceive
+            .context("timed out")
+            .with_stage(QueryStage::Challenge)
+            .with_address(SocketAddr::new([127, 0, 0, 1].into(), 27015));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "kind": "PacketReceive",
+                "source": "timed out",
+                "address": "127.0.0.1:27015",
+                "protocol": null,
+                "stage": "challenge",
+            })
+        );
+    }
 }
@@ -1,7 +1,11 @@
 use crate::error::ErrorSource;
 use crate::GDError;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// All GameDig Error kinds.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum GDErrorKind {
     /// The received packet was bigger than the buffer size.
@@ -38,6 +42,11 @@ pub enum GDErrorKind {
     TypeParse,
     /// Couldn't find the host specified.
     HostLookup,
+    /// Couldn't read or write a file.
+    FileAccess,
+    /// The deadline of a batch of queries passed before the query finished (or
+    /// was started).
+    Deadline,
 }
 
 impl GDErrorKind {
@@ -6,7 +6,10 @@ pub mod error;
 pub mod kind;
 /// `GDResult`, a shorthand of `Result<T, GDError>`.
 pub mod result;
+/// The stages of a query.
+pub mod stage;
 
 pub use error::*;
 pub use kind::*;
 pub use result::*;
+pub use stage::*;
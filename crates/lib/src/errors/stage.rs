@@ -0,0 +1,17 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The stage of a query an error happened at.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueryStage {
+    /// Getting (or answering) the challenge of the server.
+    Challenge,
+    /// Getting the server information.
+    Info,
+    /// Getting the players.
+    Players,
+    /// Getting the rules (or other server variables).
+    Rules,
+}
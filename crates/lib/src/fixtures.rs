@@ -0,0 +1,45 @@
+//! Responses and packets shared by the unit tests.
+
+use crate::protocols::types::{CommonResponse, CommonResponseOwned};
+use crate::protocols::unreal2;
+
+#[path = "../tests/common/packets.rs"]
+mod packets;
+
+pub use packets::*;
+
+/// An Unreal 2 response (32 players maximum) on `map` with `players_online`
+/// players, named `Player1`, `Player2`...
+pub fn response(map: &str, players_online: u32) -> CommonResponseOwned {
+    unreal2::Response {
+        server_info: unreal2::ServerInfo {
+            server_id: 0,
+            ip: String::new(),
+            game_port: 7777,
+            query_port: 7778,
+            name: String::new(),
+            map: map.to_string(),
+            game_type: String::new(),
+            num_players: players_online,
+            max_players: 32,
+            password: false,
+        },
+        mutators_and_rules: Default::default(),
+        players: unreal2::Players {
+            players: (1 ..= players_online)
+                .map(|id| {
+                    unreal2::Player {
+                        id,
+                        name: format!("Player{id}"),
+                        ping: 50,
+                        score: 0,
+                        stats_id: 0,
+                    }
+                })
+                .collect(),
+            ..Default::default()
+        },
+        ping: None,
+    }
+    .as_owned()
+}
@@ -0,0 +1,278 @@
+//! Query a server without knowing its game
+
+use std::net::SocketAddr;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::games::{mindustry, minecraft};
+use crate::protocols::gamespy::{self, GameSpyVersion};
+use crate::protocols::quake::{self, QuakeVersion};
+use crate::protocols::types::{CommonResponse, ProprietaryProtocol, Protocol, TimeoutSettings};
+use crate::protocols::{unreal2, valve};
+use crate::GDErrorKind::AutoQuery;
+use crate::GDResult;
+
+#[cfg(feature = "game_defs")]
+use crate::games::{find_games_by_app_id, GAMES};
+#[cfg(feature = "game_defs")]
+use crate::protocols::types::GenericResponse;
+
+/// The first valid response of an [auto_query].
+pub struct AutoQueryResponse {
+    /// The protocol that the server answered to.
+    pub protocol: Protocol,
+    pub response: Box<dyn CommonResponse>,
+    /// The ids of the [GAMES] definitions that match the response (sorted),
+    /// empty when the protocol is shared by many games without telling which
+    /// one the server is running (see [auto_query]).
+    #[cfg(feature = "game_defs")]
+    pub games: Vec<&'static str>,
+}
+
+/// The protocols that are probed (Minecraft trying all of its variants).
+const PROTOCOLS: [Protocol; 10] = [
+    Protocol::Valve(valve::Engine::Source(None)),
+    Protocol::PROPRIETARY(ProprietaryProtocol::Minecraft(None)),
+    Protocol::Gamespy(GameSpyVersion::Three),
+    Protocol::Gamespy(GameSpyVersion::Two),
+    Protocol::Gamespy(GameSpyVersion::One),
+    Protocol::Quake(QuakeVersion::Three),
+    Protocol::Quake(QuakeVersion::Two),
+    Protocol::Quake(QuakeVersion::One),
+    Protocol::Unreal2,
+    Protocol::PROPRIETARY(ProprietaryProtocol::Mindustry),
+];
+
+/// Query a single protocol, returning the protocol that answered (which can be
+/// more specific than the given one).
+fn probe(
+    protocol: Protocol,
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<(Protocol, Box<dyn CommonResponse>)> {
+    let response: Box<dyn CommonResponse> = match &protocol {
+        Protocol::Valve(engine) => Box::new(valve::query(address, *engine, None, timeout_settings)?),
+        Protocol::Gamespy(GameSpyVersion::One) => Box::new(gamespy::one::query(address, timeout_settings)?),
+        Protocol::Gamespy(GameSpyVersion::Two) => Box::new(gamespy::two::query(address, timeout_settings)?),
+        Protocol::Gamespy(GameSpyVersion::Three) => Box::new(gamespy::three::query(address, timeout_settings)?),
+        Protocol::Quake(QuakeVersion::One) => Box::new(quake::one::query(address, timeout_settings)?),
+        Protocol::Quake(QuakeVersion::Two) => Box::new(quake::two::query(address, timeout_settings)?),
+        Protocol::Quake(QuakeVersion::Three) => Box::new(quake::three::query(address, timeout_settings)?),
+        Protocol::Unreal2 => {
+            Box::new(unreal2::query(
+                address,
+                &unreal2::GatheringSettings::default(),
+                timeout_settings,
+            )?)
+        }
+        Protocol::PROPRIETARY(ProprietaryProtocol::Mindustry) => {
+            Box::new(mindustry::query(
+                &address.ip(),
+                Some(address.port()),
+                &timeout_settings,
+            )?)
+        }
+        Protocol::PROPRIETARY(ProprietaryProtocol::Minecraft(None)) => {
+            let response = minecraft::protocol::query(address, timeout_settings, None)?;
+            return Ok((
+                Protocol::PROPRIETARY(ProprietaryProtocol::Minecraft(Some(response.server_type))),
+                Box::new(response),
+            ));
+        }
+        _ => return Err(AutoQuery.into()),
+    };
+
+    Ok((protocol, response))
+}
+
+/// Find out which protocol a server uses by querying all of them at once.
+///
+/// Every protocol is queried on the given port (some games answer queries on
+/// another port than the game one, in which case that one has to be given),
+/// the first valid response is returned, or [AutoQuery] if none answered.
+///
+/// The games of the response are found from the Steam app id (or the folder
+/// for GoldSrc games) of Valve servers, and from the protocol when it is only
+/// used by a game (Minecraft and Mindustry), the other protocols (GameSpy,
+/// Quake and Unreal 2) don't tell which of their games the server is running.
+///
+/// ```no_run
+/// use gamedig::auto_query;
+///
+/// let detected = auto_query(&"127.0.0.1:27015".parse().unwrap(), None).unwrap();
+/// println!("{:?} {:?}", detected.protocol, detected.games);
+/// ```
+pub fn auto_query(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<AutoQueryResponse> {
+    let (sender, receiver) = mpsc::channel();
+
+    for protocol in PROTOCOLS {
+        let address = *address;
+        let sender = sender.clone();
+
+        thread::spawn(move || {
+            // The receiver is gone once a response was found.
+            let _ = sender.send(probe(protocol, &address, timeout_settings));
+        });
+    }
+    drop(sender);
+
+    // Ends once every probe has finished.
+    let (protocol, response) = receiver.into_iter().find_map(Result::ok).ok_or(AutoQuery)?;
+
+    Ok(AutoQueryResponse {
+        #[cfg(feature = "game_defs")]
+        games: matching_games(&protocol, response.as_ref()),
+        protocol,
+        response,
+    })
+}
+
+/// The GoldSrc games folders, as their definitions don't have an app id.
+#[cfg(feature = "game_defs")]
+const GOLDSRC_FOLDERS: [(&str, &str); 5] = [
+    ("cstrike", "counterstrike"),
+    ("czero", "cscz"),
+    ("dod", "dod"),
+    ("svencoop", "sco"),
+    ("tfc", "tfc"),
+];
+
+/// Find the [GAMES] definitions that match an [auto_query] response, Valve
+/// ones by the app id (or the folder for GoldSrc games) and the proprietary
+/// ones by their protocol, none for the protocols shared by many games.
+#[cfg(feature = "game_defs")]
+fn matching_games(protocol: &Protocol, response: &dyn CommonResponse) -> Vec<&'static str> {
+    if let GenericResponse::Valve(valve_response) = response.as_original() {
+        let by_appid = find_games_by_app_id(valve_response.info.appid);
+
+        if !by_appid.is_empty() {
+            return by_appid;
+        }
+
+        return GOLDSRC_FOLDERS
+            .iter()
+            .filter(|(folder, _)| *folder == valve_response.info.folder)
+            .map(|(_, id)| *id)
+            .collect();
+    }
+
+    let Protocol::PROPRIETARY(proprietary) = protocol else {
+        return Vec::new();
+    };
+
+    let mut ids: Vec<&'static str> = GAMES
+        .entries()
+        .filter(|(_, game)| {
+            match (&game.protocol, proprietary) {
+                (Protocol::PROPRIETARY(ProprietaryProtocol::Minecraft(None)), ProprietaryProtocol::Minecraft(_)) => {
+                    true
+                }
+                (Protocol::PROPRIETARY(game_protocol), _) => game_protocol == proprietary,
+                _ => false,
+            }
+        })
+        .map(|(id, _)| *id)
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+#[cfg(all(test, feature = "game_defs"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::valve_info;
+    use std::net::UdpSocket;
+    use std::time::Duration;
+
+    #[test]
+    fn goldsrc_folders_are_defined() {
+        for (_, id) in GOLDSRC_FOLDERS {
+            assert!(matches!(
+                GAMES.get(id).unwrap().protocol,
+                Protocol::Valve(valve::Engine::GoldSrc(_))
+            ));
+        }
+    }
+
+    fn timeout_settings() -> Option<TimeoutSettings> {
+        TimeoutSettings::new(
+            Some(Duration::from_millis(200)),
+            None,
+            Some(Duration::from_millis(200)),
+            0,
+        )
+        .ok()
+    }
+
+    #[test]
+    fn detects_valve() {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+
+        // Only answer the info request, ignoring every other protocol.
+        thread::spawn(move || {
+            let mut buffer = [0; 1400];
+            loop {
+                let (size, client) = socket.recv_from(&mut buffer).unwrap();
+                if buffer[.. size] == valve::request(valve::Request::Info) {
+                    socket.send_to(&valve_info("My Server"), client).unwrap();
+                }
+            }
+        });
+
+        let detected = auto_query(&address, timeout_settings()).unwrap();
+        assert_eq!(
+            detected.protocol,
+            Protocol::Valve(valve::Engine::Source(None))
+        );
+        assert_eq!(detected.response.name(), Some("My Server"));
+        assert_eq!(detected.games, ["teamfortress2"]);
+    }
+
+    #[test]
+    fn matching_by_protocol() {
+        let minecraft = minecraft::JavaResponse {
+            game_version: "1.20".to_string(),
+            protocol_version: 763,
+            players_maximum: 20,
+            players_online: 0,
+            players: None,
+            description: String::new(),
+            favicon: None,
+            previews_chat: None,
+            enforces_secure_chat: None,
+            server_type: minecraft::Server::Java,
+            ping: None,
+        };
+        assert_eq!(
+            matching_games(
+                &Protocol::PROPRIETARY(ProprietaryProtocol::Minecraft(Some(
+                    minecraft::Server::Java
+                ))),
+                &minecraft
+            ),
+            ["minecraft", "minecraftjava"]
+        );
+
+        // Many games use Quake 3, the response doesn't tell which.
+        let quake = quake::Response::<quake::three::Player> {
+            name: "Server".to_string(),
+            map: "q3dm17".to_string(),
+            players: Vec::new(),
+            players_online: 0,
+            players_maximum: 16,
+            game_version: None,
+            unused_entries: Default::default(),
+            ping: None,
+        };
+        assert!(matching_games(&Protocol::Quake(QuakeVersion::Three), &quake).is_empty());
+    }
+
+    #[test]
+    fn nothing_answers() {
+        // Nothing is listening on this port.
+        let address = "127.0.0.1:1".parse().unwrap();
+
+        assert!(matches!(auto_query(&address, timeout_settings()), Err(e) if e.kind == AutoQuery));
+    }
+}
@@ -0,0 +1,293 @@
+//! Query many servers at once
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::games::query::query_with_timeout_and_extra_settings;
+use crate::games::types::Game;
+use crate::protocols::types::{CommonResponse, ExtraRequestSettings, TimeoutSettings};
+use crate::GDErrorKind::Deadline;
+use crate::GDResult;
+
+/// A server to query using [query_many].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryTarget {
+    pub game: Game,
+    pub address: SocketAddr,
+    pub timeout_settings: Option<TimeoutSettings>,
+    pub extra_settings: Option<ExtraRequestSettings>,
+}
+
+impl QueryTarget {
+    /// Make a target using the default timeout and extra settings.
+    pub const fn new(game: Game, address: SocketAddr) -> Self {
+        Self {
+            game,
+            address,
+            timeout_settings: None,
+            extra_settings: None,
+        }
+    }
+}
+
+/// The result of a [QueryTarget], `index` being its position in the targets
+/// given to [query_many].
+pub type QueryManyResult = (usize, GDResult<Box<dyn CommonResponse>>);
+
+/// The targets that haven't been started yet and the indexes of the ones that
+/// have.
+struct Pending {
+    targets: std::iter::Enumerate<std::vec::IntoIter<QueryTarget>>,
+    started: HashSet<usize>,
+}
+
+type PendingTargets = Arc<Mutex<Pending>>;
+
+/// Query many servers concurrently (using at most `concurrency` threads),
+/// yielding the results as they finish.
+///
+/// Once the `deadline` has passed no more queries are started and the targets
+/// that haven't finished are yielded as [Deadline] errors, the ones in
+/// progress first (they won't be interrupted, but they will be dropped).
+///
+/// ```no_run
+/// use gamedig::{query_many, QueryTarget, GAMES};
+/// use std::time::Duration;
+///
+/// let targets = vec![
+///     QueryTarget::new(GAMES.get("teamfortress2").unwrap().clone(), "127.0.0.1:27015".parse().unwrap()),
+///     QueryTarget::new(GAMES.get("minecraft").unwrap().clone(), "127.0.0.1:25565".parse().unwrap()),
+/// ];
+///
+/// for (index, result) in query_many(targets, 16, Duration::from_secs(10)) {
+///     println!("{index}: {:?}", result.map(|r| r.name().map(str::to_string)));
+/// }
+/// ```
+pub fn query_many(targets: Vec<QueryTarget>, concurrency: usize, deadline: Duration) -> QueryMany {
+    let deadline = Instant::now() + deadline;
+    let remaining: HashSet<usize> = (0 .. targets.len()).collect();
+    let workers = concurrency.max(1).min(targets.len());
+
+    let pending: PendingTargets = Arc::new(Mutex::new(Pending {
+        targets: targets.into_iter().enumerate(),
+        started: HashSet::new(),
+    }));
+    let (sender, receiver) = mpsc::channel();
+
+    for _ in 0 .. workers {
+        let pending = pending.clone();
+        let sender = sender.clone();
+
+        thread::spawn(move || {
+            loop {
+                if Instant::now() >= deadline {
+                    break;
+                }
+
+                // Don't hold the lock while querying.
+                let next = pending.lock().ok().and_then(|mut p| {
+                    let next = p.targets.next();
+                    if let Some((index, _)) = &next {
+                        p.started.insert(*index);
+                    }
+                    next
+                });
+                let Some((index, target)) = next else {
+                    break;
+                };
+
+                let result = query_with_timeout_and_extra_settings(
+                    &target.game,
+                    &target.address.ip(),
+                    Some(target.address.port()),
+                    target.timeout_settings,
+                    target.extra_settings,
+                );
+
+                if sender.send((index, result)).is_err() {
+                    // The results are not wanted anymore.
+                    break;
+                }
+            }
+        });
+    }
+
+    QueryMany {
+        receiver,
+        pending,
+        remaining,
+        deadline,
+    }
+}
+
+/// An iterator over the results of [query_many], in the order that they
+/// finished.
+pub struct QueryMany {
+    receiver: Receiver<QueryManyResult>,
+    pending: PendingTargets,
+    /// Targets that have not been yielded yet.
+    remaining: HashSet<usize>,
+    deadline: Instant,
+}
+
+impl Iterator for QueryMany {
+    type Item = QueryManyResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+
+        loop {
+            let timeout = self.deadline.saturating_duration_since(Instant::now());
+            let (index, result) = match self.receiver.recv_timeout(timeout) {
+                Ok(item) => item,
+                // The deadline passed (or every worker stopped because of it).
+                Err(_) => {
+                    let pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+                    let in_flight = self
+                        .remaining
+                        .iter()
+                        .filter(|index| pending.started.contains(index))
+                        .min();
+                    let (index, reason) = match in_flight {
+                        Some(index) => (*index, "The deadline passed before the query finished"),
+                        None => {
+                            (
+                                *self.remaining.iter().min()?,
+                                "The query wasn't started before the deadline",
+                            )
+                        }
+                    };
+                    (index, Err(Deadline.context(reason)))
+                }
+            };
+
+            // The result of a query that finished after it was yielded as timed
+            // out.
+            if !self.remaining.remove(&index) {
+                continue;
+            }
+
+            return Some((index, result));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.remaining.len(), Some(self.remaining.len())) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocols::{types::Protocol, valve::Engine};
+
+    fn target(port: u16) -> QueryTarget {
+        QueryTarget {
+            game: Game {
+                name: "Test".into(),
+                default_port: port,
+                protocol: Protocol::Valve(Engine::new(440)),
+                request_settings: ExtraRequestSettings::default(),
+            },
+            address: SocketAddr::new([127, 0, 0, 1].into(), port),
+            timeout_settings: TimeoutSettings::new(
+                Some(Duration::from_millis(100)),
+                Some(Duration::from_millis(100)),
+                None,
+                0,
+            )
+            .ok(),
+            extra_settings: None,
+        }
+    }
+
+    #[test]
+    fn yields_every_target() {
+        // Nothing is listening on these, every query should fail.
+        let targets: Vec<_> = (0 .. 5).map(|i| target(1 + i)).collect();
+
+        let mut indexes: Vec<_> = query_many(targets, 2, Duration::from_secs(30))
+            .map(|(index, result)| {
+                assert!(result.is_err());
+                index
+            })
+            .collect();
+        indexes.sort_unstable();
+
+        assert_eq!(indexes, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn deadline() {
+        let start = Instant::now();
+        let results: Vec<_> = query_many(vec![target(1), target(2)], 1, Duration::ZERO).collect();
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(_, result)| {
+            matches!(result, Err(e) if e.kind == Deadline
+                && e.source.as_ref().unwrap().to_string() == "The query wasn't started before the deadline")
+        }));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn in_flight_first() {
+        // Receives the query without ever answering it.
+        let silent = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let slow = target(silent.local_addr().unwrap().port());
+
+        // The first query is in progress when the deadline passes, the second
+        // one is never started.
+        let results: Vec<_> = query_many(vec![slow, target(1)], 1, Duration::from_millis(20))
+            .map(|(index, result)| {
+                let error = result.err().unwrap();
+                (index, error.kind, error.source.unwrap().to_string())
+            })
+            .collect();
+
+        assert_eq!(
+            results,
+            [
+                (
+                    0,
+                    Deadline,
+                    "The deadline passed before the query finished".to_string()
+                ),
+                (
+                    1,
+                    Deadline,
+                    "The query wasn't started before the deadline".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn late_results_are_skipped() {
+        // Receives the query without ever answering it.
+        let silent = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let slow = target(silent.local_addr().unwrap().port());
+
+        let mut results = query_many(vec![slow, target(1)], 1, Duration::from_millis(20));
+        let first = results.next().unwrap();
+        // Let the slow query finish (and send its result) after the deadline.
+        thread::sleep(Duration::from_millis(300));
+
+        let mut indexes: Vec<_> = std::iter::once(first)
+            .chain(results)
+            .map(|(index, _)| index)
+            .collect();
+        indexes.sort_unstable();
+
+        assert_eq!(indexes, [0, 1]);
+    }
+
+    #[test]
+    fn no_targets() {
+        assert_eq!(query_many(Vec::new(), 4, Duration::from_secs(1)).count(), 0);
+    }
+}
@@ -0,0 +1,409 @@
+//! Reuse the responses of recent queries
+//!
+//! A [QueryCache] answers a query with the response (or failure) of the same
+//! query (same game, address and settings) made recently, identical queries
+//! made at the same time sharing a single one.
+//!
+//! ```no_run
+//! use gamedig::games::QueryCache;
+//! use gamedig::GAMES;
+//! use std::time::Duration;
+//!
+//! let cache = QueryCache::new().set_ttl(Duration::from_secs(10));
+//! let game = GAMES.get("teamfortress2").unwrap();
+//!
+//! for _ in 0 .. 3 {
+//!     // Only the first one queries the server.
+//!     let response = cache.query(game, &"127.0.0.1".parse().unwrap(), None, None, None);
+//!     println!("{:?}", response.map(|r| r.players_online));
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::games::query::query_with_timeout_and_extra_settings;
+use crate::games::types::Game;
+use crate::protocols::types::{CommonResponse, CommonResponseOwned, ExtraRequestSettings, Protocol, TimeoutSettings};
+use crate::{GDError, GDErrorKind, GDResult, QueryStage};
+
+/// What identifies a query.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    game: Game,
+    address: IpAddr,
+    port: Option<u16>,
+    timeout_settings: Option<TimeoutSettings>,
+    extra_settings: Option<ExtraRequestSettings>,
+}
+
+/// A failed query, errors not being clonable.
+#[derive(Debug, Clone)]
+struct CachedError {
+    kind: GDErrorKind,
+    source: Option<String>,
+    address: Option<SocketAddr>,
+    protocol: Option<Box<Protocol>>,
+    stage: Option<QueryStage>,
+}
+
+impl CachedError {
+    fn new(error: &GDError) -> Self {
+        Self {
+            kind: error.kind.clone(),
+            source: error.source.as_ref().map(|source| source.to_string()),
+            address: error.address,
+            protocol: error.protocol.clone(),
+            stage: error.stage,
+        }
+    }
+
+    /// A new error of the same kind, reason and context (with a new
+    /// backtrace).
+    fn to_error(&self) -> GDError {
+        let mut error = GDError::new(self.kind.clone(), self.source.clone().map(Into::into));
+        error.address = self.address;
+        error.protocol = self.protocol.clone();
+        error.stage = self.stage;
+        error
+    }
+}
+
+type Outcome = Result<Arc<CommonResponseOwned>, CachedError>;
+
+enum SlotState {
+    /// The query is being made.
+    Pending,
+    Done {
+        expires: Instant,
+        outcome: Outcome,
+    },
+}
+
+/// The outcome of a query, shared by the ones waiting for it.
+struct Slot {
+    state: Mutex<SlotState>,
+    done: Condvar,
+}
+
+impl Slot {
+    fn is_fresh(&self, now: Instant) -> bool {
+        match &*self.state.lock().unwrap() {
+            SlotState::Pending => true,
+            SlotState::Done { expires, .. } => *expires > now,
+        }
+    }
+
+    fn finish(&self, expires: Instant, outcome: Outcome) {
+        *self.state.lock().unwrap() = SlotState::Done { expires, outcome };
+        self.done.notify_all();
+    }
+
+    fn wait(&self) -> Outcome {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            match &*state {
+                SlotState::Pending => state = self.done.wait(state).unwrap(),
+                SlotState::Done { outcome, .. } => return outcome.clone(),
+            }
+        }
+    }
+}
+
+/// Finishes a slot (as an already expired failure) if its query panicked, so
+/// that the ones waiting for it don't wait forever.
+struct PendingGuard<'a>(Option<&'a Slot>);
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(slot) = self.0 {
+            slot.finish(
+                Instant::now(),
+                Err(CachedError {
+                    kind: GDErrorKind::PacketReceive,
+                    source: Some("The query panicked".to_string()),
+                    address: None,
+                    protocol: None,
+                    stage: None,
+                }),
+            );
+        }
+    }
+}
+
+/// A cache of query results, which can be shared between threads.
+pub struct QueryCache {
+    ttl: Duration,
+    failure_ttl: Duration,
+    capacity: usize,
+    slots: Mutex<HashMap<CacheKey, Arc<Slot>>>,
+}
+
+impl QueryCache {
+    /// A cache keeping the responses for 5 seconds and the failures for 1
+    /// second, of at most 1024 queries.
+    pub fn new() -> Self {
+        Self {
+            ttl: Duration::from_secs(5),
+            failure_ttl: Duration::from_secs(1),
+            capacity: 1024,
+            slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set how long a response is reused.
+    pub const fn set_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Set how long a failure is reused (usually shorter than for responses,
+    /// so that servers coming back are soon seen).
+    pub const fn set_failure_ttl(mut self, failure_ttl: Duration) -> Self {
+        self.failure_ttl = failure_ttl;
+        self
+    }
+
+    /// Set how many queries are kept at most, the ones expiring first being
+    /// removed to make room (the queries being made are always kept).
+    pub const fn set_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Query a server (as [query_with_timeout_and_extra_settings]), reusing
+    /// the result of the same query if it's recent enough or being made.
+    ///
+    /// A reused failure is a new error of the same kind, reason and context.
+    pub fn query(
+        &self,
+        game: &Game,
+        address: &IpAddr,
+        port: Option<u16>,
+        timeout_settings: Option<TimeoutSettings>,
+        extra_settings: Option<ExtraRequestSettings>,
+    ) -> GDResult<Arc<CommonResponseOwned>> {
+        let key = CacheKey {
+            game: game.clone(),
+            address: *address,
+            port,
+            timeout_settings,
+            extra_settings,
+        };
+
+        self.get_or_query(key, |key| {
+            query_with_timeout_and_extra_settings(
+                &key.game,
+                &key.address,
+                key.port,
+                key.timeout_settings,
+                key.extra_settings.clone(),
+            )
+        })
+    }
+
+    fn get_or_query(
+        &self,
+        key: CacheKey,
+        query: impl FnOnce(&CacheKey) -> GDResult<Box<dyn CommonResponse>>,
+    ) -> GDResult<Arc<CommonResponseOwned>> {
+        let (slot, made_elsewhere) = {
+            let mut slots = self.slots.lock().unwrap();
+            match slots.get(&key) {
+                Some(slot) if slot.is_fresh(Instant::now()) => (slot.clone(), true),
+                _ => {
+                    self.make_room(&mut slots);
+                    let slot = Arc::new(Slot {
+                        state: Mutex::new(SlotState::Pending),
+                        done: Condvar::new(),
+                    });
+                    slots.insert(key.clone(), slot.clone());
+                    (slot, false)
+                }
+            }
+        };
+
+        if made_elsewhere {
+            return slot.wait().map_err(|error| error.to_error());
+        }
+
+        let mut guard = PendingGuard(Some(&slot));
+        let result = query(&key).map(|response| Arc::new(response.as_owned()));
+        guard.0 = None;
+
+        let (expires, outcome) = match &result {
+            Ok(response) => (Instant::now() + self.ttl, Ok(response.clone())),
+            Err(error) => {
+                (
+                    Instant::now() + self.failure_ttl,
+                    Err(CachedError::new(error)),
+                )
+            }
+        };
+        slot.finish(expires, outcome);
+
+        result
+    }
+
+    /// Remove the expired queries, and if there are still too many the ones
+    /// expiring first.
+    fn make_room(&self, slots: &mut HashMap<CacheKey, Arc<Slot>>) {
+        if slots.len() < self.capacity {
+            return;
+        }
+
+        let now = Instant::now();
+        slots.retain(|_, slot| slot.is_fresh(now));
+
+        let mut done: Vec<(Instant, CacheKey)> = slots
+            .iter()
+            .filter_map(|(key, slot)| {
+                match &*slot.state.lock().unwrap() {
+                    SlotState::Pending => None,
+                    SlotState::Done { expires, .. } => Some((*expires, key.clone())),
+                }
+            })
+            .collect();
+        done.sort_by_key(|(expires, _)| *expires);
+
+        let excess = (slots.len() + 1).saturating_sub(self.capacity.max(1));
+        for (_, key) in done.into_iter().take(excess) {
+            slots.remove(&key);
+        }
+    }
+
+    /// Number of queries kept (including the expired ones not removed yet).
+    pub fn len(&self) -> usize { self.slots.lock().unwrap().len() }
+
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Forget every query.
+    pub fn clear(&self) { self.slots.lock().unwrap().clear(); }
+}
+
+impl Default for QueryCache {
+    fn default() -> Self { Self::new() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::response;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    fn key(port: u16) -> CacheKey {
+        CacheKey {
+            game: Game {
+                name: "Unreal Tournament 2004".into(),
+                default_port: 7778,
+                protocol: Protocol::Unreal2,
+                request_settings: ExtraRequestSettings::default(),
+            },
+            address: [127, 0, 0, 1].into(),
+            port: Some(port),
+            timeout_settings: None,
+            extra_settings: None,
+        }
+    }
+
+    /// The answer of a query to a server with `players_online` players.
+    fn answer(players_online: u32) -> GDResult<Box<dyn CommonResponse>> {
+        Ok(Box::new(response("DM-Deck17", players_online)))
+    }
+
+    #[test]
+    fn reused() {
+        let cache = QueryCache::new().set_failure_ttl(Duration::ZERO);
+        let queries = &AtomicUsize::new(0);
+        let query = |players_online| {
+            move |_: &CacheKey| {
+                queries.fetch_add(1, Ordering::SeqCst);
+                answer(players_online)
+            }
+        };
+
+        assert_eq!(
+            cache.get_or_query(key(1), query(4)).unwrap().players_online,
+            4
+        );
+        assert_eq!(
+            cache.get_or_query(key(1), query(8)).unwrap().players_online,
+            4
+        );
+        assert_eq!(
+            cache.get_or_query(key(2), query(8)).unwrap().players_online,
+            8
+        );
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+
+        // Failures (expiring at once here) are remade.
+        for _ in 0 .. 2 {
+            let error = cache
+                .get_or_query(key(3), |_| {
+                    queries.fetch_add(1, Ordering::SeqCst);
+                    Err(GDErrorKind::PacketReceive.context("timed out"))
+                })
+                .unwrap_err();
+            assert_eq!(error.kind, GDErrorKind::PacketReceive);
+        }
+        assert_eq!(queries.load(Ordering::SeqCst), 4);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn failures_reused() {
+        let cache = QueryCache::new();
+        cache
+            .get_or_query(key(1), |_| {
+                Err(GDErrorKind::PacketReceive
+                    .context("timed out")
+                    .with_stage(QueryStage::Players))
+            })
+            .unwrap_err();
+
+        let error = cache.get_or_query(key(1), |_| answer(4)).unwrap_err();
+        assert_eq!(error.kind, GDErrorKind::PacketReceive);
+        assert_eq!(error.stage, Some(QueryStage::Players));
+        assert_eq!(error.source.unwrap().to_string(), "timed out");
+    }
+
+    #[test]
+    fn coalesced() {
+        let cache = QueryCache::new();
+        let queries = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0 .. 8 {
+                scope.spawn(|| {
+                    let response = cache.get_or_query(key(1), |_| {
+                        queries.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(200));
+                        answer(4)
+                    });
+                    assert_eq!(response.unwrap().players_online, 4);
+                });
+            }
+        });
+
+        assert_eq!(queries.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn bounded() {
+        let cache = QueryCache::new().set_capacity(2);
+        for port in 0 .. 5 {
+            cache.get_or_query(key(port), |_| answer(4)).unwrap();
+            assert!(cache.len() <= 2);
+        }
+
+        // The last ones are kept.
+        cache
+            .get_or_query(key(4), |_| panic!("queried again"))
+            .unwrap();
+    }
+}
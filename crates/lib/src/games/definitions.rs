@@ -7,9 +7,7 @@ use crate::Game;
 use crate::protocols::types::{GatherToggle, ProprietaryProtocol};
 use crate::protocols::valve::GatheringSettings;
 use phf::{phf_map, Map};
-
-#[cfg(feature = "tls")]
-use crate::protocols::epic::Credentials;
+use std::borrow::Cow;
 
 macro_rules! game {
     ($name: literal, $default_port: expr, $protocol: expr) => {
@@ -23,7 +21,7 @@ macro_rules! game {
 
     ($name: literal, $default_port: expr, $protocol: expr, $extra_request_settings: expr) => {
         Game {
-            name: $name,
+            name: Cow::Borrowed($name),
             default_port: $default_port,
             protocol: $protocol,
             request_settings: $extra_request_settings,
@@ -152,3 +150,24 @@ pub static GAMES: Map<&'static str, Game> = phf_map! {
     "mordhau" => game!("Mordhau", 27015, Protocol::Valve(Engine::new(629_760))),
     "mindustry" => game!("Mindustry", crate::games::mindustry::DEFAULT_PORT, Protocol::PROPRIETARY(ProprietaryProtocol::Mindustry)),
 };
+
+/// Other ids the games are known by (common abbreviations and former ids),
+/// mapped to their current ones
+///
+/// An id that is still used by a game isn't an alias, such as `csgo` (for
+/// Counter-Strike: Global Offensive, not Counter-Strike 2).
+pub static ALIASES: Map<&'static str, &'static str> = phf_map! {
+    "7d2d" => "sdtd",
+    "ark" => "ase",
+    "bf1942" => "battlefield1942",
+    "cs" => "counterstrike",
+    "cs2" => "counterstrike2",
+    "darkesthour" => "dhe4445",
+    "gmod" => "garrysmod",
+    "left4dead" => "l4d",
+    "left4dead2" => "l4d2",
+    "quake3arena" => "q3a",
+    "tf2" => "teamfortress2",
+    "ut2003" => "unrealtournament2003",
+    "ut2004" => "unrealtournament2004",
+};
@@ -1,7 +1,10 @@
 use crate::eco::{EcoRequestSettings, Response, Root};
 use crate::http::HttpClient;
+#[cfg(feature = "tokio")]
+use crate::GDErrorKind::PacketReceive;
 use crate::{GDResult, TimeoutSettings};
 use std::net::{IpAddr, SocketAddr};
+use std::time::Instant;
 
 /// Query an eco server.
 #[inline]
@@ -31,7 +34,34 @@ pub fn query_with_timeout_and_extra_settings(
         extra_settings.unwrap_or_default().into(),
     )?;
 
+    // The HTTP request being the only one, its time is the ping.
+    let sent = Instant::now();
     let response = client.get_json::<Root>("/frontpage", None)?;
+    let ping = sent.elapsed();
 
-    Ok(response.into())
+    Ok(Response {
+        ping: Some(ping),
+        ..response.into()
+    })
+}
+
+/// The async counterpart of [query_with_timeout_and_extra_settings].
+///
+/// The HTTP client is blocking, so the query is run on tokio's blocking
+/// thread pool.
+#[cfg(feature = "tokio")]
+pub async fn query_with_timeout_and_extra_settings_async(
+    address: &IpAddr,
+    port: Option<u16>,
+    timeout_settings: &Option<TimeoutSettings>,
+    extra_settings: Option<EcoRequestSettings>,
+) -> GDResult<Response> {
+    let address = *address;
+    let timeout_settings = *timeout_settings;
+
+    tokio::task::spawn_blocking(move || {
+        query_with_timeout_and_extra_settings(&address, port, &timeout_settings, extra_settings)
+    })
+    .await
+    .map_err(|e| PacketReceive.context(e))?
 }
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 
 use crate::http::{HttpProtocol, HttpSettings};
 use crate::protocols::types::{CommonPlayer, CommonResponse};
@@ -98,7 +99,7 @@ pub struct Player {
 }
 
 impl CommonPlayer for Player {
-    fn as_original(&self) -> crate::protocols::types::GenericPlayer {
+    fn as_original(&self) -> crate::protocols::types::GenericPlayer<'_> {
         crate::protocols::types::GenericPlayer::Eco(self)
     }
 
@@ -145,6 +146,8 @@ pub struct Response {
     pub relay_address: String,
     pub access: String,
     pub connect: String,
+    /// Round-trip time of the first request.
+    pub ping: Option<Duration>,
 }
 
 impl From<Root> for Response {
@@ -196,12 +199,13 @@ impl From<Root> for Response {
             relay_address: value.relay_address,
             access: value.access,
             connect: value.join_url,
+            ping: None,
         }
     }
 }
 
 impl CommonResponse for Response {
-    fn as_original(&self) -> crate::protocols::GenericResponse { crate::protocols::GenericResponse::Eco(self) }
+    fn as_original(&self) -> crate::protocols::GenericResponse<'_> { crate::protocols::GenericResponse::Eco(self) }
 
     fn players_online(&self) -> u32 { self.players_online }
 
@@ -214,6 +218,8 @@ impl CommonResponse for Response {
     fn has_password(&self) -> Option<bool> { Some(self.has_password) }
 
     fn players(&self) -> Option<Vec<&dyn CommonPlayer>> { Some(self.players.iter().map(|p| p as _).collect()) }
+
+    fn ping(&self) -> Option<Duration> { self.ping }
 }
 
 /// Extra request settings for eco queries.
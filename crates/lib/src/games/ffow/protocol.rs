@@ -1,6 +1,8 @@
 use crate::buffer::{Buffer, Utf8Decoder};
 use crate::games::ffow::types::Response;
 use crate::protocols::types::TimeoutSettings;
+#[cfg(feature = "tokio")]
+use crate::protocols::valve::AsyncValveProtocol;
 use crate::protocols::valve::{Engine, Environment, Server, ValveProtocol};
 use crate::GDResult;
 use byteorder::LittleEndian;
@@ -24,7 +26,42 @@ pub fn query_with_timeout(
         String::from("LSQ").into_bytes(),
     )?;
 
-    let mut buffer = Buffer::<LittleEndian>::new(&data);
+    Ok(Response {
+        ping: client.ping(),
+        ..parse_response(&data)?
+    })
+}
+
+/// The async counterpart of [query_with_timeout].
+#[cfg(feature = "tokio")]
+pub async fn query_with_timeout_async(
+    address: &IpAddr,
+    port: Option<u16>,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Response> {
+    let mut client = AsyncValveProtocol::new(
+        &SocketAddr::new(*address, port.unwrap_or(5478)),
+        timeout_settings,
+    )
+    .await?;
+    let data = client
+        .get_request_data(
+            &Engine::GoldSrc(true),
+            0,
+            0x46,
+            String::from("LSQ").into_bytes(),
+        )
+        .await?;
+
+    Ok(Response {
+        ping: client.ping(),
+        ..parse_response(&data)?
+    })
+}
+
+/// Parse a response packet.
+pub fn parse_response(data: &[u8]) -> GDResult<Response> {
+    let mut buffer = Buffer::<LittleEndian>::new(data);
 
     let protocol_version = buffer.read::<u8>()?;
     let name = buffer.read_string::<Utf8Decoder>(None)?;
@@ -62,5 +99,6 @@ pub fn query_with_timeout(
         round,
         rounds_maximum,
         time_left,
+        ping: None,
     })
 }
@@ -8,6 +8,9 @@ use crate::GDResult;
 use byteorder::BigEndian;
 use std::net::{IpAddr, SocketAddr};
 
+#[cfg(feature = "tokio")]
+use crate::protocols::gamespy::three::AsyncGameSpy3;
+
 fn parse_players_and_teams(packet: &[u8]) -> GDResult<Vec<Player>> {
     let mut buf = Buffer::<BigEndian>::new(packet);
 
@@ -25,6 +28,9 @@ fn parse_players_and_teams(packet: &[u8]) -> GDResult<Vec<Player>> {
     Ok(players)
 }
 
+/// The payload of the gamespy 3 data request.
+pub const REQUEST_PAYLOAD: [u8; 4] = [0xFF, 0xFF, 0xFF, 0x02];
+
 pub fn query(address: &IpAddr, port: Option<u16>) -> GDResult<Response> { query_with_timeout(address, port, None) }
 
 pub fn query_with_timeout(
@@ -35,11 +41,21 @@ pub fn query_with_timeout(
     let mut client = GameSpy3::new_custom(
         &SocketAddr::new(*address, port.unwrap_or(7777)),
         timeout_settings,
-        [0xFF, 0xFF, 0xFF, 0x02],
+        REQUEST_PAYLOAD,
         true,
     )?;
-
     let packets = client.get_server_packets()?;
+
+    Ok(Response {
+        ping: client.ping(),
+        ..parse_response(&packets)?
+    })
+}
+
+/// Parse the data packets collected by a gamespy 3
+/// [PacketCollector](crate::protocols::gamespy::three::PacketCollector) in
+/// single packets mode (the data request being made with [REQUEST_PAYLOAD]).
+pub fn parse_response(packets: &[Vec<u8>]) -> GDResult<Response> {
     let data = packets
         .first()
         .ok_or_else(|| PacketBad.context("First packet missing"))?;
@@ -71,5 +87,28 @@ pub fn query_with_timeout(
         players,
         players_maximum,
         players_online,
+        ping: None,
+    })
+}
+
+/// The async counterpart of [query_with_timeout].
+#[cfg(feature = "tokio")]
+pub async fn query_with_timeout_async(
+    address: &IpAddr,
+    port: Option<u16>,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Response> {
+    let mut client = AsyncGameSpy3::new_custom(
+        &SocketAddr::new(*address, port.unwrap_or(7777)),
+        timeout_settings,
+        REQUEST_PAYLOAD,
+        true,
+    )
+    .await?;
+    let packets = client.get_server_packets().await?;
+
+    Ok(Response {
+        ping: client.ping(),
+        ..parse_response(&packets)?
     })
 }
@@ -0,0 +1,193 @@
+//! Find games by something else than their id
+
+use crate::games::types::Game;
+use crate::protocols::types::Protocol;
+use crate::protocols::valve::Engine;
+
+#[cfg(feature = "game_defs")]
+use crate::games::{ALIASES, GAMES};
+
+/// Whether the game is a Valve one with the Steam app id as its main or its
+/// dedicated server one.
+fn has_app_id(game: &Game, app_id: u32) -> bool {
+    matches!(game.protocol, Protocol::Valve(Engine::Source(Some((main, dedicated))))
+        if main == app_id || dedicated == Some(app_id))
+}
+
+/// The ids of the games with the Steam app id (see [find_games_by_app_id]).
+pub(crate) fn ids_by_app_id<'a>(games: impl Iterator<Item = (&'a str, &'a Game)>, app_id: u32) -> Vec<&'a str> {
+    let mut ids: Vec<&str> = games
+        .filter(|(_, game)| has_app_id(game, app_id))
+        .map(|(id, _)| id)
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+/// Lowercase the text, keeping only its alphanumeric characters.
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The lowercase alphanumeric words of the text.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Whether all the characters of the needle appear in the haystack in order.
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+/// How well a game matches a search, lower being better, None if it doesn't.
+fn search_score(query: &str, query_words: &[String], id: &str, game: &Game) -> Option<u8> {
+    let name = normalize(&game.name);
+
+    if id == query {
+        Some(0)
+    } else if name == query {
+        Some(1)
+    } else if id.starts_with(query) || name.starts_with(query) {
+        Some(2)
+    } else if id.contains(query) || name.contains(query) {
+        Some(3)
+    } else {
+        let name_words = words(&game.name);
+        if query_words.iter().all(|query_word| {
+            name_words
+                .iter()
+                .any(|word| word.starts_with(query_word.as_str()))
+        }) {
+            Some(4)
+        } else if name.chars().next() == query.chars().next() && is_subsequence(query, &name) {
+            // Acronyms and abbreviations (tf2 for Team Fortress 2).
+            Some(5)
+        } else {
+            None
+        }
+    }
+}
+
+/// The ids of the games that match a search (see [search_games]), `alias`
+/// being the id the query is an alias of (if any).
+pub(crate) fn search_ids<'a>(
+    games: impl Iterator<Item = (&'a str, &'a Game)>,
+    query: &str,
+    alias: Option<&str>,
+) -> Vec<&'a str> {
+    let query_words = words(query);
+    let query = normalize(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut found: Vec<(u8, &str)> = games
+        .filter_map(|(id, game)| {
+            let score = if alias == Some(id) {
+                Some(0)
+            } else {
+                search_score(&query, &query_words, id, game)
+            };
+
+            score.map(|score| (score, id))
+        })
+        .collect();
+    found.sort_unstable();
+
+    found.into_iter().map(|(_, id)| id).collect()
+}
+
+/// Find the ids of the [built-in games](GAMES) that use a Steam app id, either
+/// as their main or dedicated server app id (sorted).
+///
+/// ```
+/// use gamedig::find_games_by_app_id;
+///
+/// assert_eq!(find_games_by_app_id(440), ["teamfortress2"]);
+/// ```
+#[cfg(feature = "game_defs")]
+pub fn find_games_by_app_id(app_id: u32) -> Vec<&'static str> {
+    ids_by_app_id(GAMES.entries().map(|(id, game)| (*id, game)), app_id)
+}
+
+/// Search the [built-in games](GAMES) by id, name or [alias](ALIASES), the
+/// best matches first.
+///
+/// The search ignores case and punctuation, exact ids and aliases come first,
+/// then exact names, names or ids starting with or containing the query,
+/// names containing all the query words and at last names containing the
+/// query letters in order (acronyms).
+///
+/// ```
+/// use gamedig::search_games;
+///
+/// assert_eq!(search_games("Team Fortress")[0], "teamfortress2");
+/// assert_eq!(search_games("cs2")[0], "counterstrike2");
+/// ```
+#[cfg(feature = "game_defs")]
+pub fn search_games(query: &str) -> Vec<&'static str> {
+    search_ids(
+        GAMES.entries().map(|(id, game)| (*id, game)),
+        query,
+        resolve_game_id(query),
+    )
+}
+
+/// Get the id of a [built-in game](GAMES) from its id or one of its
+/// [aliases](ALIASES), ignoring case.
+///
+/// ```
+/// use gamedig::resolve_game_id;
+///
+/// assert_eq!(resolve_game_id("cs2"), Some("counterstrike2"));
+/// assert_eq!(resolve_game_id("CounterStrike2"), Some("counterstrike2"));
+/// ```
+#[cfg(feature = "game_defs")]
+pub fn resolve_game_id(id_or_alias: &str) -> Option<&'static str> {
+    let id_or_alias = id_or_alias.to_lowercase();
+    GAMES
+        .get_key(id_or_alias.as_str())
+        .or_else(|| ALIASES.get(id_or_alias.as_str()))
+        .copied()
+}
+
+#[cfg(all(test, feature = "game_defs"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn by_app_id() {
+        assert_eq!(find_games_by_app_id(730), ["counterstrike2", "csgo"]);
+        // Operation: Harsh Doorstop's dedicated server app id.
+        assert_eq!(find_games_by_app_id(950_900), ["ohd"]);
+        assert!(find_games_by_app_id(0).is_empty());
+    }
+
+    #[test]
+    fn search() {
+        assert_eq!(search_games("teamfortress2")[0], "teamfortress2");
+        assert_eq!(search_games("Left 4 Dead")[0], "l4d");
+        assert_eq!(search_games("left4dead")[0], "l4d");
+        assert!(search_games("fortress team").contains(&"teamfortress2"));
+        assert!(search_games("tf2").contains(&"teamfortress2"));
+        assert!(search_games("").is_empty());
+        assert!(search_games("zzzzzz").is_empty());
+    }
+
+    #[test]
+    fn aliases() {
+        assert_eq!(resolve_game_id("left4dead"), Some("l4d"));
+        assert_eq!(resolve_game_id("TF2"), Some("teamfortress2"));
+        // Counter-Strike: Global Offensive keeps its own id.
+        assert_eq!(resolve_game_id("csgo"), Some("csgo"));
+        assert_eq!(search_games("ut2004")[0], "unrealtournament2004");
+        assert_eq!(resolve_game_id("nothing"), None);
+    }
+}
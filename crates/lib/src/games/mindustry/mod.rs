@@ -23,3 +23,15 @@ pub fn query(ip: &IpAddr, port: Option<u16>, timeout_settings: &Option<TimeoutSe
 
     protocol::query_with_retries(&address, timeout_settings)
 }
+
+/// The async counterpart of [query].
+#[cfg(feature = "tokio")]
+pub async fn query_async(
+    ip: &IpAddr,
+    port: Option<u16>,
+    timeout_settings: &Option<TimeoutSettings>,
+) -> GDResult<ServerData> {
+    let address = SocketAddr::new(*ip, port.unwrap_or(DEFAULT_PORT));
+
+    protocol::query_with_retries_async(&address, timeout_settings).await
+}
@@ -8,6 +8,9 @@ use crate::{
     TimeoutSettings,
 };
 
+#[cfg(feature = "tokio")]
+use crate::{socket::AsyncUdpSocket, utils::retry_on_timeout_async};
+
 use super::types::ServerData;
 
 /// Mindustry max datagram packet size.
@@ -16,7 +19,10 @@ pub const MAX_BUFFER_SIZE: usize = 500;
 /// Send a ping packet.
 ///
 /// [Reference](https://github.com/Anuken/Mindustry/blob/a2e5fbdedb2fc1c8d3c157bf344d10ad6d321442/core/src/mindustry/net/ArcNetProvider.java#L248)
-pub(crate) fn send_ping(socket: &mut UdpSocket) -> GDResult<()> { socket.send(&[-2i8 as u8, 1i8 as u8]) }
+pub(crate) fn send_ping(socket: &mut UdpSocket) -> GDResult<()> { socket.send(&PING_REQUEST) }
+
+/// The ping packet sent by [send_ping].
+const PING_REQUEST: [u8; 2] = [-2i8 as u8, 1i8 as u8];
 
 /// Parse server data.
 ///
@@ -35,6 +41,7 @@ pub fn parse_server_data<B: byteorder::ByteOrder, D: buffer::StringDecoder>(
         player_limit: buffer.read()?,
         description: buffer.read_string::<D>(None)?,
         mode_name: buffer.read_string::<D>(None).ok(),
+        ping: None,
     })
 }
 
@@ -47,7 +54,10 @@ pub fn query(address: &SocketAddr, timeout_settings: &Option<TimeoutSettings>) -
     let socket_data = socket.receive(Some(MAX_BUFFER_SIZE))?;
     let mut buffer = Buffer::new(&socket_data);
 
-    parse_server_data::<byteorder::BigEndian, buffer::Utf8LengthPrefixedDecoder>(&mut buffer)
+    Ok(ServerData {
+        ping: socket.ping(),
+        ..parse_server_data::<byteorder::BigEndian, buffer::Utf8LengthPrefixedDecoder>(&mut buffer)?
+    })
 }
 
 /// Query a Mindustry server.
@@ -56,3 +66,30 @@ pub fn query_with_retries(address: &SocketAddr, timeout_settings: &Option<Timeou
 
     utils::retry_on_timeout(retries, || query(address, timeout_settings))
 }
+
+/// The async counterpart of [query].
+#[cfg(feature = "tokio")]
+pub async fn query_async(address: &SocketAddr, timeout_settings: &Option<TimeoutSettings>) -> GDResult<ServerData> {
+    let mut socket = AsyncUdpSocket::new(address, timeout_settings).await?;
+
+    socket.send(&PING_REQUEST).await?;
+
+    let socket_data = socket.receive(Some(MAX_BUFFER_SIZE)).await?;
+    let mut buffer = Buffer::new(&socket_data);
+
+    Ok(ServerData {
+        ping: socket.ping(),
+        ..parse_server_data::<byteorder::BigEndian, buffer::Utf8LengthPrefixedDecoder>(&mut buffer)?
+    })
+}
+
+/// The async counterpart of [query_with_retries].
+#[cfg(feature = "tokio")]
+pub async fn query_with_retries_async(
+    address: &SocketAddr,
+    timeout_settings: &Option<TimeoutSettings>,
+) -> GDResult<ServerData> {
+    let retries = TimeoutSettings::get_retries_or_default(timeout_settings);
+
+    retry_on_timeout_async!(retries, query_async(address, timeout_settings))
+}
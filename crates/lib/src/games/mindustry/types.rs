@@ -4,6 +4,7 @@ use crate::{
 };
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Mindustry sever data
 ///
@@ -21,6 +22,8 @@ pub struct ServerData {
     pub player_limit: i32,
     pub description: String,
     pub mode_name: Option<String>,
+    /// Round-trip time of the first request.
+    pub ping: Option<Duration>,
 }
 
 /// Mindustry game mode
@@ -64,7 +67,7 @@ impl GameMode {
 }
 
 impl CommonResponse for ServerData {
-    fn as_original(&self) -> GenericResponse { GenericResponse::Mindustry(self) }
+    fn as_original(&self) -> GenericResponse<'_> { GenericResponse::Mindustry(self) }
 
     fn players_online(&self) -> u32 { self.players.try_into().unwrap_or(0) }
     fn players_maximum(&self) -> u32 { self.player_limit.try_into().unwrap_or(0) }
@@ -73,6 +76,7 @@ impl CommonResponse for ServerData {
 
     fn map(&self) -> Option<&str> { Some(&self.map) }
     fn description(&self) -> Option<&str> { Some(&self.description) }
+    fn ping(&self) -> Option<Duration> { self.ping }
 }
 
 #[cfg(test)]
@@ -80,6 +84,7 @@ mod test {
     use crate::protocols::types::CommonResponse;
 
     use super::ServerData;
+    use std::time::Duration;
 
     #[test]
     fn common_impl() {
@@ -94,6 +99,7 @@ mod test {
             player_limit: 20,
             description: String::from("description"),
             mode_name: Some(String::from("campaign")),
+            ping: Some(Duration::from_millis(12)),
         };
 
         let common: &dyn CommonResponse = &data;
@@ -103,5 +109,7 @@ mod test {
         assert_eq!(common.game_mode(), Some("pvp"));
         assert_eq!(common.map(), Some("map"));
         assert_eq!(common.description(), Some("description"));
+        assert_eq!(common.ping(), Some(Duration::from_millis(12)));
+        assert_eq!(common.as_json().ping, Some(12));
     }
 }
@@ -4,7 +4,6 @@ pub mod protocol;
 /// All types used by the implementation.
 pub mod types;
 
-pub use protocol::*;
 pub use types::*;
 
 use crate::{GDErrorKind, GDResult};
@@ -12,8 +12,19 @@ use crate::{
 
 use std::net::SocketAddr;
 
+#[cfg(feature = "tokio")]
+use crate::{socket::AsyncUdpSocket, utils::retry_on_timeout_async};
+
 use byteorder::LittleEndian;
 
+/// The status request packet.
+pub(crate) const STATUS_REQUEST: [u8; 33] = [
+    0x01, // Message ID: ID_UNCONNECTED_PING
+    0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, // Nonce / timestamp
+    0x00, 0xff, 0xff, 0x00, 0xfe, 0xfe, 0xfe, 0xfe, 0xfd, 0xfd, 0xfd, 0xfd, 0x12, 0x34, // Magic
+    0x56, 0x78, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Client GUID
+];
+
 pub struct Bedrock {
     socket: UdpSocket,
     retry_count: usize,
@@ -30,17 +41,6 @@ impl Bedrock {
         })
     }
 
-    fn send_status_request(&mut self) -> GDResult<()> {
-        self.socket.send(&[
-            0x01, // Message ID: ID_UNCONNECTED_PING
-            0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, // Nonce / timestamp
-            0x00, 0xff, 0xff, 0x00, 0xfe, 0xfe, 0xfe, 0xfe, 0xfd, 0xfd, 0xfd, 0xfd, 0x12, 0x34, // Magic
-            0x56, 0x78, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // Client GUID
-        ])?;
-
-        Ok(())
-    }
-
     /// Send a status request, and parse the response.
     /// This function will retry fetch on timeouts.
     fn get_info(&mut self) -> GDResult<BedrockResponse> {
@@ -49,10 +49,19 @@ impl Bedrock {
 
     /// Send a status request, and parse the response (without retry logic).
     fn get_info_impl(&mut self) -> GDResult<BedrockResponse> {
-        self.send_status_request()?;
+        self.socket.send(&STATUS_REQUEST)?;
 
-        let received = self.socket.receive(None)?;
-        let mut buffer = Buffer::<LittleEndian>::new(&received);
+        let data = self.socket.receive(None)?;
+
+        Ok(BedrockResponse {
+            ping: self.socket.ping(),
+            ..Self::parse_response(&data)?
+        })
+    }
+
+    /// Parse the status response.
+    pub(crate) fn parse_response(data: &[u8]) -> GDResult<BedrockResponse> {
+        let mut buffer = Buffer::<LittleEndian>::new(data);
 
         if buffer.read::<u8>()? != 0x1c {
             return Err(PacketBad.context("Expected 0x1c"));
@@ -102,6 +111,7 @@ impl Bedrock {
                 Some(v) => Some(GameMode::from_bedrock(v)?),
             },
             server_type: Server::Bedrock,
+            ping: None,
         })
     }
 
@@ -109,3 +119,31 @@ impl Bedrock {
         Self::new(address, timeout_settings)?.get_info()
     }
 }
+
+#[cfg(feature = "tokio")]
+impl Bedrock {
+    /// The async counterpart of [Bedrock::query].
+    pub async fn query_async(
+        address: &SocketAddr,
+        timeout_settings: Option<TimeoutSettings>,
+    ) -> GDResult<BedrockResponse> {
+        let mut socket = AsyncUdpSocket::new(address, &timeout_settings).await?;
+
+        retry_on_timeout_async!(
+            TimeoutSettings::get_retries_or_default(&timeout_settings),
+            Self::get_info_async_impl(&mut socket)
+        )
+    }
+
+    /// Send a status request, and parse the response (without retry logic).
+    async fn get_info_async_impl(socket: &mut AsyncUdpSocket) -> GDResult<BedrockResponse> {
+        socket.send(&STATUS_REQUEST).await?;
+
+        let data = socket.receive(None).await?;
+
+        Ok(BedrockResponse {
+            ping: socket.ping(),
+            ..Self::parse_response(&data)?
+        })
+    }
+}
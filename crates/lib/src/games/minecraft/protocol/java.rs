@@ -12,6 +12,9 @@ use byteorder::LittleEndian;
 use serde_json::Value;
 use std::net::SocketAddr;
 
+#[cfg(feature = "tokio")]
+use crate::{socket::AsyncTcpSocket, utils::retry_on_timeout_async};
+
 pub struct Java {
     socket: TcpSocket,
     request_settings: RequestSettings,
@@ -34,33 +37,21 @@ impl Java {
         })
     }
 
-    fn send(&mut self, data: Vec<u8>) -> GDResult<()> {
-        self.socket
-            .send(&[as_varint(data.len() as i32), data].concat())
-    }
-
-    fn receive(&mut self) -> GDResult<Vec<u8>> {
-        let data = &self.socket.receive(None)?;
-        let mut buffer = Buffer::<LittleEndian>::new(data);
-
-        let _packet_length = get_varint(&mut buffer)? as usize;
-        // this declared 'packet length' from within the packet might be wrong (?), not
-        // checking with it...
-
-        Ok(buffer.remaining_bytes().to_vec())
-    }
+    /// Prefix a packet with its length.
+    fn frame(data: Vec<u8>) -> Vec<u8> { [as_varint(data.len() as i32), data].concat() }
 
-    fn send_handshake(&mut self) -> GDResult<()> {
+    /// Build the (framed) handshake, status and ping request packets.
+    pub(crate) fn requests(request_settings: &RequestSettings, port: u16) -> GDResult<[Vec<u8>; 3]> {
         let handshake_payload = [
             &[
                 // Packet ID (0)
                 0x00,
             ], // Protocol Version (-1 to determine version)
-            as_varint(self.request_settings.protocol_version).as_slice(),
+            as_varint(request_settings.protocol_version).as_slice(),
             // Server address (can be anything)
-            as_string(&self.request_settings.hostname)?.as_slice(),
+            as_string(&request_settings.hostname)?.as_slice(),
             // Server port (can be anything)
-            &self.socket.port().to_le_bytes(),
+            &port.to_le_bytes(),
             &[
                 // Next state (1 for status)
                 0x01,
@@ -68,27 +59,17 @@ impl Java {
         ]
         .concat();
 
-        self.send(handshake_payload)?;
-
-        Ok(())
-    }
-
-    fn send_status_request(&mut self) -> GDResult<()> {
-        self.send(
-            [0x00] // Packet ID (0)
-                .to_vec(),
-        )?;
-
-        Ok(())
-    }
-
-    fn send_ping_request(&mut self) -> GDResult<()> {
-        self.send(
-            [0x01] // Packet ID (1)
-                .to_vec(),
-        )?;
-
-        Ok(())
+        Ok([
+            Self::frame(handshake_payload),
+            Self::frame(
+                [0x00] // Packet ID (0)
+                    .to_vec(),
+            ),
+            Self::frame(
+                [0x01] // Packet ID (1)
+                    .to_vec(),
+            ),
+        ])
     }
 
     /// Send minecraft ping request and parse the response.
@@ -100,12 +81,25 @@ impl Java {
     /// Send minecraft ping request and parse the response (without retry
     /// logic).
     fn get_info_impl(&mut self) -> GDResult<JavaResponse> {
-        self.send_handshake()?;
-        self.send_status_request()?;
-        self.send_ping_request()?;
+        for request in Self::requests(&self.request_settings, self.socket.port())? {
+            self.socket.send(&request)?;
+        }
 
-        let socket_data = self.receive()?;
-        let mut buffer = Buffer::<LittleEndian>::new(&socket_data);
+        let data = self.socket.receive(None)?;
+
+        Ok(JavaResponse {
+            ping: self.socket.ping(),
+            ..Self::parse_response(&data)?
+        })
+    }
+
+    /// Parse the status response.
+    pub(crate) fn parse_response(data: &[u8]) -> GDResult<JavaResponse> {
+        let mut buffer = Buffer::<LittleEndian>::new(data);
+
+        let _packet_length = get_varint(&mut buffer)? as usize;
+        // this declared 'packet length' from within the packet might be wrong (?), not
+        // checking with it...
 
         if get_varint(&mut buffer)? != 0 {
             // first var int is the packet id
@@ -159,6 +153,7 @@ impl Java {
             previews_chat: value_response["previewsChat"].as_bool(),
             enforces_secure_chat: value_response["enforcesSecureChat"].as_bool(),
             server_type: Server::Java,
+            ping: None,
         })
     }
 
@@ -170,3 +165,39 @@ impl Java {
         Self::new(address, timeout_settings, request_settings)?.get_info()
     }
 }
+
+#[cfg(feature = "tokio")]
+impl Java {
+    /// The async counterpart of [Java::query].
+    pub async fn query_async(
+        address: &SocketAddr,
+        timeout_settings: Option<TimeoutSettings>,
+        request_settings: Option<RequestSettings>,
+    ) -> GDResult<JavaResponse> {
+        let request_settings = request_settings.unwrap_or_default();
+        let mut socket = AsyncTcpSocket::new(address, &timeout_settings).await?;
+
+        retry_on_timeout_async!(
+            TimeoutSettings::get_retries_or_default(&timeout_settings),
+            Self::get_info_async_impl(&mut socket, &request_settings)
+        )
+    }
+
+    /// Send minecraft ping request and parse the response (without retry
+    /// logic).
+    async fn get_info_async_impl(
+        socket: &mut AsyncTcpSocket,
+        request_settings: &RequestSettings,
+    ) -> GDResult<JavaResponse> {
+        for request in Self::requests(request_settings, socket.port())? {
+            socket.send(&request).await?;
+        }
+
+        let data = socket.receive(None).await?;
+
+        Ok(JavaResponse {
+            ping: socket.ping(),
+            ..Self::parse_response(&data)?
+        })
+    }
+}
@@ -11,6 +11,19 @@ use crate::{
 };
 use std::net::SocketAddr;
 
+#[cfg(feature = "tokio")]
+use crate::{socket::AsyncTcpSocket, utils::retry_on_timeout_async};
+
+/// The initial request packet.
+pub(crate) const INITIAL_REQUEST: [u8; 19] = [
+    0xfe, // Packet ID (FE)
+    0x01, // Ping payload (01)
+    0xfa, // Packet identifier for plugin message
+    0x00, 0x07, // Length of 'GameDig' string (7) as unsigned short
+    0x00, 0x47, 0x00, 0x61, 0x00, 0x6D, 0x00, 0x65, 0x00, 0x44, 0x00, 0x69, 0x00,
+    0x67, // 'GameDig' string as UTF-16BE
+];
+
 pub struct LegacyV1_6 {
     socket: TcpSocket,
     retry_count: usize,
@@ -27,19 +40,6 @@ impl LegacyV1_6 {
         })
     }
 
-    fn send_initial_request(&mut self) -> GDResult<()> {
-        self.socket.send(&[
-            0xfe, // Packet ID (FE)
-            0x01, // Ping payload (01)
-            0xfa, // Packet identifier for plugin message
-            0x00, 0x07, // Length of 'GameDig' string (7) as unsigned short
-            0x00, 0x47, 0x00, 0x61, 0x00, 0x6D, 0x00, 0x65, 0x00, 0x44, 0x00, 0x69, 0x00,
-            0x67, // 'GameDig' string as UTF-16BE
-        ])?;
-
-        Ok(())
-    }
-
     pub(crate) fn is_protocol(buffer: &mut Buffer<BigEndian>) -> GDResult<bool> {
         let state = buffer
             .remaining_bytes()
@@ -80,6 +80,7 @@ impl LegacyV1_6 {
             previews_chat: None,
             enforces_secure_chat: None,
             server_type: Server::Legacy(LegacyGroup::V1_6),
+            ping: None,
         })
     }
 
@@ -91,10 +92,19 @@ impl LegacyV1_6 {
 
     /// Send info request and parse response (without retry logic).
     fn get_info_impl(&mut self) -> GDResult<JavaResponse> {
-        self.send_initial_request()?;
+        self.socket.send(&INITIAL_REQUEST)?;
 
         let data = self.socket.receive(None)?;
-        let mut buffer = Buffer::<BigEndian>::new(&data);
+
+        Ok(JavaResponse {
+            ping: self.socket.ping(),
+            ..Self::parse_response(&data)?
+        })
+    }
+
+    /// Parse the info response.
+    pub(crate) fn parse_response(data: &[u8]) -> GDResult<JavaResponse> {
+        let mut buffer = Buffer::<BigEndian>::new(data);
 
         if buffer.read::<u8>()? != 0xFF {
             return Err(ProtocolFormat.context("Expected 0xFF"));
@@ -114,3 +124,31 @@ impl LegacyV1_6 {
         Self::new(address, timeout_settings)?.get_info()
     }
 }
+
+#[cfg(feature = "tokio")]
+impl LegacyV1_6 {
+    /// The async counterpart of [LegacyV1_6::query].
+    pub async fn query_async(
+        address: &SocketAddr,
+        timeout_settings: Option<TimeoutSettings>,
+    ) -> GDResult<JavaResponse> {
+        let mut socket = AsyncTcpSocket::new(address, &timeout_settings).await?;
+
+        retry_on_timeout_async!(
+            TimeoutSettings::get_retries_or_default(&timeout_settings),
+            Self::get_info_async_impl(&mut socket)
+        )
+    }
+
+    /// Send info request and parse response (without retry logic).
+    async fn get_info_async_impl(socket: &mut AsyncTcpSocket) -> GDResult<JavaResponse> {
+        socket.send(&INITIAL_REQUEST).await?;
+
+        let data = socket.receive(None).await?;
+
+        Ok(JavaResponse {
+            ping: socket.ping(),
+            ..Self::parse_response(&data)?
+        })
+    }
+}
@@ -10,8 +10,14 @@ use crate::{
 
 use std::net::SocketAddr;
 
+#[cfg(feature = "tokio")]
+use crate::{socket::AsyncTcpSocket, utils::retry_on_timeout_async};
+
 use byteorder::BigEndian;
 
+/// The initial request packet.
+pub(crate) const INITIAL_REQUEST: [u8; 1] = [0xFE];
+
 pub struct LegacyVB1_8 {
     socket: TcpSocket,
     retry_count: usize,
@@ -28,8 +34,6 @@ impl LegacyVB1_8 {
         })
     }
 
-    fn send_initial_request(&mut self) -> GDResult<()> { self.socket.send(&[0xFE]) }
-
     /// Send request for info and parse response.
     /// This function will retry fetch on timeouts.
     fn get_info(&mut self) -> GDResult<JavaResponse> {
@@ -38,10 +42,19 @@ impl LegacyVB1_8 {
 
     /// Send request for info and parse response (without retry logic).
     fn get_info_impl(&mut self) -> GDResult<JavaResponse> {
-        self.send_initial_request()?;
+        self.socket.send(&INITIAL_REQUEST)?;
 
         let data = self.socket.receive(None)?;
-        let mut buffer = Buffer::<BigEndian>::new(&data);
+
+        Ok(JavaResponse {
+            ping: self.socket.ping(),
+            ..Self::parse_response(&data)?
+        })
+    }
+
+    /// Parse the info response.
+    pub(crate) fn parse_response(data: &[u8]) -> GDResult<JavaResponse> {
+        let mut buffer = Buffer::<BigEndian>::new(data);
 
         if buffer.read::<u8>()? != 0xFF {
             return Err(ProtocolFormat.context("Expected 0xFF"));
@@ -70,6 +83,7 @@ impl LegacyVB1_8 {
             previews_chat: None,
             enforces_secure_chat: None,
             server_type: Server::Legacy(LegacyGroup::VB1_8),
+            ping: None,
         })
     }
 
@@ -77,3 +91,31 @@ impl LegacyVB1_8 {
         Self::new(address, timeout_settings)?.get_info()
     }
 }
+
+#[cfg(feature = "tokio")]
+impl LegacyVB1_8 {
+    /// The async counterpart of [LegacyVB1_8::query].
+    pub async fn query_async(
+        address: &SocketAddr,
+        timeout_settings: Option<TimeoutSettings>,
+    ) -> GDResult<JavaResponse> {
+        let mut socket = AsyncTcpSocket::new(address, &timeout_settings).await?;
+
+        retry_on_timeout_async!(
+            TimeoutSettings::get_retries_or_default(&timeout_settings),
+            Self::get_info_async_impl(&mut socket)
+        )
+    }
+
+    /// Send info request and parse response (without retry logic).
+    async fn get_info_async_impl(socket: &mut AsyncTcpSocket) -> GDResult<JavaResponse> {
+        socket.send(&INITIAL_REQUEST).await?;
+
+        let data = socket.receive(None).await?;
+
+        Ok(JavaResponse {
+            ping: socket.ping(),
+            ..Self::parse_response(&data)?
+        })
+    }
+}
@@ -13,8 +13,10 @@ use crate::{
         LegacyGroup,
     },
     protocols::types::TimeoutSettings,
+    GDError,
     GDErrorKind::AutoQuery,
     GDResult,
+    QueryStage,
 };
 use std::net::SocketAddr;
 
@@ -24,6 +26,15 @@ mod legacy_v1_4;
 mod legacy_v1_6;
 mod legacy_vb1_8;
 
+/// Add the stage (the Minecraft queries only get the server info) and the
+/// address of the server to an error.
+fn with_context(error: GDError, address: &SocketAddr) -> GDError {
+    error.with_stage(QueryStage::Info).with_address(*address)
+}
+
+/// The error of a server that answered none of the protocol variants.
+fn no_variant(address: &SocketAddr) -> GDError { GDError::from(AutoQuery).with_address(*address) }
+
 /// Queries a Minecraft server with all the protocol variants one by one (Java
 /// -> Bedrock -> Legacy (1.6 -> 1.4 -> Beta 1.8)).
 pub fn query(
@@ -43,7 +54,7 @@ pub fn query(
         return Ok(response);
     }
 
-    Err(AutoQuery.into())
+    Err(no_variant(address))
 }
 
 /// Query a Java Server.
@@ -52,7 +63,7 @@ pub fn query_java(
     timeout_settings: Option<TimeoutSettings>,
     request_settings: Option<RequestSettings>,
 ) -> GDResult<JavaResponse> {
-    Java::query(address, timeout_settings, request_settings)
+    Java::query(address, timeout_settings, request_settings).map_err(|e| with_context(e, address))
 }
 
 /// Query a (Java) Legacy Server (1.6 -> 1.4 -> Beta 1.8).
@@ -69,7 +80,7 @@ pub fn query_legacy(address: &SocketAddr, timeout_settings: Option<TimeoutSettin
         return Ok(response);
     }
 
-    Err(AutoQuery.into())
+    Err(no_variant(address))
 }
 
 /// Query a specific (Java) Legacy Server.
@@ -83,9 +94,124 @@ pub fn query_legacy_specific(
         LegacyGroup::V1_4 => LegacyV1_4::query(address, timeout_settings),
         LegacyGroup::VB1_8 => LegacyVB1_8::query(address, timeout_settings),
     }
+    .map_err(|e| with_context(e, address))
 }
 
 /// Query a Bedrock Server.
 pub fn query_bedrock(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<BedrockResponse> {
-    Bedrock::query(address, timeout_settings)
+    Bedrock::query(address, timeout_settings).map_err(|e| with_context(e, address))
+}
+
+/// Build the handshake, status and ping request packets of a Java Server
+/// (`port` being the one that the server is queried on).
+pub fn java_requests(request_settings: &RequestSettings, port: u16) -> GDResult<[Vec<u8>; 3]> {
+    Java::requests(request_settings, port)
+}
+
+/// Parse the status response packet of a Java Server.
+pub fn parse_java_response(data: &[u8]) -> GDResult<JavaResponse> { Java::parse_response(data) }
+
+/// Get the request packet of a specific (Java) Legacy Server.
+pub const fn legacy_request(group: LegacyGroup) -> &'static [u8] {
+    match group {
+        LegacyGroup::V1_6 => &legacy_v1_6::INITIAL_REQUEST,
+        LegacyGroup::V1_4 => &legacy_v1_4::INITIAL_REQUEST,
+        LegacyGroup::VB1_8 => &legacy_vb1_8::INITIAL_REQUEST,
+    }
+}
+
+/// Parse the response packet of a specific (Java) Legacy Server.
+pub fn parse_legacy_response(group: LegacyGroup, data: &[u8]) -> GDResult<JavaResponse> {
+    match group {
+        LegacyGroup::V1_6 => LegacyV1_6::parse_response(data),
+        LegacyGroup::V1_4 => LegacyV1_4::parse_response(data),
+        LegacyGroup::VB1_8 => LegacyVB1_8::parse_response(data),
+    }
+}
+
+/// The status request packet of a Bedrock Server.
+pub const BEDROCK_STATUS_REQUEST: [u8; 33] = bedrock::STATUS_REQUEST;
+
+/// Parse the status response packet of a Bedrock Server.
+pub fn parse_bedrock_response(data: &[u8]) -> GDResult<BedrockResponse> { Bedrock::parse_response(data) }
+
+/// The async counterpart of [query].
+#[cfg(feature = "tokio")]
+pub async fn query_async(
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+    request_settings: Option<RequestSettings>,
+) -> GDResult<JavaResponse> {
+    if let Ok(response) = query_java_async(address, timeout_settings, request_settings).await {
+        return Ok(response);
+    }
+
+    if let Ok(response) = query_bedrock_async(address, timeout_settings).await {
+        return Ok(JavaResponse::from_bedrock_response(response));
+    }
+
+    if let Ok(response) = query_legacy_async(address, timeout_settings).await {
+        return Ok(response);
+    }
+
+    Err(no_variant(address))
+}
+
+/// The async counterpart of [query_java].
+#[cfg(feature = "tokio")]
+pub async fn query_java_async(
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+    request_settings: Option<RequestSettings>,
+) -> GDResult<JavaResponse> {
+    Java::query_async(address, timeout_settings, request_settings)
+        .await
+        .map_err(|e| with_context(e, address))
+}
+
+/// The async counterpart of [query_legacy].
+#[cfg(feature = "tokio")]
+pub async fn query_legacy_async(
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<JavaResponse> {
+    if let Ok(response) = query_legacy_specific_async(LegacyGroup::V1_6, address, timeout_settings).await {
+        return Ok(response);
+    }
+
+    if let Ok(response) = query_legacy_specific_async(LegacyGroup::V1_4, address, timeout_settings).await {
+        return Ok(response);
+    }
+
+    if let Ok(response) = query_legacy_specific_async(LegacyGroup::VB1_8, address, timeout_settings).await {
+        return Ok(response);
+    }
+
+    Err(no_variant(address))
+}
+
+/// The async counterpart of [query_legacy_specific].
+#[cfg(feature = "tokio")]
+pub async fn query_legacy_specific_async(
+    group: LegacyGroup,
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<JavaResponse> {
+    match group {
+        LegacyGroup::V1_6 => LegacyV1_6::query_async(address, timeout_settings).await,
+        LegacyGroup::V1_4 => LegacyV1_4::query_async(address, timeout_settings).await,
+        LegacyGroup::VB1_8 => LegacyVB1_8::query_async(address, timeout_settings).await,
+    }
+    .map_err(|e| with_context(e, address))
+}
+
+/// The async counterpart of [query_bedrock].
+#[cfg(feature = "tokio")]
+pub async fn query_bedrock_async(
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<BedrockResponse> {
+    Bedrock::query_async(address, timeout_settings)
+        .await
+        .map_err(|e| with_context(e, address))
 }
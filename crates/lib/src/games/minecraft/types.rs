@@ -15,6 +15,8 @@ use crate::{
 use byteorder::ByteOrder;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 /// The type of Minecraft Server you want to query.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -49,9 +51,11 @@ pub struct Player {
 }
 
 impl CommonPlayer for Player {
-    fn as_original(&self) -> GenericPlayer { GenericPlayer::Minecraft(self) }
+    fn as_original(&self) -> GenericPlayer<'_> { GenericPlayer::Minecraft(self) }
 
     fn name(&self) -> &str { &self.name }
+
+    fn extra(&self) -> BTreeMap<String, String> { BTreeMap::from([("id".to_string(), self.id.clone())]) }
 }
 
 /// Versioned response type
@@ -62,6 +66,41 @@ pub enum VersionedResponse<'a> {
     Java(&'a JavaResponse),
 }
 
+/// Owned versioned response type
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionedResponseOwned {
+    Bedrock(BedrockResponse),
+    Java(JavaResponse),
+}
+
+impl VersionedResponseOwned {
+    /// Get the borrowed versioned response.
+    pub const fn as_versioned(&self) -> VersionedResponse<'_> {
+        match self {
+            Self::Bedrock(response) => VersionedResponse::Bedrock(response),
+            Self::Java(response) => VersionedResponse::Java(response),
+        }
+    }
+
+    /// Get the response as a common one.
+    pub fn as_common(&self) -> &dyn CommonResponse {
+        match self {
+            Self::Bedrock(response) => response,
+            Self::Java(response) => response,
+        }
+    }
+}
+
+impl From<VersionedResponse<'_>> for VersionedResponseOwned {
+    fn from(response: VersionedResponse<'_>) -> Self {
+        match response {
+            VersionedResponse::Bedrock(response) => Self::Bedrock(response.clone()),
+            VersionedResponse::Java(response) => Self::Java(response.clone()),
+        }
+    }
+}
+
 /// A Java query response.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -87,6 +126,8 @@ pub struct JavaResponse {
     pub enforces_secure_chat: Option<bool>,
     /// Tell's the server type.
     pub server_type: Server,
+    /// Round-trip time of the first request.
+    pub ping: Option<Duration>,
 }
 
 /// Java-only additional request settings.
@@ -134,12 +175,13 @@ impl From<ExtraRequestSettings> for RequestSettings {
 }
 
 impl CommonResponse for JavaResponse {
-    fn as_original(&self) -> GenericResponse { GenericResponse::Minecraft(VersionedResponse::Java(self)) }
+    fn as_original(&self) -> GenericResponse<'_> { GenericResponse::Minecraft(VersionedResponse::Java(self)) }
 
     fn description(&self) -> Option<&str> { Some(&self.description) }
     fn players_maximum(&self) -> u32 { self.players_maximum }
     fn players_online(&self) -> u32 { self.players_online }
     fn game_version(&self) -> Option<&str> { Some(&self.game_version) }
+    fn ping(&self) -> Option<Duration> { self.ping }
 
     fn players(&self) -> Option<Vec<&dyn CommonPlayer>> {
         self.players
@@ -172,16 +214,19 @@ pub struct BedrockResponse {
     pub game_mode: Option<GameMode>,
     /// Tells the server type.
     pub server_type: Server,
+    /// Round-trip time of the first request.
+    pub ping: Option<Duration>,
 }
 
 impl CommonResponse for BedrockResponse {
-    fn as_original(&self) -> GenericResponse { GenericResponse::Minecraft(VersionedResponse::Bedrock(self)) }
+    fn as_original(&self) -> GenericResponse<'_> { GenericResponse::Minecraft(VersionedResponse::Bedrock(self)) }
 
     fn name(&self) -> Option<&str> { Some(&self.name) }
     fn map(&self) -> Option<&str> { self.map.as_deref() }
     fn game_version(&self) -> Option<&str> { Some(&self.version_name) }
     fn players_maximum(&self) -> u32 { self.players_maximum }
     fn players_online(&self) -> u32 { self.players_online }
+    fn ping(&self) -> Option<Duration> { self.ping }
 }
 
 impl JavaResponse {
@@ -197,6 +242,7 @@ impl JavaResponse {
             previews_chat: None,
             enforces_secure_chat: None,
             server_type: Server::Bedrock,
+            ping: response.ping,
         }
     }
 }
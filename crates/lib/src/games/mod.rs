@@ -37,8 +37,27 @@ pub use types::*;
 pub mod query;
 pub use query::*;
 
+pub mod batch;
+pub use batch::*;
+
+pub mod monitor;
+pub use monitor::*;
+
+pub mod cache;
+pub use cache::*;
+
+pub mod auto;
+pub use auto::*;
+
+pub mod registry;
+pub use registry::*;
+
+pub mod lookup;
+#[cfg(feature = "game_defs")]
+pub use lookup::*;
+
 #[cfg(feature = "game_defs")]
 mod definitions;
 
 #[cfg(feature = "game_defs")]
-pub use definitions::GAMES;
+pub use definitions::{ALIASES, GAMES};
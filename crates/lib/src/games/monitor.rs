@@ -0,0 +1,518 @@
+//! Query servers periodically, reporting what changed between the queries
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::ops::ControlFlow;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::games::batch::{query_many, QueryTarget};
+use crate::protocols::types::CommonResponse;
+use crate::GDError;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// What is compared between two queries of a server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerSnapshot {
+    pub map: Option<String>,
+    pub game_version: Option<String>,
+    pub has_password: Option<bool>,
+    pub players_online: u32,
+    /// Names of the players (sorted).
+    pub players: Vec<String>,
+}
+
+impl ServerSnapshot {
+    pub fn new(response: &dyn CommonResponse) -> Self {
+        let mut players: Vec<String> = response
+            .players()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|player| player.name().to_string())
+            .collect();
+        players.sort_unstable();
+
+        Self {
+            map: response.map().map(str::to_string),
+            game_version: response.game_version().map(str::to_string),
+            has_password: response.has_password(),
+            players_online: response.players_online(),
+            players,
+        }
+    }
+
+    /// The changes from the previous snapshot to this one.
+    pub fn changes_since(&self, previous: &Self) -> Vec<ServerChange> {
+        let mut changes = Vec::new();
+
+        if self.map != previous.map {
+            changes.push(ServerChange::MapChanged {
+                from: previous.map.clone(),
+                to: self.map.clone(),
+            });
+        }
+
+        if self.game_version != previous.game_version {
+            changes.push(ServerChange::VersionChanged {
+                from: previous.game_version.clone(),
+                to: self.game_version.clone(),
+            });
+        }
+
+        if self.has_password != previous.has_password {
+            changes.push(ServerChange::PasswordChanged {
+                from: previous.has_password,
+                to: self.has_password,
+            });
+        }
+
+        if self.players_online != previous.players_online {
+            changes.push(ServerChange::PlayersOnlineChanged {
+                from: previous.players_online,
+                to: self.players_online,
+            });
+        }
+
+        // Names can be shared by many players, so they are counted.
+        let mut counts: HashMap<&str, i32> = HashMap::new();
+        for name in &self.players {
+            *counts.entry(name).or_default() += 1;
+        }
+        for name in &previous.players {
+            *counts.entry(name).or_default() -= 1;
+        }
+
+        let mut counts: Vec<(&str, i32)> = counts.into_iter().collect();
+        counts.sort_unstable();
+        for (name, count) in counts {
+            for _ in 0 .. count.abs() {
+                let name = name.to_string();
+                changes.push(
+                    if count > 0 {
+                        ServerChange::PlayerJoined { name }
+                    } else {
+                        ServerChange::PlayerLeft { name }
+                    },
+                );
+            }
+        }
+
+        changes
+    }
+}
+
+/// A change of a server between two queries.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerChange {
+    PlayerJoined {
+        name: String,
+    },
+    PlayerLeft {
+        name: String,
+    },
+    MapChanged {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    VersionChanged {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    PasswordChanged {
+        from: Option<bool>,
+        to: Option<bool>,
+    },
+    PlayersOnlineChanged {
+        from: u32,
+        to: u32,
+    },
+    /// The server stopped answering (or was never reachable), `reason` being
+    /// why its query failed.
+    WentOffline {
+        reason: String,
+    },
+    /// The server answers again after having gone offline.
+    CameBack,
+}
+
+impl fmt::Display for ServerChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn or_none<T: ToString>(value: &Option<T>) -> String {
+            value
+                .as_ref()
+                .map_or_else(|| "none".to_string(), ToString::to_string)
+        }
+
+        match self {
+            Self::PlayerJoined { name } => write!(f, "Player joined: {name}"),
+            Self::PlayerLeft { name } => write!(f, "Player left: {name}"),
+            Self::MapChanged { from, to } => write!(f, "Map changed: {} -> {}", or_none(from), or_none(to)),
+            Self::VersionChanged { from, to } => write!(f, "Version changed: {} -> {}", or_none(from), or_none(to)),
+            Self::PasswordChanged { from, to } => write!(f, "Password changed: {} -> {}", or_none(from), or_none(to)),
+            Self::PlayersOnlineChanged { from, to } => write!(f, "Players online: {from} -> {to}"),
+            Self::WentOffline { reason } => write!(f, "Server went offline: {reason}"),
+            Self::CameBack => write!(f, "Server came back online"),
+        }
+    }
+}
+
+/// A change of one of the servers of a [Monitor].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonitorEvent {
+    /// Position of the server in the monitor targets.
+    pub target: usize,
+    pub address: SocketAddr,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub change: ServerChange,
+}
+
+/// The last known state of a server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ServerState {
+    Online(ServerSnapshot),
+    Offline,
+}
+
+/// Why a query failed.
+fn error_reason(error: &GDError) -> String {
+    error
+        .source
+        .as_ref()
+        .map_or_else(|| format!("{:?}", error.kind), ToString::to_string)
+}
+
+/// Follows the state of a single server from the results of its queries,
+/// reporting its changes (a [Monitor] has one for every target, this being for
+/// querying a server some other way).
+///
+/// The first query is the reference for the next ones, so it doesn't make any
+/// change unless it fails (the server being then reported as offline).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerTracker {
+    /// None until the first query.
+    state: Option<ServerState>,
+}
+
+impl ServerTracker {
+    pub const fn new() -> Self { Self { state: None } }
+
+    /// Record the result of a query, returning the changes since the previous
+    /// one.
+    pub fn record(&mut self, result: Result<&dyn CommonResponse, &GDError>) -> Vec<ServerChange> {
+        let (changes, state) = match (self.state.take(), result) {
+            (Some(ServerState::Online(previous)), Ok(response)) => {
+                let snapshot = ServerSnapshot::new(response);
+                (
+                    snapshot.changes_since(&previous),
+                    ServerState::Online(snapshot),
+                )
+            }
+            (Some(ServerState::Offline), Ok(response)) => {
+                (
+                    vec![ServerChange::CameBack],
+                    ServerState::Online(ServerSnapshot::new(response)),
+                )
+            }
+            (None, Ok(response)) => {
+                (
+                    Vec::new(),
+                    ServerState::Online(ServerSnapshot::new(response)),
+                )
+            }
+            (Some(ServerState::Offline), Err(_)) => (Vec::new(), ServerState::Offline),
+            (Some(ServerState::Online(_)) | None, Err(error)) => {
+                (
+                    vec![ServerChange::WentOffline {
+                        reason: error_reason(error),
+                    }],
+                    ServerState::Offline,
+                )
+            }
+        };
+        self.state = Some(state);
+
+        changes
+    }
+}
+
+/// Query servers periodically (concurrently, with [query_many]), reporting
+/// their changes between two queries as [events](MonitorEvent).
+///
+/// The first query of a server is the reference for the next ones, so it
+/// doesn't make any event unless it fails (the server being then reported as
+/// offline).
+///
+/// ```no_run
+/// use gamedig::{Monitor, QueryTarget, GAMES};
+/// use std::time::Duration;
+///
+/// let targets = vec![QueryTarget::new(
+///     GAMES.get("teamfortress2").unwrap().clone(),
+///     "127.0.0.1:27015".parse().unwrap(),
+/// )];
+///
+/// let events = Monitor::new(targets).spawn(Duration::from_secs(30));
+/// for event in events {
+///     println!("{}: {}", event.address, event.change);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Monitor {
+    targets: Vec<QueryTarget>,
+    trackers: Vec<ServerTracker>,
+    concurrency: usize,
+    deadline: Duration,
+}
+
+impl Monitor {
+    /// Monitor the targets, querying at most 16 of them at the same time and
+    /// giving up on the ones that haven't answered after 30 seconds.
+    pub fn new(targets: Vec<QueryTarget>) -> Self {
+        Self {
+            trackers: vec![ServerTracker::new(); targets.len()],
+            targets,
+            concurrency: 16,
+            deadline: Duration::from_secs(30),
+        }
+    }
+
+    /// Set how many servers can be queried at the same time.
+    pub const fn set_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Set how long the queries of a poll can take (the servers that haven't
+    /// answered being then considered offline).
+    pub const fn set_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// The monitored servers.
+    pub fn targets(&self) -> &[QueryTarget] { &self.targets }
+
+    /// Record the result of a query of a target, returning the changes since
+    /// its previous one (this is what [Monitor::poll] does with every result,
+    /// for querying the targets some other way).
+    pub fn record(&mut self, target: usize, result: Result<&dyn CommonResponse, &GDError>) -> Vec<MonitorEvent> {
+        let Some(tracker) = self.trackers.get_mut(target) else {
+            return Vec::new();
+        };
+        let changes = tracker.record(result);
+
+        let address = self.targets[target].address;
+        changes
+            .into_iter()
+            .map(|change| {
+                MonitorEvent {
+                    target,
+                    address,
+                    change,
+                }
+            })
+            .collect()
+    }
+
+    /// Query every target once, returning the changes since the previous poll
+    /// (in the order that the queries finished).
+    pub fn poll(&mut self) -> Vec<MonitorEvent> {
+        let results = query_many(self.targets.clone(), self.concurrency, self.deadline);
+
+        let mut events = Vec::new();
+        for (target, result) in results {
+            events.extend(self.record(target, result.as_deref()));
+        }
+
+        events
+    }
+
+    /// Poll the targets every `interval`, giving every event to `on_event`
+    /// until it breaks.
+    pub fn run<F: FnMut(MonitorEvent) -> ControlFlow<()>>(&mut self, interval: Duration, mut on_event: F) {
+        loop {
+            let started = Instant::now();
+
+            for event in self.poll() {
+                if on_event(event).is_break() {
+                    return;
+                }
+            }
+
+            thread::sleep(interval.saturating_sub(started.elapsed()));
+        }
+    }
+
+    /// Poll the targets every `interval` on another thread, sending the events
+    /// to the returned channel (the polls stopping once it's dropped).
+    pub fn spawn(mut self, interval: Duration) -> Receiver<MonitorEvent> {
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            self.run(interval, |event| {
+                match sender.send(event) {
+                    Ok(()) => ControlFlow::Continue(()),
+                    Err(_) => ControlFlow::Break(()),
+                }
+            });
+        });
+
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::response;
+    use crate::games::types::Game;
+    use crate::protocols::types::{ExtraRequestSettings, Protocol};
+    use crate::protocols::valve::Engine;
+    use crate::GDErrorKind::PacketReceive;
+
+    fn snapshot(map: &str, players: &[&str]) -> ServerSnapshot {
+        ServerSnapshot {
+            map: Some(map.to_string()),
+            game_version: Some("1.0".to_string()),
+            has_password: Some(false),
+            players_online: players.len() as u32,
+            players: players.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn changes() {
+        let previous = snapshot("cp_foundry", &["Alice", "Bob", "Player", "Player"]);
+
+        assert!(previous.changes_since(&previous).is_empty());
+
+        let mut current = snapshot("ctf_2fort", &["Alice", "Carol", "Player"]);
+        current.has_password = Some(true);
+        assert_eq!(
+            current.changes_since(&previous),
+            [
+                ServerChange::MapChanged {
+                    from: Some("cp_foundry".to_string()),
+                    to: Some("ctf_2fort".to_string()),
+                },
+                ServerChange::PasswordChanged {
+                    from: Some(false),
+                    to: Some(true),
+                },
+                ServerChange::PlayersOnlineChanged { from: 4, to: 3 },
+                ServerChange::PlayerLeft {
+                    name: "Bob".to_string()
+                },
+                ServerChange::PlayerJoined {
+                    name: "Carol".to_string()
+                },
+                ServerChange::PlayerLeft {
+                    name: "Player".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            ServerChange::MapChanged {
+                from: None,
+                to: Some("de_dust2".to_string())
+            }
+            .to_string(),
+            "Map changed: none -> de_dust2"
+        );
+        assert_eq!(
+            ServerChange::PlayersOnlineChanged { from: 1, to: 2 }.to_string(),
+            "Players online: 1 -> 2"
+        );
+    }
+
+    #[test]
+    fn records() {
+        let address = SocketAddr::new([127, 0, 0, 1].into(), 27015);
+        let game = Game {
+            name: "Test".into(),
+            default_port: 27015,
+            protocol: Protocol::Valve(Engine::new(440)),
+            request_settings: ExtraRequestSettings::default(),
+        };
+        let mut monitor = Monitor::new(vec![QueryTarget::new(game, address)]);
+
+        let changes = |events: Vec<MonitorEvent>| -> Vec<ServerChange> {
+            events
+                .into_iter()
+                .map(|event| {
+                    assert_eq!((event.target, event.address), (0, address));
+                    event.change
+                })
+                .collect()
+        };
+        let error = PacketReceive.context("No answer");
+
+        let first = response("DM-Deck17", 1);
+        // The first query is the reference.
+        assert!(monitor.record(0, Ok(&first)).is_empty());
+        assert!(monitor.record(0, Ok(&first)).is_empty());
+
+        assert_eq!(
+            changes(monitor.record(0, Err(&error))),
+            [ServerChange::WentOffline {
+                reason: "No answer".to_string()
+            }]
+        );
+        assert!(monitor.record(0, Err(&error)).is_empty());
+
+        assert_eq!(
+            changes(monitor.record(0, Ok(&response("DM-Deck17", 2)))),
+            [ServerChange::CameBack]
+        );
+        assert_eq!(
+            changes(monitor.record(0, Ok(&response("CTF-Face", 0)))),
+            [
+                ServerChange::MapChanged {
+                    from: Some("DM-Deck17".to_string()),
+                    to: Some("CTF-Face".to_string()),
+                },
+                ServerChange::PlayersOnlineChanged { from: 2, to: 0 },
+                ServerChange::PlayerLeft {
+                    name: "Player1".to_string()
+                },
+                ServerChange::PlayerLeft {
+                    name: "Player2".to_string()
+                },
+            ]
+        );
+
+        // Unknown targets are ignored.
+        assert!(monitor.record(1, Err(&error)).is_empty());
+    }
+
+    #[test]
+    fn unreachable() {
+        // Nothing is listening on this port.
+        let game = Game {
+            name: "Test".into(),
+            default_port: 1,
+            protocol: Protocol::Valve(Engine::new(440)),
+            request_settings: ExtraRequestSettings::default(),
+        };
+        let mut monitor = Monitor::new(vec![QueryTarget::new(
+            game,
+            SocketAddr::new([127, 0, 0, 1].into(), 1),
+        )])
+        .set_deadline(Duration::ZERO);
+
+        let events = monitor.poll();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0].change, ServerChange::WentOffline { .. }));
+    }
+}
@@ -8,7 +8,7 @@ use crate::protocols;
 use crate::protocols::gamespy::GameSpyVersion;
 use crate::protocols::quake::QuakeVersion;
 use crate::protocols::types::{CommonResponse, ExtraRequestSettings, ProprietaryProtocol, Protocol, TimeoutSettings};
-use crate::GDResult;
+use crate::{GDError, GDResult};
 
 /// Make a query given a game definition
 #[inline]
@@ -28,12 +28,34 @@ pub fn query_with_timeout(
 }
 
 /// Make a query given a game definition, timeout settings, and extra settings
+///
+/// Errors have the address of the server and the protocol of the game as
+/// their context.
 pub fn query_with_timeout_and_extra_settings(
     game: &Game,
     address: &IpAddr,
     port: Option<u16>,
     timeout_settings: Option<TimeoutSettings>,
     extra_settings: Option<ExtraRequestSettings>,
+) -> GDResult<Box<dyn CommonResponse>> {
+    query_game(game, address, port, timeout_settings, extra_settings)
+        .map_err(|error| with_query_context(error, game, address, port))
+}
+
+/// Add the address of the server and the protocol of the game to an error.
+fn with_query_context(error: GDError, game: &Game, address: &IpAddr, port: Option<u16>) -> GDError {
+    error
+        .with_address(SocketAddr::new(*address, port.unwrap_or(game.default_port)))
+        .with_protocol(game.protocol.clone())
+}
+
+/// Query a server with the protocol of its game.
+fn query_game(
+    game: &Game,
+    address: &IpAddr,
+    port: Option<u16>,
+    timeout_settings: Option<TimeoutSettings>,
+    extra_settings: Option<ExtraRequestSettings>,
 ) -> GDResult<Box<dyn CommonResponse>> {
     let socket_addr = SocketAddr::new(*address, port.unwrap_or(game.default_port));
     Ok(match &game.protocol {
@@ -129,3 +151,205 @@ pub fn query_with_timeout_and_extra_settings(
         }
     })
 }
+
+/// The async counterpart of [query].
+#[cfg(feature = "tokio")]
+#[inline]
+pub async fn query_async(game: &Game, address: &IpAddr, port: Option<u16>) -> GDResult<Box<dyn CommonResponse>> {
+    query_with_timeout_and_extra_settings_async(game, address, port, None, None).await
+}
+
+/// The async counterpart of [query_with_timeout].
+#[cfg(feature = "tokio")]
+#[inline]
+pub async fn query_with_timeout_async(
+    game: &Game,
+    address: &IpAddr,
+    port: Option<u16>,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Box<dyn CommonResponse>> {
+    query_with_timeout_and_extra_settings_async(game, address, port, timeout_settings, None).await
+}
+
+/// The async counterpart of [query_with_timeout_and_extra_settings].
+#[cfg(feature = "tokio")]
+pub async fn query_with_timeout_and_extra_settings_async(
+    game: &Game,
+    address: &IpAddr,
+    port: Option<u16>,
+    timeout_settings: Option<TimeoutSettings>,
+    extra_settings: Option<ExtraRequestSettings>,
+) -> GDResult<Box<dyn CommonResponse>> {
+    query_game_async(game, address, port, timeout_settings, extra_settings)
+        .await
+        .map_err(|error| with_query_context(error, game, address, port))
+}
+
+/// The async counterpart of [query_game].
+#[cfg(feature = "tokio")]
+async fn query_game_async(
+    game: &Game,
+    address: &IpAddr,
+    port: Option<u16>,
+    timeout_settings: Option<TimeoutSettings>,
+    extra_settings: Option<ExtraRequestSettings>,
+) -> GDResult<Box<dyn CommonResponse>> {
+    let socket_addr = SocketAddr::new(*address, port.unwrap_or(game.default_port));
+    Ok(match &game.protocol {
+        Protocol::Valve(engine) => {
+            protocols::valve::query_async(
+                &socket_addr,
+                *engine,
+                extra_settings
+                    .or_else(|| Option::from(game.request_settings.clone()))
+                    .map(ExtraRequestSettings::into),
+                timeout_settings,
+            )
+            .await
+            .map(Box::new)?
+        }
+        #[cfg(feature = "tls")]
+        Protocol::Epic(credentials) => {
+            protocols::epic::query_with_timeout_async(credentials.clone(), &socket_addr, timeout_settings)
+                .await
+                .map(Box::new)?
+        }
+        Protocol::Gamespy(version) => {
+            match version {
+                GameSpyVersion::One => {
+                    protocols::gamespy::one::query_async(&socket_addr, timeout_settings)
+                        .await
+                        .map(Box::new)?
+                }
+                GameSpyVersion::Two => {
+                    protocols::gamespy::two::query_async(&socket_addr, timeout_settings)
+                        .await
+                        .map(Box::new)?
+                }
+                GameSpyVersion::Three => {
+                    protocols::gamespy::three::query_async(&socket_addr, timeout_settings)
+                        .await
+                        .map(Box::new)?
+                }
+            }
+        }
+        Protocol::Quake(version) => {
+            match version {
+                QuakeVersion::One => {
+                    protocols::quake::one::query_async(&socket_addr, timeout_settings)
+                        .await
+                        .map(Box::new)?
+                }
+                QuakeVersion::Two => {
+                    protocols::quake::two::query_async(&socket_addr, timeout_settings)
+                        .await
+                        .map(Box::new)?
+                }
+                QuakeVersion::Three => {
+                    protocols::quake::three::query_async(&socket_addr, timeout_settings)
+                        .await
+                        .map(Box::new)?
+                }
+            }
+        }
+        Protocol::Unreal2 => {
+            protocols::unreal2::query_async(
+                &socket_addr,
+                &extra_settings
+                    .map(ExtraRequestSettings::into)
+                    .unwrap_or_default(),
+                timeout_settings,
+            )
+            .await
+            .map(Box::new)?
+        }
+        Protocol::PROPRIETARY(protocol) => {
+            match protocol {
+                ProprietaryProtocol::Savage2 => {
+                    savage2::query_with_timeout_async(address, port, timeout_settings)
+                        .await
+                        .map(Box::new)?
+                }
+                ProprietaryProtocol::TheShip => {
+                    theship::query_with_timeout_async(address, port, timeout_settings)
+                        .await
+                        .map(Box::new)?
+                }
+                ProprietaryProtocol::FFOW => {
+                    ffow::query_with_timeout_async(address, port, timeout_settings)
+                        .await
+                        .map(Box::new)?
+                }
+                ProprietaryProtocol::JC2M => {
+                    jc2m::query_with_timeout_async(address, port, timeout_settings)
+                        .await
+                        .map(Box::new)?
+                }
+                ProprietaryProtocol::Mindustry => {
+                    mindustry::query_async(address, port, &timeout_settings)
+                        .await
+                        .map(Box::new)?
+                }
+                ProprietaryProtocol::Minecraft(version) => {
+                    match version {
+                        Some(minecraft::Server::Java) => {
+                            minecraft::protocol::query_java_async(
+                                &socket_addr,
+                                timeout_settings,
+                                extra_settings.map(ExtraRequestSettings::into),
+                            )
+                            .await
+                            .map(Box::new)?
+                        }
+                        Some(minecraft::Server::Bedrock) => {
+                            minecraft::protocol::query_bedrock_async(&socket_addr, timeout_settings)
+                                .await
+                                .map(Box::new)?
+                        }
+                        Some(minecraft::Server::Legacy(group)) => {
+                            minecraft::protocol::query_legacy_specific_async(*group, &socket_addr, timeout_settings)
+                                .await
+                                .map(Box::new)?
+                        }
+                        None => {
+                            minecraft::protocol::query_async(
+                                &socket_addr,
+                                timeout_settings,
+                                extra_settings.map(ExtraRequestSettings::into),
+                            )
+                            .await
+                            .map(Box::new)?
+                        }
+                    }
+                }
+                ProprietaryProtocol::Eco => {
+                    eco::query_with_timeout_and_extra_settings_async(
+                        address,
+                        port,
+                        &timeout_settings,
+                        extra_settings.map(ExtraRequestSettings::into),
+                    )
+                    .await
+                    .map(Box::new)?
+                }
+            }
+        }
+    })
+}
+
+#[cfg(all(test, feature = "tokio", feature = "game_defs"))]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>(_: T) {}
+
+    #[test]
+    fn query_async_future_is_send() {
+        let game = crate::GAMES.get("teamfortress2").unwrap();
+        let address = "127.0.0.1".parse().unwrap();
+
+        // Not awaited, only checking that the future can be spawned on a
+        // multi-threaded runtime.
+        assert_send(query_async(game, &address, None));
+    }
+}
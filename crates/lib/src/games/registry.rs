@@ -0,0 +1,367 @@
+//! Game definitions that can be extended at runtime
+
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+use crate::games::lookup::{ids_by_app_id, search_ids};
+use crate::games::query::query_with_timeout_and_extra_settings;
+use crate::games::types::Game;
+use crate::protocols::types::{CommonResponse, ExtraRequestSettings, TimeoutSettings};
+use crate::GDErrorKind::InvalidInput;
+use crate::GDResult;
+
+#[cfg(feature = "serde")]
+use crate::GDError;
+#[cfg(feature = "serde")]
+use crate::GDErrorKind::JsonParse;
+
+#[cfg(feature = "game_defs")]
+use crate::games::{ALIASES, GAMES};
+
+/// A set of game definitions (by their ids), that starts from the
+/// [built-in ones](GAMES) and to which games can be added at runtime.
+///
+/// ```
+/// use gamedig::protocols::{types::Protocol, valve::Engine};
+/// use gamedig::{ExtraRequestSettings, Game, GameRegistry};
+///
+/// let mut registry = GameRegistry::new();
+/// registry.register(
+///     "mygame",
+///     Game {
+///         name: "My Game".into(),
+///         default_port: 27015,
+///         protocol: Protocol::Valve(Engine::new(123456)),
+///         request_settings: ExtraRequestSettings::default(),
+///     },
+/// );
+///
+/// assert_eq!(registry.get("mygame").unwrap().name, "My Game");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameRegistry {
+    games: BTreeMap<String, Game>,
+}
+
+impl GameRegistry {
+    /// Make a registry with the built-in game definitions.
+    #[cfg(feature = "game_defs")]
+    pub fn new() -> Self {
+        Self {
+            games: GAMES
+                .entries()
+                .map(|(id, game)| (id.to_string(), game.clone()))
+                .collect(),
+        }
+    }
+
+    /// Make a registry without any game definitions.
+    pub fn empty() -> Self { Self::default() }
+
+    /// Add a game, returning the one that was using the id before (if any).
+    pub fn register<S: Into<String>>(&mut self, id: S, game: Game) -> Option<Game> {
+        self.games.insert(id.into(), game)
+    }
+
+    /// Remove a game by its id.
+    pub fn unregister(&mut self, id: &str) -> Option<Game> { self.games.remove(id) }
+
+    /// Get a game by its id.
+    pub fn get(&self, id: &str) -> Option<&Game> { self.games.get(id) }
+
+    /// Whether a game is using the id.
+    pub fn contains(&self, id: &str) -> bool { self.games.contains_key(id) }
+
+    /// Iterate over the games and their ids (sorted by id).
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Game)> {
+        self.games.iter().map(|(id, game)| (id.as_str(), game))
+    }
+
+    /// Number of games.
+    pub fn len(&self) -> usize { self.games.len() }
+
+    /// Whether there are no games.
+    pub fn is_empty(&self) -> bool { self.games.is_empty() }
+
+    /// Get a game by its id or [alias](Self::resolve), or an [InvalidInput]
+    /// error if there is none.
+    pub fn find(&self, id: &str) -> GDResult<&Game> {
+        self.resolve(id)
+            .and_then(|id| self.get(id))
+            .ok_or_else(|| InvalidInput.context(format!("Unknown game id: {id}")))
+    }
+
+    /// Get the id of a game from its id or, if no game is using it, from one
+    /// of the [built-in aliases](crate::games::ALIASES), ignoring case if
+    /// nothing matches exactly.
+    pub fn resolve(&self, id_or_alias: &str) -> Option<&str> {
+        self.resolve_exact(id_or_alias)
+            .or_else(|| self.resolve_exact(&id_or_alias.to_lowercase()))
+    }
+
+    fn resolve_exact(&self, id_or_alias: &str) -> Option<&str> {
+        if let Some((id, _)) = self.games.get_key_value(id_or_alias) {
+            return Some(id);
+        }
+
+        #[cfg(feature = "game_defs")]
+        if let Some(id) = ALIASES.get(id_or_alias) {
+            return self.games.get_key_value(*id).map(|(id, _)| id.as_str());
+        }
+
+        None
+    }
+
+    /// Ids of the games that use a Steam app id, either as their main or
+    /// dedicated server app id (sorted).
+    pub fn find_by_app_id(&self, app_id: u32) -> Vec<&str> { ids_by_app_id(self.iter(), app_id) }
+
+    /// Search the games by id, name or alias, the best matches first (see
+    /// [search_games](crate::games::search_games) for how they are ranked).
+    pub fn search(&self, query: &str) -> Vec<&str> { search_ids(self.iter(), query, self.resolve(query)) }
+
+    /// Make a query given a game id.
+    #[inline]
+    pub fn query(&self, id: &str, address: &IpAddr, port: Option<u16>) -> GDResult<Box<dyn CommonResponse>> {
+        self.query_with_timeout_and_extra_settings(id, address, port, None, None)
+    }
+
+    /// Make a query given a game id and timeout settings.
+    #[inline]
+    pub fn query_with_timeout(
+        &self,
+        id: &str,
+        address: &IpAddr,
+        port: Option<u16>,
+        timeout_settings: Option<TimeoutSettings>,
+    ) -> GDResult<Box<dyn CommonResponse>> {
+        self.query_with_timeout_and_extra_settings(id, address, port, timeout_settings, None)
+    }
+
+    /// Make a query given a game id, timeout settings, and extra settings.
+    pub fn query_with_timeout_and_extra_settings(
+        &self,
+        id: &str,
+        address: &IpAddr,
+        port: Option<u16>,
+        timeout_settings: Option<TimeoutSettings>,
+        extra_settings: Option<ExtraRequestSettings>,
+    ) -> GDResult<Box<dyn CommonResponse>> {
+        query_with_timeout_and_extra_settings(
+            self.find(id)?,
+            address,
+            port,
+            timeout_settings,
+            extra_settings,
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl GameRegistry {
+    /// Register the games of a definitions file: a map of ids to games (in the
+    /// same schema as [Game], `request_settings` being optional), for example:
+    ///
+    /// ```json
+    /// {
+    ///     "mygame": {
+    ///         "name": "My Game",
+    ///         "default_port": 27015,
+    ///         "protocol": { "Valve": { "Source": [123456, null] } }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Every entry is checked on its own, the valid ones are registered (over
+    /// the existing ones) and the errors of the invalid ones are returned
+    /// along with their ids.
+    pub fn register_definitions(&mut self, definitions: serde_json::Value) -> GDResult<Vec<(String, GDError)>> {
+        let serde_json::Value::Object(definitions) = definitions else {
+            return Err(JsonParse.context("Expected the definitions to be a map of ids to games"));
+        };
+
+        let mut errors = Vec::new();
+        for (id, definition) in definitions {
+            match parse_definition(&id, definition) {
+                Ok(game) => {
+                    self.register(id, game);
+                }
+                Err(e) => errors.push((id, e)),
+            }
+        }
+
+        Ok(errors)
+    }
+
+    /// Register the games of a JSON definitions file's content, see
+    /// [GameRegistry::register_definitions].
+    pub fn register_json_definitions(&mut self, json: &str) -> GDResult<Vec<(String, GDError)>> {
+        self.register_definitions(serde_json::from_str(json).map_err(|e| JsonParse.context(e))?)
+    }
+}
+
+/// Parse and check a single game definition.
+#[cfg(feature = "serde")]
+fn parse_definition(id: &str, definition: serde_json::Value) -> GDResult<Game> {
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    {
+        return Err(InvalidInput.context("The id must only contain lowercase letters and digits"));
+    }
+
+    let game: Game = serde_json::from_value(definition).map_err(|e| JsonParse.context(e))?;
+
+    if game.name.trim().is_empty() {
+        return Err(InvalidInput.context("The name is empty"));
+    }
+
+    if game.default_port == 0 {
+        return Err(InvalidInput.context("The default port is 0"));
+    }
+
+    Ok(game)
+}
+
+impl<S: Into<String>> Extend<(S, Game)> for GameRegistry {
+    fn extend<T: IntoIterator<Item = (S, Game)>>(&mut self, games: T) {
+        self.games
+            .extend(games.into_iter().map(|(id, game)| (id.into(), game)));
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, Game)> for GameRegistry {
+    fn from_iter<T: IntoIterator<Item = (S, Game)>>(games: T) -> Self {
+        let mut registry = Self::empty();
+        registry.extend(games);
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocols::{types::Protocol, valve::Engine};
+
+    fn game(name: &str) -> Game {
+        Game {
+            name: name.to_string().into(),
+            default_port: 27015,
+            protocol: Protocol::Valve(Engine::new(123456)),
+            request_settings: ExtraRequestSettings::default(),
+        }
+    }
+
+    #[test]
+    fn register() {
+        let mut registry = GameRegistry::empty();
+        assert!(registry.is_empty());
+
+        assert_eq!(registry.register("mygame", game("My Game")), None);
+        assert_eq!(
+            registry.register("mygame", game("My Other Game")),
+            Some(game("My Game"))
+        );
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.get("mygame"), Some(&game("My Other Game")));
+
+        assert_eq!(registry.unregister("mygame"), Some(game("My Other Game")));
+        assert!(!registry.contains("mygame"));
+    }
+
+    #[test]
+    fn unknown_game() {
+        let registry = GameRegistry::empty();
+
+        assert!(matches!(registry.find("mygame"), Err(e) if e.kind == InvalidInput));
+        assert!(registry
+            .query("mygame", &"127.0.0.1".parse().unwrap(), None)
+            .is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn definitions() {
+        let mut registry = GameRegistry::empty();
+        let errors = registry
+            .register_json_definitions(
+                r#"{
+                    "mygame": {
+                        "name": "My Game",
+                        "default_port": 27015,
+                        "protocol": { "Valve": { "Source": [123456, null] } }
+                    },
+                    "noport": { "name": "No Port", "protocol": "Unreal2" },
+                    "zeroport": { "name": "Zero Port", "default_port": 0, "protocol": "Unreal2" },
+                    "Bad-Id": { "name": "Bad Id", "default_port": 7778, "protocol": "Unreal2" }
+                }"#,
+            )
+            .unwrap();
+
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.get("mygame"), Some(&game("My Game")));
+
+        let mut failed: Vec<_> = errors
+            .iter()
+            .map(|(id, e)| (id.as_str(), e.kind.clone()))
+            .collect();
+        failed.sort_unstable_by_key(|(id, _)| *id);
+        assert_eq!(
+            failed,
+            [
+                ("Bad-Id", InvalidInput),
+                ("noport", JsonParse),
+                ("zeroport", InvalidInput)
+            ]
+        );
+
+        assert!(registry.register_json_definitions("[]").is_err());
+    }
+
+    #[cfg(feature = "game_defs")]
+    #[test]
+    fn built_in_games() {
+        let mut registry = GameRegistry::new();
+        assert_eq!(registry.len(), GAMES.len());
+        assert_eq!(registry.get("teamfortress2"), GAMES.get("teamfortress2"));
+
+        // Built-in games can be overridden.
+        registry.register("teamfortress2", game("My Game"));
+        assert_eq!(registry.get("teamfortress2").unwrap().name, "My Game");
+    }
+
+    #[test]
+    fn lookup() {
+        let mut registry = GameRegistry::empty();
+        registry.register("mygame", game("My Game"));
+        registry.register("myothergame", game("My Other Game"));
+
+        assert_eq!(registry.find_by_app_id(123456), ["mygame", "myothergame"]);
+        assert!(registry.find_by_app_id(440).is_empty());
+        assert_eq!(registry.search("my other"), ["myothergame"]);
+        assert_eq!(registry.search("MyGame"), ["mygame", "myothergame"]);
+        assert_eq!(registry.resolve("mygame"), Some("mygame"));
+        assert_eq!(registry.resolve("MyGame"), Some("mygame"));
+    }
+
+    #[cfg(feature = "game_defs")]
+    #[test]
+    fn aliases() {
+        let mut registry = GameRegistry::new();
+        assert_eq!(registry.resolve("tf2"), Some("teamfortress2"));
+        assert_eq!(
+            registry.find("tf2"),
+            Ok(GAMES.get("teamfortress2").unwrap())
+        );
+        assert_eq!(registry.search("TF2")[0], "teamfortress2");
+        assert_eq!(registry.resolve("TF2"), Some("teamfortress2"));
+
+        // Games take precedence over aliases, which need their game to exist.
+        registry.register("tf2", game("My Game"));
+        assert_eq!(registry.resolve("tf2"), Some("tf2"));
+        registry.unregister("teamfortress2");
+        registry.unregister("tf2");
+        assert_eq!(registry.resolve("tf2"), None);
+    }
+}
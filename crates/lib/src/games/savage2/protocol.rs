@@ -1,6 +1,8 @@
 use crate::buffer::{Buffer, Utf8Decoder};
 use crate::games::savage2::types::Response;
 use crate::protocols::types::TimeoutSettings;
+#[cfg(feature = "tokio")]
+use crate::socket::AsyncUdpSocket;
 use crate::socket::{Socket, UdpSocket};
 use crate::GDResult;
 use byteorder::LittleEndian;
@@ -17,7 +19,34 @@ pub fn query_with_timeout(
     let mut socket = UdpSocket::new(addr, &timeout_settings)?;
     socket.send(&[0x01])?;
     let data = socket.receive(None)?;
-    let mut buffer = Buffer::<LittleEndian>::new(&data);
+
+    Ok(Response {
+        ping: socket.ping(),
+        ..parse_response(&data)?
+    })
+}
+
+/// The async counterpart of [query_with_timeout].
+#[cfg(feature = "tokio")]
+pub async fn query_with_timeout_async(
+    address: &IpAddr,
+    port: Option<u16>,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Response> {
+    let addr = &SocketAddr::new(*address, port.unwrap_or(11235));
+    let mut socket = AsyncUdpSocket::new(addr, &timeout_settings).await?;
+    socket.send(&[0x01]).await?;
+    let data = socket.receive(None).await?;
+
+    Ok(Response {
+        ping: socket.ping(),
+        ..parse_response(&data)?
+    })
+}
+
+/// Parse a response packet.
+pub fn parse_response(data: &[u8]) -> GDResult<Response> {
+    let mut buffer = Buffer::<LittleEndian>::new(data);
 
     buffer.move_cursor(12)?;
 
@@ -33,5 +62,6 @@ pub fn query_with_timeout(
         game_mode: buffer.read_string::<Utf8Decoder>(None)?,
         protocol_version: buffer.read_string::<Utf8Decoder>(None)?,
         level_minimum: buffer.read::<u8>()?,
+        ping: None,
     })
 }
@@ -2,6 +2,7 @@ use crate::protocols::types::CommonResponse;
 use crate::protocols::GenericResponse;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -17,14 +18,17 @@ pub struct Response {
     pub game_mode: String,
     pub protocol_version: String,
     pub level_minimum: u8,
+    /// Round-trip time of the first request.
+    pub ping: Option<Duration>,
 }
 
 impl CommonResponse for Response {
-    fn as_original(&self) -> GenericResponse { GenericResponse::Savage2(self) }
+    fn as_original(&self) -> GenericResponse<'_> { GenericResponse::Savage2(self) }
 
     fn name(&self) -> Option<&str> { Some(&self.name) }
     fn game_mode(&self) -> Option<&str> { Some(&self.game_mode) }
     fn map(&self) -> Option<&str> { Some(&self.map) }
     fn players_maximum(&self) -> u32 { self.players_maximum.into() }
     fn players_online(&self) -> u32 { self.players_online.into() }
+    fn ping(&self) -> Option<Duration> { self.ping }
 }
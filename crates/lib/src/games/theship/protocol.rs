@@ -21,3 +21,21 @@ pub fn query_with_timeout(
 
     Response::new_from_valve_response(valve_response)
 }
+
+/// The async counterpart of [query_with_timeout].
+#[cfg(feature = "tokio")]
+pub async fn query_with_timeout_async(
+    address: &IpAddr,
+    port: Option<u16>,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Response> {
+    let valve_response = valve::query_async(
+        &SocketAddr::new(*address, port.unwrap_or(27015)),
+        Engine::new(2400),
+        None,
+        timeout_settings,
+    )
+    .await?;
+
+    Response::new_from_valve_response(valve_response)
+}
@@ -1,9 +1,10 @@
 use crate::protocols::types::{CommonPlayer, CommonResponse, GenericPlayer};
-use crate::protocols::valve::{get_optional_extracted_data, Server, ServerPlayer};
+use crate::protocols::valve::{duration_from_secs, get_optional_extracted_data, Server, ServerPlayer};
 use crate::protocols::{valve, GenericResponse};
 use crate::GDErrorKind::PacketBad;
 use crate::GDResult;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -31,10 +32,14 @@ impl TheShipPlayer {
 }
 
 impl CommonPlayer for TheShipPlayer {
-    fn as_original(&self) -> GenericPlayer { GenericPlayer::TheShip(self) }
+    fn as_original(&self) -> GenericPlayer<'_> { GenericPlayer::TheShip(self) }
 
     fn name(&self) -> &str { &self.name }
     fn score(&self) -> Option<i32> { Some(self.score) }
+    fn time_connected(&self) -> Option<Duration> { duration_from_secs(self.duration) }
+    fn deaths(&self) -> Option<u32> { Some(self.deaths) }
+
+    fn extra(&self) -> BTreeMap<String, String> { BTreeMap::from([("money".to_string(), self.money.to_string())]) }
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -61,10 +66,12 @@ pub struct Response {
     pub mode: u8,
     pub witnesses: u8,
     pub duration: u8,
+    /// Round-trip time of the first request.
+    pub ping: Option<Duration>,
 }
 
 impl CommonResponse for Response {
-    fn as_original(&self) -> GenericResponse { GenericResponse::TheShip(self) }
+    fn as_original(&self) -> GenericResponse<'_> { GenericResponse::TheShip(self) }
 
     fn name(&self) -> Option<&str> { Some(&self.name) }
     fn map(&self) -> Option<&str> { Some(&self.map) }
@@ -73,6 +80,7 @@ impl CommonResponse for Response {
     fn players_online(&self) -> u32 { self.players_online.into() }
     fn players_bots(&self) -> Option<u32> { Some(self.players_bots.into()) }
     fn has_password(&self) -> Option<bool> { Some(self.has_password) }
+    fn ping(&self) -> Option<Duration> { self.ping }
 
     fn players(&self) -> Option<Vec<&dyn CommonPlayer>> {
         Some(
@@ -117,6 +125,7 @@ impl Response {
             mode: the_unwrapped_ship.mode,
             witnesses: the_unwrapped_ship.witnesses,
             duration: the_unwrapped_ship.duration,
+            ping: response.ping,
         })
     }
 }
@@ -2,19 +2,22 @@
 
 use crate::protocols::types::{ExtraRequestSettings, Protocol};
 
+use std::borrow::Cow;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// Definition of a game
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Game {
     /// Full name of the game
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     /// Default port used by game
     pub default_port: u16,
     /// The protocol the game's query uses
     pub protocol: Protocol,
     /// Request settings.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub request_settings: ExtraRequestSettings,
 }
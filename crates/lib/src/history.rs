@@ -0,0 +1,385 @@
+//! A history of the states of servers, stored in an append-only file
+//!
+//! Every query of a server is recorded (as its [owned
+//! response](CommonResponseOwned), or as a failure) under the game id and
+//! address of the server, a history can then be queried over a time range for
+//! the entries, the uptime, the players per hour and the maps that were played.
+//!
+//! ```no_run
+//! use gamedig::history::History;
+//! use std::time::{Duration, SystemTime};
+//! # use gamedig::protocols::types::CommonResponse;
+//! # fn record(response: Option<&dyn CommonResponse>) -> gamedig::GDResult<()> {
+//!
+//! let mut history = History::open("history.jsonl")?;
+//!
+//! let address = "127.0.0.1:27015".parse().unwrap();
+//! history.record("teamfortress2", address, response.map(|r| r.as_owned()))?;
+//!
+//! let last_day = SystemTime::now() - Duration::from_secs(24 * 60 * 60) ..;
+//! println!("{:?}", history.server("teamfortress2", address, last_day).uptime());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, Write};
+use std::net::SocketAddr;
+use std::ops::RangeBounds;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::protocols::types::CommonResponseOwned;
+use crate::GDErrorKind::{FileAccess, JsonParse};
+use crate::GDResult;
+
+const HOUR: u64 = 60 * 60;
+
+/// A recorded query of a server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// The game id.
+    pub game: String,
+    pub address: SocketAddr,
+    /// When the server was queried.
+    pub time: SystemTime,
+    /// None if the query failed.
+    pub response: Option<CommonResponseOwned>,
+}
+
+/// The states of servers, optionally stored in a file (one JSON entry per
+/// line, only ever appended to).
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+    file: Option<File>,
+}
+
+impl History {
+    /// A history that is only kept in memory.
+    pub fn new() -> Self { Self::default() }
+
+    /// Open (or create) a history file, reading its entries.
+    ///
+    /// An incomplete last line (from an interrupted write) is removed, any
+    /// other invalid line is a [JsonParse] error.
+    pub fn open<P: AsRef<Path>>(path: P) -> GDResult<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .map_err(|e| FileAccess.context(e))?;
+
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .map_err(|e| FileAccess.context(e))?;
+
+        let complete = content.rfind('\n').map_or(0, |end| end + 1);
+        if complete < content.len() {
+            file.set_len(complete as u64)
+                .and_then(|_| file.seek(std::io::SeekFrom::End(0)))
+                .map_err(|e| FileAccess.context(e))?;
+        }
+
+        let entries = content[.. complete]
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|e| JsonParse.context(format!("Line {}: {e}", index + 1)))
+            })
+            .collect::<GDResult<_>>()?;
+
+        Ok(Self {
+            entries,
+            file: Some(file),
+        })
+    }
+
+    /// Record a query of a server made now, `response` being None if it
+    /// failed.
+    pub fn record(&mut self, game: &str, address: SocketAddr, response: Option<CommonResponseOwned>) -> GDResult<()> {
+        self.record_at(game, address, SystemTime::now(), response)
+    }
+
+    /// Record a query of a server made at a given time.
+    pub fn record_at(
+        &mut self,
+        game: &str,
+        address: SocketAddr,
+        time: SystemTime,
+        response: Option<CommonResponseOwned>,
+    ) -> GDResult<()> {
+        let entry = HistoryEntry {
+            game: game.to_string(),
+            address,
+            time,
+            response,
+        };
+
+        if let Some(file) = &mut self.file {
+            let mut line = serde_json::to_string(&entry).map_err(|e| JsonParse.context(e))?;
+            line.push('\n');
+            // A single write so that an interruption can only leave an
+            // incomplete last line.
+            file.write_all(line.as_bytes())
+                .and_then(|_| file.flush())
+                .map_err(|e| FileAccess.context(e))?;
+        }
+
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// All the entries, in the order they were recorded.
+    pub fn entries(&self) -> &[HistoryEntry] { &self.entries }
+
+    /// The recorded servers (game id and address), sorted.
+    pub fn servers(&self) -> Vec<(&str, SocketAddr)> {
+        let mut servers: Vec<(&str, SocketAddr)> = self
+            .entries
+            .iter()
+            .map(|entry| (entry.game.as_str(), entry.address))
+            .collect();
+        servers.sort_unstable();
+        servers.dedup();
+        servers
+    }
+
+    /// The history of a server over a time range (such as `start .. end` or
+    /// `start ..`).
+    pub fn server<R: RangeBounds<SystemTime>>(&self, game: &str, address: SocketAddr, range: R) -> ServerHistory<'_> {
+        let mut entries: Vec<&HistoryEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.game == game && entry.address == address && range.contains(&entry.time))
+            .collect();
+        entries.sort_by_key(|entry| entry.time);
+
+        ServerHistory { entries }
+    }
+}
+
+/// The players of a server during an hour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HourlyPlayers {
+    /// The start of the hour.
+    pub hour: SystemTime,
+    pub peak: u32,
+    pub average: f64,
+    /// Number of (successful) queries during the hour.
+    pub samples: usize,
+}
+
+/// A map that was played on a server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapPeriod {
+    pub map: Option<String>,
+    /// When the map was first seen.
+    pub from: SystemTime,
+    /// When the map was last seen.
+    pub to: SystemTime,
+}
+
+/// The history of a server over a time range (see [History::server]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerHistory<'a> {
+    /// The entries, by time.
+    pub entries: Vec<&'a HistoryEntry>,
+}
+
+impl<'a> ServerHistory<'a> {
+    /// The responses, by time.
+    pub fn responses(&self) -> impl Iterator<Item = (SystemTime, &'a CommonResponseOwned)> + '_ {
+        self.entries.iter().filter_map(|entry| {
+            entry
+                .response
+                .as_ref()
+                .map(|response| (entry.time, response))
+        })
+    }
+
+    /// The percentage (0 to 100) of the queries that succeeded, None if there
+    /// are none.
+    pub fn uptime(&self) -> Option<f64> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let online = self.responses().count();
+        Some(online as f64 * 100. / self.entries.len() as f64)
+    }
+
+    /// The peak and average players of every (UTC) hour with successful
+    /// queries, by time.
+    pub fn hourly_players(&self) -> Vec<HourlyPlayers> {
+        let mut hours: Vec<(u64, Vec<u32>)> = Vec::new();
+
+        for (time, response) in self.responses() {
+            let seconds = time
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs());
+            let hour = seconds - seconds % HOUR;
+
+            match hours.last_mut() {
+                Some((last, players)) if *last == hour => players.push(response.players_online),
+                _ => hours.push((hour, vec![response.players_online])),
+            }
+        }
+
+        hours
+            .into_iter()
+            .map(|(hour, players)| {
+                HourlyPlayers {
+                    hour: UNIX_EPOCH + Duration::from_secs(hour),
+                    peak: players.iter().copied().max().unwrap_or(0),
+                    average: players.iter().map(|p| f64::from(*p)).sum::<f64>() / players.len() as f64,
+                    samples: players.len(),
+                }
+            })
+            .collect()
+    }
+
+    /// The maps that were played (from the successful queries), by time.
+    pub fn map_rotation(&self) -> Vec<MapPeriod> {
+        let mut maps: Vec<MapPeriod> = Vec::new();
+
+        for (time, response) in self.responses() {
+            match maps.last_mut() {
+                Some(last) if last.map == response.map => last.to = time,
+                _ => {
+                    maps.push(MapPeriod {
+                        map: response.map.clone(),
+                        from: time,
+                        to: time,
+                    })
+                }
+            }
+        }
+
+        maps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::response;
+
+    fn at(seconds: u64) -> SystemTime { UNIX_EPOCH + Duration::from_secs(seconds) }
+
+    fn history() -> History {
+        let address = SocketAddr::new([127, 0, 0, 1].into(), 7778);
+        let mut history = History::new();
+        for (seconds, response) in [
+            (0, Some(response("DM-Deck17", 4))),
+            (600, Some(response("DM-Deck17", 8))),
+            (1200, None),
+            (3600, Some(response("CTF-Face", 10))),
+            (4200, Some(response("DM-Deck17", 2))),
+        ] {
+            history
+                .record_at("ut2004", address, at(seconds), response)
+                .unwrap();
+        }
+        history.record_at("ut2003", address, at(0), None).unwrap();
+
+        history
+    }
+
+    #[test]
+    fn statistics() {
+        let history = history();
+        let address = SocketAddr::new([127, 0, 0, 1].into(), 7778);
+        assert_eq!(
+            history.servers(),
+            [("ut2003", address), ("ut2004", address)]
+        );
+
+        let server = history.server("ut2004", address, ..);
+        assert_eq!(server.entries.len(), 5);
+        assert_eq!(server.uptime(), Some(80.));
+
+        assert_eq!(
+            server.hourly_players(),
+            [
+                HourlyPlayers {
+                    hour: at(0),
+                    peak: 8,
+                    average: 6.,
+                    samples: 2,
+                },
+                HourlyPlayers {
+                    hour: at(3600),
+                    peak: 10,
+                    average: 6.,
+                    samples: 2,
+                },
+            ]
+        );
+
+        let maps: Vec<_> = server
+            .map_rotation()
+            .into_iter()
+            .map(|period| (period.map.unwrap(), period.from, period.to))
+            .collect();
+        assert_eq!(
+            maps,
+            [
+                ("DM-Deck17".to_string(), at(0), at(600)),
+                ("CTF-Face".to_string(), at(3600), at(3600)),
+                ("DM-Deck17".to_string(), at(4200), at(4200)),
+            ]
+        );
+
+        // Time ranges.
+        assert_eq!(
+            history
+                .server("ut2004", address, at(600) .. at(3600))
+                .uptime(),
+            Some(50.)
+        );
+        assert_eq!(
+            history.server("ut2004", address, at(5000) ..).uptime(),
+            None
+        );
+    }
+
+    #[test]
+    fn file() {
+        let path = std::env::temp_dir().join(format!("gamedig-history-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut history = History::open(&path).unwrap();
+        for entry in self::history().entries() {
+            history
+                .record_at(
+                    &entry.game,
+                    entry.address,
+                    entry.time,
+                    entry.response.clone(),
+                )
+                .unwrap();
+        }
+
+        // An interrupted write.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"game\":").unwrap();
+
+        let mut reopened = History::open(&path).unwrap();
+        assert_eq!(reopened.entries(), history.entries());
+
+        reopened
+            .record_at("ut2004", history.entries()[0].address, at(7200), None)
+            .unwrap();
+        assert_eq!(History::open(&path).unwrap().entries().len(), 7);
+
+        std::fs::write(&path, "not json\n").unwrap();
+        assert!(matches!(History::open(&path), Err(e) if e.kind == JsonParse));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
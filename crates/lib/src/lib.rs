@@ -35,16 +35,22 @@
 //! `game_defs` - include game definitions for programmatic access (enabled by
 //! default). <br>
 //! `clap` - enable clap derivations for gamedig settings types. <br>
-//! `tls` - enable TLS support for the HTTP client.
+//! `tls` - enable TLS support for the HTTP client. <br>
+//! `tokio` - enable the async query functions (`query_async` and friends).
 
 pub mod errors;
 #[cfg(feature = "games")]
 pub mod games;
+#[cfg(feature = "serde")]
+pub mod history;
+pub mod metrics;
 pub mod protocols;
 #[cfg(feature = "services")]
 pub mod services;
 
 mod buffer;
+#[cfg(test)]
+mod fixtures;
 mod http;
 mod socket;
 mod utils;
@@ -55,8 +61,6 @@ pub mod capture;
 pub use errors::*;
 #[cfg(feature = "games")]
 pub use games::*;
-#[cfg(feature = "games")]
-pub use query::*;
 #[cfg(feature = "services")]
 pub use services::*;
 
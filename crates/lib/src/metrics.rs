@@ -0,0 +1,245 @@
+//! Time series formats of responses: InfluxDB line protocol and OpenMetrics
+//!
+//! Every response is a point (or sample) tagged by the game, the host and
+//! the map of its server, with the player counts as its fields.
+//!
+//! ```
+//! use gamedig::metrics::{to_influx_line, Series};
+//! # use gamedig::protocols::types::CommonResponse;
+//! # fn print(response: &dyn CommonResponse) {
+//! let series = Series::new("teamfortress2", "127.0.0.1:27015");
+//! // gamedig,game=teamfortress2,host=127.0.0.1:27015,map=ctf_2fort players_online=12i,players_max=32i,players_bots=0i
+//! println!("{}", to_influx_line(series, response, None));
+//! # }
+//! ```
+
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::protocols::types::CommonResponse;
+
+/// The name of the InfluxDB measurement and prefix of the OpenMetrics metrics.
+pub const MEASUREMENT: &str = "gamedig";
+
+/// What identifies the time series of a server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Series<'a> {
+    /// The game id.
+    pub game: &'a str,
+    /// The server, such as `127.0.0.1:27015`.
+    pub host: &'a str,
+}
+
+impl<'a> Series<'a> {
+    pub const fn new(game: &'a str, host: &'a str) -> Self { Self { game, host } }
+
+    /// The tags (name and value) of a response of the server, the map only
+    /// being one if it's known.
+    fn tags<'b>(&self, response: &'b dyn CommonResponse) -> Vec<(&'static str, &'b str)>
+    where 'a: 'b {
+        let mut tags = vec![("game", self.game), ("host", self.host)];
+        if let Some(map) = response.map().filter(|map| !map.is_empty()) {
+            tags.push(("map", map));
+        }
+
+        tags
+    }
+}
+
+/// A gauge of the OpenMetrics expositions (and a field of the InfluxDB
+/// points).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gauge {
+    /// The name, without the [MEASUREMENT] prefix.
+    pub name: &'static str,
+    pub help: &'static str,
+}
+
+impl Gauge {
+    /// The name of the metric (`gamedig_<name>`).
+    pub fn metric(&self) -> String { format!("{MEASUREMENT}_{}", self.name) }
+}
+
+pub const PLAYERS_ONLINE: Gauge = Gauge {
+    name: "players_online",
+    help: "Number of players online.",
+};
+
+pub const PLAYERS_MAX: Gauge = Gauge {
+    name: "players_max",
+    help: "Maximum number of players.",
+};
+
+pub const PLAYERS_BOTS: Gauge = Gauge {
+    name: "players_bots",
+    help: "Number of bots online.",
+};
+
+/// The fields (gauge and value) of a response, the unknown ones being
+/// skipped.
+fn fields(response: &dyn CommonResponse) -> Vec<(Gauge, u32)> {
+    let mut fields = vec![
+        (PLAYERS_ONLINE, response.players_online()),
+        (PLAYERS_MAX, response.players_maximum()),
+    ];
+    if let Some(bots) = response.players_bots() {
+        fields.push((PLAYERS_BOTS, bots));
+    }
+
+    fields
+}
+
+/// Escape an InfluxDB tag value (or measurement).
+fn escape_influx(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, ',' | '=' | ' ') {
+            escaped.push('\\');
+        }
+        // New lines would end the point.
+        escaped.push(if c == '\n' { ' ' } else { c });
+    }
+
+    escaped
+}
+
+/// Escape an OpenMetrics (or Prometheus) label value.
+pub fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Format a response as an InfluxDB line protocol point, with a nanoseconds
+/// timestamp if given (InfluxDB using the time it received the point
+/// otherwise).
+pub fn to_influx_line(series: Series, response: &dyn CommonResponse, timestamp: Option<SystemTime>) -> String {
+    let mut line = MEASUREMENT.to_string();
+    for (name, value) in series.tags(response) {
+        let _ = write!(line, ",{name}={}", escape_influx(value));
+    }
+
+    let fields: Vec<String> = fields(response)
+        .into_iter()
+        .map(|(gauge, value)| format!("{}={value}i", gauge.name))
+        .collect();
+    line.push(' ');
+    line.push_str(&fields.join(","));
+
+    if let Some(timestamp) = timestamp {
+        let nanoseconds = timestamp
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos());
+        let _ = write!(line, " {nanoseconds}");
+    }
+
+    line
+}
+
+/// A sample of a gauge: its labels (name and value) and value.
+pub type Sample<'a> = (Vec<(&'a str, &'a str)>, f64);
+
+/// Write a gauge of an OpenMetrics (or Prometheus) exposition: its type, help
+/// and samples.
+pub fn write_gauge(text: &mut String, gauge: &Gauge, samples: &[Sample]) {
+    let metric = gauge.metric();
+    let _ = writeln!(text, "# TYPE {metric} gauge");
+    let _ = writeln!(text, "# HELP {metric} {}", gauge.help);
+
+    for (labels, value) in samples {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(name, value)| format!("{name}=\"{}\"", escape_label(value)))
+            .collect();
+        let _ = writeln!(text, "{metric}{{{}}} {value}", labels.join(","));
+    }
+}
+
+/// Format responses as an OpenMetrics exposition: a gauge per field
+/// (`gamedig_players_online`...) with a sample per response.
+pub fn to_openmetrics<'a>(samples: &[(Series<'a>, &'a dyn CommonResponse)]) -> String {
+    // Fields in order of appearance, with their samples.
+    let mut gauges: Vec<(Gauge, Vec<Sample>)> = Vec::new();
+
+    for (series, response) in samples {
+        let labels = series.tags(*response);
+
+        for (gauge, value) in fields(*response) {
+            let sample = (labels.clone(), value.into());
+            match gauges.iter_mut().find(|(known, _)| *known == gauge) {
+                Some((_, gauge_samples)) => gauge_samples.push(sample),
+                None => gauges.push((gauge, vec![sample])),
+            }
+        }
+    }
+
+    let mut text = String::new();
+    for (gauge, gauge_samples) in gauges {
+        write_gauge(&mut text, &gauge, &gauge_samples);
+    }
+    text.push_str("# EOF\n");
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::response;
+    use std::time::Duration;
+
+    #[test]
+    fn influx_line() {
+        let mut response = response("ctf 2fort", 12);
+        response.players_bots = Some(2);
+
+        assert_eq!(
+            to_influx_line(
+                Series::new("teamfortress2", "127.0.0.1:27015"),
+                &response,
+                Some(UNIX_EPOCH + Duration::from_secs(1))
+            ),
+            "gamedig,game=teamfortress2,host=127.0.0.1:27015,map=ctf\\ 2fort \
+             players_online=12i,players_max=32i,players_bots=2i 1000000000"
+        );
+
+        response.map = None;
+        response.players_bots = None;
+        assert_eq!(
+            to_influx_line(Series::new("minecraft", "example.com"), &response, None),
+            "gamedig,game=minecraft,host=example.com players_online=12i,players_max=32i"
+        );
+    }
+
+    #[test]
+    fn openmetrics() {
+        let mut first = response("de_\"dust\"", 12);
+        first.players_bots = None;
+        let mut second = response("", 12);
+        second.map = None;
+        second.players_bots = Some(1);
+
+        let text = to_openmetrics(&[
+            (Series::new("csgo", "127.0.0.1:27015"), &first),
+            (Series::new("tf2", "127.0.0.1:27016"), &second),
+        ]);
+        assert_eq!(
+            text.lines().collect::<Vec<_>>(),
+            [
+                "# TYPE gamedig_players_online gauge",
+                "# HELP gamedig_players_online Number of players online.",
+                r#"gamedig_players_online{game="csgo",host="127.0.0.1:27015",map="de_\"dust\""} 12"#,
+                r#"gamedig_players_online{game="tf2",host="127.0.0.1:27016"} 12"#,
+                "# TYPE gamedig_players_max gauge",
+                "# HELP gamedig_players_max Maximum number of players.",
+                r#"gamedig_players_max{game="csgo",host="127.0.0.1:27015",map="de_\"dust\""} 32"#,
+                r#"gamedig_players_max{game="tf2",host="127.0.0.1:27016"} 32"#,
+                "# TYPE gamedig_players_bots gauge",
+                "# HELP gamedig_players_bots Number of bots online.",
+                r#"gamedig_players_bots{game="tf2",host="127.0.0.1:27016"} 1"#,
+                "# EOF",
+            ]
+        );
+    }
+}
@@ -51,6 +51,20 @@ macro_rules! game_query_fn {
                 &std::net::SocketAddr::new(*address, port.unwrap_or($default_port)),
             )
         }
+
+        #[doc = "The async counterpart of [query]."]
+        #[cfg(feature = "tokio")]
+        pub async fn query_async(
+            address: &std::net::IpAddr,
+            port: Option<u16>,
+        ) -> crate::GDResult<crate::protocols::epic::Response> {
+            crate::protocols::epic::query_with_timeout_async(
+                $credentials,
+                &std::net::SocketAddr::new(*address, port.unwrap_or($default_port)),
+                None,
+            )
+            .await
+        }
     };
 }
 
@@ -1,5 +1,7 @@
 use crate::http::HttpClient;
 use crate::protocols::epic::Response;
+#[cfg(feature = "tokio")]
+use crate::GDErrorKind::PacketReceive;
 use crate::GDErrorKind::{JsonParse, PacketBad};
 use crate::{GDResult, TimeoutSettings};
 use base64::prelude::BASE64_STANDARD;
@@ -10,10 +12,10 @@ use serde::Serialize;
 use serde_json::Value;
 use std::net::SocketAddr;
 
-const EPIC_API_ENDPOINT: &'static str = "https://api.epicgames.dev";
+const EPIC_API_ENDPOINT: &str = "https://api.epicgames.dev";
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Credentials {
     #[cfg_attr(feature = "serde", serde(skip_deserializing, skip_serializing))]
     pub deployment: &'static str,
@@ -69,7 +71,7 @@ impl EpicProtocol {
     pub fn auth_by_client(&mut self) -> GDResult<String> {
         let body = [
             ("grant_type", "client_credentials"),
-            ("deployment_id", &self.credentials.deployment),
+            ("deployment_id", self.credentials.deployment),
         ];
 
         let auth_format = format!("{}:{}", self.credentials.id, self.credentials.secret);
@@ -176,6 +178,24 @@ pub fn query_with_timeout(
     address: &SocketAddr,
     timeout_settings: Option<TimeoutSettings>,
 ) -> GDResult<Response> {
-    let mut client = EpicProtocol::new(credentials, timeout_settings.unwrap_or_default())?;
-    client.query(address)
+    EpicProtocol::new(credentials, timeout_settings.unwrap_or_default())
+        .and_then(|mut client| client.query(address))
+        .map_err(|e| e.with_address(*address))
+}
+
+/// The async counterpart of [query_with_timeout].
+///
+/// The HTTP client is blocking, so the query is run on tokio's blocking
+/// thread pool.
+#[cfg(feature = "tokio")]
+pub async fn query_with_timeout_async(
+    credentials: Credentials,
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Response> {
+    let address = *address;
+
+    tokio::task::spawn_blocking(move || query_with_timeout(credentials, &address, timeout_settings))
+        .await
+        .map_err(|e| PacketReceive.context(e).with_address(address))?
 }
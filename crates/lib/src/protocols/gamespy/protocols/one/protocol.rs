@@ -14,36 +14,32 @@ use crate::{
     socket::{Socket, UdpSocket},
     GDErrorKind,
     GDResult,
+    QueryStage,
 };
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::time::Duration;
 
-/// Send status request, and parse response into HashMap.
-/// This function will retry fetch on timeouts.
-fn get_server_values(
-    address: &SocketAddr,
-    timeout_settings: &Option<TimeoutSettings>,
-) -> GDResult<HashMap<String, String>> {
-    let mut socket = UdpSocket::new(address, timeout_settings)?;
-    retry_on_timeout(
-        TimeoutSettings::get_retries_or_default(timeout_settings),
-        move || get_server_values_impl(&mut socket),
-    )
-}
-
-/// Send status request, and parse response into HashMap (without retry logic).
-fn get_server_values_impl(socket: &mut UdpSocket) -> GDResult<HashMap<String, String>> {
-    socket.send(b"\\status\\xserverquery")?;
+#[cfg(feature = "tokio")]
+use crate::{socket::AsyncUdpSocket, utils::retry_on_timeout_async};
 
-    let mut received_query_id: Option<usize> = None;
-    let mut parts: Vec<usize> = Vec::new();
-    let mut is_finished = false;
+/// The status request packet.
+pub const STATUS_REQUEST: &[u8] = b"\\status\\xserverquery";
 
-    let mut server_values = HashMap::new();
+/// Collects the server values of the (possibly multiple) status response
+/// packets.
+#[derive(Default)]
+pub struct ServerValuesCollector {
+    received_query_id: Option<usize>,
+    parts: Vec<usize>,
+    server_values: HashMap<String, String>,
+}
 
-    while !is_finished {
-        let data = socket.receive(None)?;
-        let mut bufferer = Buffer::<LittleEndian>::new(&data);
+impl ServerValuesCollector {
+    /// Feed a received packet, returns true once the final packet has been
+    /// received.
+    pub fn push(&mut self, data: &[u8]) -> GDResult<bool> {
+        let mut bufferer = Buffer::<LittleEndian>::new(data);
 
         let mut as_string = bufferer.read_string::<Utf8Decoder>(None)?;
         as_string.remove(0);
@@ -57,14 +53,14 @@ fn get_server_values_impl(socket: &mut UdpSocket) -> GDResult<HashMap<String, St
                 .get(position + 1)
                 .map_or_else(String::new, Clone::clone);
 
-            server_values.insert(key, value);
+            self.server_values.insert(key, value);
         }
 
-        is_finished = server_values.remove("final").is_some();
+        let is_finished = self.server_values.remove("final").is_some();
 
-        let query_data = server_values.get("queryid");
+        let query_data = self.server_values.get("queryid");
 
-        let mut part = parts.len(); // if the part number isn't provided, it's value is the parts length
+        let mut part = self.parts.len(); // if the part number isn't provided, it's value is the parts length
         let mut query_id = None;
         if let Some(qid) = query_data {
             let split: Vec<&str> = qid.split('.').collect();
@@ -78,21 +74,50 @@ fn get_server_values_impl(socket: &mut UdpSocket) -> GDResult<HashMap<String, St
             };
         }
 
-        server_values.remove("queryid");
+        self.server_values.remove("queryid");
 
-        if received_query_id.is_some() && received_query_id != query_id {
+        if self.received_query_id.is_some() && self.received_query_id != query_id {
             return Err(GDErrorKind::PacketBad.into()); // wrong query id!
         }
 
-        received_query_id = query_id;
+        self.received_query_id = query_id;
 
-        match parts.contains(&part) {
+        match self.parts.contains(&part) {
             true => Err(GDErrorKind::PacketBad)?,
-            false => parts.push(part),
+            false => self.parts.push(part),
         }
+
+        Ok(is_finished)
     }
 
-    Ok(server_values)
+    /// Get the collected server values.
+    pub fn into_values(self) -> HashMap<String, String> { self.server_values }
+}
+
+/// Send status request, and parse response into HashMap (along with the
+/// ping). This function will retry fetch on timeouts.
+fn get_server_values(
+    address: &SocketAddr,
+    timeout_settings: &Option<TimeoutSettings>,
+) -> GDResult<(HashMap<String, String>, Option<Duration>)> {
+    let mut socket = UdpSocket::new(address, timeout_settings)?;
+    let server_values = retry_on_timeout(
+        TimeoutSettings::get_retries_or_default(timeout_settings),
+        || get_server_values_impl(&mut socket),
+    )
+    .map_err(|e| e.with_stage(QueryStage::Info))?;
+
+    Ok((server_values, socket.ping()))
+}
+
+/// Send status request, and parse response into HashMap (without retry logic).
+fn get_server_values_impl(socket: &mut UdpSocket) -> GDResult<HashMap<String, String>> {
+    socket.send(STATUS_REQUEST)?;
+
+    let mut collector = ServerValuesCollector::default();
+    while !collector.push(&socket.receive(None)?)? {}
+
+    Ok(collector.into_values())
 }
 
 fn extract_players(server_vars: &mut HashMap<String, String>, players_maximum: u32) -> GDResult<Vec<Player>> {
@@ -189,14 +214,33 @@ pub fn query_vars(
     timeout_settings: Option<TimeoutSettings>,
 ) -> GDResult<HashMap<String, String>> {
     get_server_values(address, &timeout_settings)
+        .map(|(server_values, _)| server_values)
+        .map_err(|e| e.with_address(*address))
 }
 
 /// Query a server by providing the address, the port and timeout settings.
 /// Providing None to the timeout settings results in using the default values.
 /// (TimeoutSettings::[default](TimeoutSettings::default)).
 pub fn query(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Response> {
-    let mut server_vars = query_vars(address, timeout_settings)?;
+    get_server_values(address, &timeout_settings)
+        .and_then(|(server_values, ping)| {
+            Ok(Response {
+                ping,
+                ..parse_server_values(server_values)?
+            })
+        })
+        .map_err(|e| e.with_address(*address))
+}
 
+/// Parse the server values of a query, the errors that aren't about the players
+/// being at the info stage.
+fn parse_server_values(server_values: HashMap<String, String>) -> GDResult<Response> {
+    parse_response(server_values).map_err(|e| e.with_stage(QueryStage::Info))
+}
+
+/// Turn the server's values (collected by [ServerValuesCollector]) into a
+/// response.
+pub fn parse_response(mut server_vars: HashMap<String, String>) -> GDResult<Response> {
     let players_maximum: u32 = server_vars
         .remove("maxplayers")
         .ok_or(GDErrorKind::PacketBad)?
@@ -207,7 +251,7 @@ pub fn query(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) ->
         Some(v) => Some(v.parse::<u8>().map_err(|e| TypeParse.context(e))?),
     };
 
-    let players = extract_players(&mut server_vars, players_maximum)?;
+    let players = extract_players(&mut server_vars, players_maximum).map_err(|e| e.with_stage(QueryStage::Players))?;
 
     Ok(Response {
         name: server_vars
@@ -239,5 +283,59 @@ pub fn query(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) ->
             .parse()
             .map_err(|e| TypeParse.context(e))?,
         unused_entries: server_vars,
+        ping: None,
     })
 }
+
+/// The async counterpart of [query_vars].
+#[cfg(feature = "tokio")]
+pub async fn query_vars_async(
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<HashMap<String, String>> {
+    get_server_values_async(address, &timeout_settings)
+        .await
+        .map(|(server_values, _)| server_values)
+        .map_err(|e| e.with_address(*address))
+}
+
+/// The async counterpart of [get_server_values].
+#[cfg(feature = "tokio")]
+async fn get_server_values_async(
+    address: &SocketAddr,
+    timeout_settings: &Option<TimeoutSettings>,
+) -> GDResult<(HashMap<String, String>, Option<Duration>)> {
+    let mut socket = AsyncUdpSocket::new(address, timeout_settings).await?;
+    let server_values = retry_on_timeout_async!(
+        TimeoutSettings::get_retries_or_default(timeout_settings),
+        get_server_values_async_impl(&mut socket)
+    )
+    .map_err(|e| e.with_stage(QueryStage::Info))?;
+
+    Ok((server_values, socket.ping()))
+}
+
+/// Send status request, and parse response into HashMap (without retry logic).
+#[cfg(feature = "tokio")]
+async fn get_server_values_async_impl(socket: &mut AsyncUdpSocket) -> GDResult<HashMap<String, String>> {
+    socket.send(STATUS_REQUEST).await?;
+
+    let mut collector = ServerValuesCollector::default();
+    while !collector.push(&socket.receive(None).await?)? {}
+
+    Ok(collector.into_values())
+}
+
+/// The async counterpart of [query].
+#[cfg(feature = "tokio")]
+pub async fn query_async(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Response> {
+    get_server_values_async(address, &timeout_settings)
+        .await
+        .and_then(|(server_values, ping)| {
+            Ok(Response {
+                ping,
+                ..parse_server_values(server_values)?
+            })
+        })
+        .map_err(|e| e.with_address(*address))
+}
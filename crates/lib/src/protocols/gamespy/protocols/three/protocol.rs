@@ -7,9 +7,13 @@ use crate::protocols::types::TimeoutSettings;
 use crate::socket::{Socket, UdpSocket};
 use crate::utils::retry_on_timeout;
 use crate::GDErrorKind::{PacketBad, TypeParse};
-use crate::{GDErrorKind, GDResult};
+use crate::{GDErrorKind, GDResult, QueryStage};
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::time::Duration;
+
+#[cfg(feature = "tokio")]
+use crate::{socket::AsyncUdpSocket, utils::retry_on_timeout_async, GDError};
 
 const THIS_SESSION_ID: u32 = 1;
 
@@ -40,6 +44,120 @@ impl RequestPacket {
     }
 }
 
+/// Build the initial handshake packet.
+pub fn handshake_request() -> Vec<u8> {
+    RequestPacket {
+        header: 65277,
+        kind: 9,
+        session_id: THIS_SESSION_ID,
+        challenge: None,
+        payload: None,
+    }
+    .to_bytes()
+}
+
+/// Build the data request packet (sent after the handshake), `challenge`
+/// being the one parsed by [parse_handshake_response] and `payload` usually
+/// [DEFAULT_PAYLOAD].
+pub fn data_request(challenge: Option<i32>, payload: [u8; 4]) -> Vec<u8> {
+    RequestPacket {
+        header: 65277,
+        kind: 0,
+        session_id: THIS_SESSION_ID,
+        challenge,
+        payload: Some(payload),
+    }
+    .to_bytes()
+}
+
+/// Validate the header of a received packet and strip it.
+fn strip_header(received: &[u8], kind: u8) -> GDResult<Vec<u8>> {
+    let mut buf = Buffer::<BigEndian>::new(received);
+
+    if buf.read::<u8>()? != kind {
+        return Err(PacketBad.context("Kind of packet did not match"));
+    }
+
+    if buf.read::<u32>()? != THIS_SESSION_ID {
+        return Err(PacketBad.context("Session ID did not match"));
+    }
+
+    Ok(buf.remaining_bytes().to_vec())
+}
+
+/// Parse the challenge out of the handshake response.
+pub fn parse_handshake_response(received: &[u8]) -> GDResult<Option<i32>> {
+    let data = strip_header(received, 9)?;
+    let mut buf = Buffer::<LittleEndian>::new(&data);
+
+    let challenge_as_string = buf.read_string::<Utf8Decoder>(None)?;
+    let challenge = challenge_as_string
+        .parse()
+        .map_err(|e| TypeParse.context(e))?;
+
+    Ok(match challenge == 0 {
+        true => None,
+        false => Some(challenge),
+    })
+}
+
+/// Collects the data packets of a response.
+pub struct PacketCollector {
+    single_packets: bool,
+    values: Vec<Vec<u8>>,
+}
+
+impl PacketCollector {
+    /// `single_packets` should be `true` for servers that answer with a
+    /// single, non `splitnum` prefixed packet.
+    pub const fn new(single_packets: bool) -> Self {
+        Self {
+            single_packets,
+            values: Vec::new(),
+        }
+    }
+
+    /// Feed a received data packet, returns the packets once all of them have
+    /// been received.
+    pub fn push(&mut self, received: &[u8]) -> GDResult<Option<Vec<Vec<u8>>>> {
+        let data = strip_header(received, 0)?;
+        let mut buf = Buffer::<BigEndian>::new(&data);
+
+        if self.single_packets {
+            buf.move_cursor(11)?;
+            return Ok(Some(vec![buf.remaining_bytes().to_vec()]));
+        }
+
+        if buf.read_string::<Utf8Decoder>(None)? != "splitnum" {
+            return Err(PacketBad.context("Expected string \"splitnum\""));
+        }
+
+        let id = buf.read::<u8>()?;
+        let is_last = (id & 0x80) > 0;
+        let packet_id = (id & 0x7f) as usize;
+        buf.move_cursor(1)?; //unknown byte regarding packet no.
+
+        let reached_expected_packets_size = is_last && packet_id + 1 != self.values.len();
+
+        while self.values.len() <= packet_id {
+            self.values.push(Vec::new());
+        }
+
+        self.values[packet_id] = buf.remaining_bytes().to_vec();
+
+        if !reached_expected_packets_size {
+            return Ok(None);
+        }
+
+        let values = std::mem::take(&mut self.values);
+        if values.iter().any(Vec::is_empty) {
+            return Err(PacketBad.context("One (or more) packets is empty"));
+        }
+
+        Ok(Some(values))
+    }
+}
+
 pub(crate) struct GameSpy3 {
     socket: UdpSocket,
     payload: [u8; 4],
@@ -48,19 +166,12 @@ pub(crate) struct GameSpy3 {
 }
 
 const PACKET_SIZE: usize = 2048;
-const DEFAULT_PAYLOAD: [u8; 4] = [0xFF, 0xFF, 0xFF, 0x01];
+/// The payload that most servers expect in the data request.
+pub const DEFAULT_PAYLOAD: [u8; 4] = [0xFF, 0xFF, 0xFF, 0x01];
 
 impl GameSpy3 {
     fn new(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Self> {
-        let socket = UdpSocket::new(address, &timeout_settings)?;
-        let retry_count = TimeoutSettings::get_retries_or_default(&timeout_settings);
-
-        Ok(Self {
-            socket,
-            payload: DEFAULT_PAYLOAD,
-            single_packets: false,
-            retry_count,
-        })
+        Self::new_custom(address, timeout_settings, DEFAULT_PAYLOAD, false)
     }
 
     pub(crate) fn new_custom(
@@ -80,113 +191,47 @@ impl GameSpy3 {
         })
     }
 
-    fn receive(&mut self, size: Option<usize>, kind: u8) -> GDResult<Vec<u8>> {
-        let received = self.socket.receive(size.or(Some(PACKET_SIZE)))?;
-        let mut buf = Buffer::<BigEndian>::new(&received);
-
-        if buf.read::<u8>()? != kind {
-            return Err(PacketBad.context("Kind of packet did not match"));
-        }
-
-        if buf.read::<u32>()? != THIS_SESSION_ID {
-            return Err(PacketBad.context("Session ID did not match"));
-        }
-
-        Ok(buf.remaining_bytes().to_vec())
-    }
-
     fn make_initial_handshake(&mut self) -> GDResult<Option<i32>> {
-        self.socket.send(
-            &RequestPacket {
-                header: 65277,
-                kind: 9,
-                session_id: THIS_SESSION_ID,
-                challenge: None,
-                payload: None,
-            }
-            .to_bytes(),
-        )?;
-
-        let data = self.receive(Some(16), 9)?;
-        let mut buf = Buffer::<LittleEndian>::new(&data);
-
-        let challenge_as_string = buf.read_string::<Utf8Decoder>(None)?;
-        let challenge = challenge_as_string
-            .parse()
-            .map_err(|e| TypeParse.context(e))?;
+        self.socket.send(&handshake_request())?;
 
-        Ok(match challenge == 0 {
-            true => None,
-            false => Some(challenge),
-        })
+        let data = self.socket.receive(Some(16))?;
+        parse_handshake_response(&data)
     }
 
     fn send_data_request(&mut self, challenge: Option<i32>) -> GDResult<()> {
-        self.socket.send(
-            &RequestPacket {
-                header: 65277,
-                kind: 0,
-                session_id: THIS_SESSION_ID,
-                challenge,
-                payload: Some(self.payload),
-            }
-            .to_bytes(),
-        )
+        self.socket.send(&data_request(challenge, self.payload))
     }
 
     /// Fetch packets from server and store in buffer.
     /// This function will retry fetch on timeouts.
     pub(crate) fn get_server_packets(&mut self) -> GDResult<Vec<Vec<u8>>> {
         retry_on_timeout(self.retry_count, move || self.get_server_packets_impl())
+            .map_err(|e| e.with_stage(QueryStage::Info))
     }
 
+    /// The round-trip time of the first request.
+    pub(crate) fn ping(&self) -> Option<Duration> { self.socket.ping() }
+
     /// Fetch packets from server and store in buffer (without retry logic).
     fn get_server_packets_impl(&mut self) -> GDResult<Vec<Vec<u8>>> {
-        let challenge = self.make_initial_handshake()?;
+        let challenge = self
+            .make_initial_handshake()
+            .map_err(|e| e.with_stage(QueryStage::Challenge))?;
         self.send_data_request(challenge)?;
 
-        let mut values: Vec<Vec<u8>> = Vec::new();
-
-        let mut reached_expected_packets_size = false;
-
-        while !reached_expected_packets_size {
-            let received_data = self.receive(None, 0)?;
-            let mut buf = Buffer::<BigEndian>::new(&received_data);
-
-            if self.single_packets {
-                buf.move_cursor(11)?;
-                return Ok(vec![buf.remaining_bytes().to_vec()]);
-            }
-
-            if buf.read_string::<Utf8Decoder>(None)? != "splitnum" {
-                return Err(PacketBad.context("Expected string \"splitnum\""));
-            }
-
-            let id = buf.read::<u8>()?;
-            let is_last = (id & 0x80) > 0;
-            let packet_id = (id & 0x7f) as usize;
-            buf.move_cursor(1)?; //unknown byte regarding packet no.
-
-            if is_last && packet_id + 1 != values.len() {
-                reached_expected_packets_size = true;
-            }
-
-            while values.len() <= packet_id {
-                values.push(Vec::new());
+        let mut collector = PacketCollector::new(self.single_packets);
+        loop {
+            let received_data = self.socket.receive(Some(PACKET_SIZE))?;
+            if let Some(packets) = collector.push(&received_data)? {
+                return Ok(packets);
             }
-
-            values[packet_id] = buf.remaining_bytes().to_vec();
-        }
-
-        if values.iter().any(Vec::is_empty) {
-            return Err(PacketBad.context("One (or more) packets is empty"));
         }
-
-        Ok(values)
     }
 }
 
-pub(crate) fn data_to_map(packet: &[u8]) -> GDResult<(HashMap<String, String>, Vec<u8>)> {
+/// Parse the key-value pairs at the start of a data packet, returning them
+/// and the remaining (players and teams) data.
+pub fn data_to_map(packet: &[u8]) -> GDResult<(HashMap<String, String>, Vec<u8>)> {
     let mut vars = HashMap::new();
 
     let mut buf = Buffer::<BigEndian>::new(packet);
@@ -210,12 +255,17 @@ pub fn query_vars(
     address: &SocketAddr,
     timeout_settings: Option<TimeoutSettings>,
 ) -> GDResult<HashMap<String, String>> {
-    let mut client = GameSpy3::new(address, timeout_settings)?;
-    let packets = client.get_server_packets()?;
+    GameSpy3::new(address, timeout_settings)
+        .and_then(|mut client| client.get_server_packets())
+        .and_then(|packets| parse_vars(&packets).map_err(|e| e.with_stage(QueryStage::Info)))
+        .map_err(|e| e.with_address(*address))
+}
 
+/// Merge the server's values of every packet collected by [PacketCollector].
+pub fn parse_vars(packets: &[Vec<u8>]) -> GDResult<HashMap<String, String>> {
     let mut vars = HashMap::new();
 
-    for packet in &packets {
+    for packet in packets {
         let (key_values, _remaining_data) = data_to_map(packet)?;
         vars.extend(key_values);
     }
@@ -351,14 +401,32 @@ fn parse_players_and_teams(packets: Vec<Vec<u8>>) -> GDResult<(Vec<Player>, Vec<
 /// Providing None to the timeout settings results in using the default values.
 /// (TimeoutSettings::[default](TimeoutSettings::default)).
 pub fn query(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Response> {
-    let mut client = GameSpy3::new(address, timeout_settings)?;
-    let packets = client.get_server_packets()?;
+    GameSpy3::new(address, timeout_settings)
+        .and_then(|mut client| {
+            let packets = client.get_server_packets()?;
+
+            Ok(Response {
+                ping: client.ping(),
+                ..parse_packets(&packets)?
+            })
+        })
+        .map_err(|e| e.with_address(*address))
+}
+
+/// Parse the packets of a query, the errors that aren't about the players or
+/// teams being at the info stage.
+fn parse_packets(packets: &[Vec<u8>]) -> GDResult<Response> {
+    parse_response(packets).map_err(|e| e.with_stage(QueryStage::Info))
+}
 
+/// Parse the data packets collected by [PacketCollector].
+pub fn parse_response(packets: &[Vec<u8>]) -> GDResult<Response> {
     let (mut server_vars, remaining_data) = data_to_map(packets.first().ok_or(GDErrorKind::PacketBad)?)?;
 
     let mut remaining_data_packets = vec![remaining_data];
     remaining_data_packets.extend_from_slice(&packets[1 ..]);
-    let (players, teams) = parse_players_and_teams(remaining_data_packets)?;
+    let (players, teams) =
+        parse_players_and_teams(remaining_data_packets).map_err(|e| e.with_stage(QueryStage::Players))?;
 
     let players_maximum = server_vars
         .remove("maxplayers")
@@ -406,5 +474,108 @@ pub fn query(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) ->
             .parse()
             .map_err(|e| TypeParse.context(e))?,
         unused_entries: server_vars,
+        ping: None,
     })
 }
+
+/// The async counterpart of [GameSpy3].
+#[cfg(feature = "tokio")]
+pub(crate) struct AsyncGameSpy3 {
+    socket: AsyncUdpSocket,
+    payload: [u8; 4],
+    single_packets: bool,
+    retry_count: usize,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncGameSpy3 {
+    async fn new(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Self> {
+        Self::new_custom(address, timeout_settings, DEFAULT_PAYLOAD, false).await
+    }
+
+    pub(crate) async fn new_custom(
+        address: &SocketAddr,
+        timeout_settings: Option<TimeoutSettings>,
+        payload: [u8; 4],
+        single_packets: bool,
+    ) -> GDResult<Self> {
+        let socket = AsyncUdpSocket::new(address, &timeout_settings).await?;
+        let retry_count = TimeoutSettings::get_retries_or_default(&timeout_settings);
+
+        Ok(Self {
+            socket,
+            payload,
+            single_packets,
+            retry_count,
+        })
+    }
+
+    /// Fetch packets from server and store in buffer.
+    /// This function will retry fetch on timeouts.
+    pub(crate) async fn get_server_packets(&mut self) -> GDResult<Vec<Vec<u8>>> {
+        retry_on_timeout_async!(self.retry_count, self.get_server_packets_impl())
+            .map_err(|e| e.with_stage(QueryStage::Info))
+    }
+
+    /// The round-trip time of the first request.
+    pub(crate) const fn ping(&self) -> Option<Duration> { self.socket.ping() }
+
+    async fn make_initial_handshake(&mut self) -> GDResult<Option<i32>> {
+        self.socket.send(&handshake_request()).await?;
+
+        let data = self.socket.receive(Some(16)).await?;
+        parse_handshake_response(&data)
+    }
+
+    /// Fetch packets from server and store in buffer (without retry logic).
+    async fn get_server_packets_impl(&mut self) -> GDResult<Vec<Vec<u8>>> {
+        let challenge = self
+            .make_initial_handshake()
+            .await
+            .map_err(|e| e.with_stage(QueryStage::Challenge))?;
+
+        self.socket
+            .send(&data_request(challenge, self.payload))
+            .await?;
+
+        let mut collector = PacketCollector::new(self.single_packets);
+        loop {
+            let received_data = self.socket.receive(Some(PACKET_SIZE)).await?;
+            if let Some(packets) = collector.push(&received_data)? {
+                return Ok(packets);
+            }
+        }
+    }
+}
+
+/// The async counterpart of [query_vars].
+#[cfg(feature = "tokio")]
+pub async fn query_vars_async(
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<HashMap<String, String>> {
+    async {
+        let mut client = AsyncGameSpy3::new(address, timeout_settings).await?;
+        let packets = client.get_server_packets().await?;
+
+        parse_vars(&packets).map_err(|e| e.with_stage(QueryStage::Info))
+    }
+    .await
+    .map_err(|e| e.with_address(*address))
+}
+
+/// The async counterpart of [query].
+#[cfg(feature = "tokio")]
+pub async fn query_async(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Response> {
+    async {
+        let mut client = AsyncGameSpy3::new(address, timeout_settings).await?;
+        let packets = client.get_server_packets().await?;
+
+        Ok(Response {
+            ping: client.ping(),
+            ..parse_packets(&packets)?
+        })
+    }
+    .await
+    .map_err(|e: GDError| e.with_address(*address))
+}
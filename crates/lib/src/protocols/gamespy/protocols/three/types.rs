@@ -1,7 +1,8 @@
 use crate::protocols::gamespy::{VersionedPlayer, VersionedResponse};
-use crate::protocols::types::{CommonPlayer, CommonResponse, GenericPlayer};
+use crate::protocols::types::{CommonPlayer, CommonResponse, CommonTeam, GenericPlayer};
 use crate::protocols::GenericResponse;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -19,12 +20,17 @@ pub struct Player {
 }
 
 impl CommonPlayer for Player {
-    fn as_original(&self) -> crate::protocols::types::GenericPlayer {
+    fn as_original(&self) -> crate::protocols::types::GenericPlayer<'_> {
         GenericPlayer::Gamespy(VersionedPlayer::Three(self))
     }
 
     fn name(&self) -> &str { &self.name }
     fn score(&self) -> Option<i32> { Some(self.score) }
+    fn ping(&self) -> Option<u32> { Some(self.ping.into()) }
+    fn team(&self) -> Option<u32> { Some(self.team.into()) }
+    fn deaths(&self) -> Option<u32> { Some(self.deaths) }
+
+    fn extra(&self) -> BTreeMap<String, String> { BTreeMap::from([("skill".to_string(), self.skill.to_string())]) }
 }
 
 /// A team's details
@@ -51,10 +57,12 @@ pub struct Response {
     pub teams: Vec<Team>,
     pub tournament: bool,
     pub unused_entries: HashMap<String, String>,
+    /// Round-trip time of the first request.
+    pub ping: Option<Duration>,
 }
 
 impl CommonResponse for Response {
-    fn as_original(&self) -> GenericResponse { GenericResponse::GameSpy(VersionedResponse::Three(self)) }
+    fn as_original(&self) -> GenericResponse<'_> { GenericResponse::GameSpy(VersionedResponse::Three(self)) }
 
     fn name(&self) -> Option<&str> { Some(&self.name) }
     fn map(&self) -> Option<&str> { Some(&self.map) }
@@ -63,6 +71,7 @@ impl CommonResponse for Response {
     fn game_version(&self) -> Option<&str> { Some(&self.game_version) }
     fn players_maximum(&self) -> u32 { self.players_maximum }
     fn players_online(&self) -> u32 { self.players_online }
+    fn ping(&self) -> Option<Duration> { self.ping }
 
     fn players(&self) -> Option<Vec<&dyn CommonPlayer>> {
         Some(
@@ -72,4 +81,29 @@ impl CommonResponse for Response {
                 .collect(),
         )
     }
+    fn teams(&self) -> Option<Vec<CommonTeam<'_>>> {
+        if self.teams.is_empty() {
+            return None;
+        }
+
+        Some(
+            self.teams
+                .iter()
+                .enumerate()
+                .map(|(index, team)| {
+                    CommonTeam {
+                        name: &team.name,
+                        score: Some(team.score),
+                        // The players' team starts at 1.
+                        players: self
+                            .players
+                            .iter()
+                            .filter(|p| usize::from(p.team) == index + 1)
+                            .map(|p| p as &dyn CommonPlayer)
+                            .collect(),
+                    }
+                })
+                .collect(),
+        )
+    }
 }
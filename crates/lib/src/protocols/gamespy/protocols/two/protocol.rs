@@ -4,11 +4,17 @@ use crate::protocols::types::TimeoutSettings;
 use crate::socket::{Socket, UdpSocket};
 use crate::utils::retry_on_timeout;
 use crate::GDErrorKind::{PacketBad, TypeParse};
-use crate::{GDErrorKind, GDResult};
+use crate::{GDErrorKind, GDResult, QueryStage};
 use byteorder::BigEndian;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 
+#[cfg(feature = "tokio")]
+use crate::{socket::AsyncUdpSocket, utils::retry_on_timeout_async, GDError};
+
+/// The request packet.
+pub const REQUEST: [u8; 10] = [0xFE, 0xFD, 0x00, 0x00, 0x00, 0x00, 0x01, 0xFF, 0xFF, 0xFF];
+
 struct GameSpy2 {
     socket: UdpSocket,
     retry_count: usize,
@@ -90,26 +96,29 @@ impl GameSpy2 {
 
     /// Send fetch request to server and store result in buffer.
     /// This function will retry fetch on timeouts.
-    fn request_data(&mut self) -> GDResult<(Vec<u8>, usize)> {
+    fn request_data(&mut self) -> GDResult<Vec<u8>> {
         retry_on_timeout(self.retry_count, move || self.request_data_impl())
     }
 
     /// Send fetch request to server and store result in buffer (without retry
     /// logic).
-    fn request_data_impl(&mut self) -> GDResult<(Vec<u8>, usize)> {
-        self.socket
-            .send(&[0xFE, 0xFD, 0x00, 0x00, 0x00, 0x00, 0x01, 0xFF, 0xFF, 0xFF])?;
+    fn request_data_impl(&mut self) -> GDResult<Vec<u8>> {
+        self.socket.send(&REQUEST)?;
 
         let received = self.socket.receive(None)?;
+        consume_response_header(&mut Buffer::<BigEndian>::new(&received))?;
 
-        let mut buf = Buffer::<BigEndian>::new(&received);
-        if buf.read::<u8>()? != 0 || buf.read::<u32>()? != 1 {
-            return Err(PacketBad.into());
-        }
+        Ok(received)
+    }
+}
 
-        let buf_index = buf.current_position();
-        Ok((received, buf_index))
+/// Consume the header part of a response packet, validating it.
+fn consume_response_header(buffer: &mut Buffer<BigEndian>) -> GDResult<()> {
+    if buffer.read::<u8>()? != 0 || buffer.read::<u32>()? != 1 {
+        return Err(PacketBad.into());
     }
+
+    Ok(())
 }
 
 fn get_server_vars(bufferer: &mut Buffer<BigEndian>) -> GDResult<HashMap<String, String>> {
@@ -168,14 +177,31 @@ fn get_players(bufferer: &mut Buffer<BigEndian>) -> GDResult<Vec<Player>> {
 }
 
 pub fn query(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Response> {
-    let mut client = GameSpy2::new(address, timeout_settings)?;
-    let (data, buf_index) = client.request_data()?;
+    GameSpy2::new(address, timeout_settings)
+        .and_then(|mut client| {
+            let data = client
+                .request_data()
+                .map_err(|e| e.with_stage(QueryStage::Info))?;
+
+            Ok(Response {
+                ping: client.socket.ping(),
+                ..parse_data(&data)?
+            })
+        })
+        .map_err(|e| e.with_address(*address))
+}
+
+/// Parse the response of a query, the errors that aren't about the players or
+/// teams being at the info stage.
+fn parse_data(data: &[u8]) -> GDResult<Response> { parse_response(data).map_err(|e| e.with_stage(QueryStage::Info)) }
 
-    let mut buffer = Buffer::<BigEndian>::new(&data);
-    buffer.move_cursor(buf_index as isize)?;
+/// Parse a response packet.
+pub fn parse_response(data: &[u8]) -> GDResult<Response> {
+    let mut buffer = Buffer::<BigEndian>::new(data);
+    consume_response_header(&mut buffer)?;
 
     let mut server_vars = get_server_vars(&mut buffer)?;
-    let players = get_players(&mut buffer)?;
+    let players = get_players(&mut buffer).map_err(|e| e.with_stage(QueryStage::Players))?;
 
     let players_online = match server_vars.remove("numplayers") {
         None => players.len(),
@@ -196,7 +222,7 @@ pub fn query(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) ->
         name: server_vars.remove("hostname").ok_or(PacketBad)?,
         map: server_vars.remove("mapname").ok_or(PacketBad)?,
         has_password: server_vars.remove("password").ok_or(PacketBad)? == "1",
-        teams: get_teams(&mut buffer)?,
+        teams: get_teams(&mut buffer).map_err(|e| e.with_stage(QueryStage::Players))?,
         players_maximum: server_vars
             .remove("maxplayers")
             .ok_or(PacketBad)?
@@ -206,5 +232,38 @@ pub fn query(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) ->
         players_minimum,
         players,
         unused_entries: server_vars,
+        ping: None,
     })
 }
+
+/// The async counterpart of [query].
+#[cfg(feature = "tokio")]
+pub async fn query_async(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Response> {
+    async {
+        let mut socket = AsyncUdpSocket::new(address, &timeout_settings).await?;
+        let data = retry_on_timeout_async!(
+            TimeoutSettings::get_retries_or_default(&timeout_settings),
+            request_data_async_impl(&mut socket)
+        )
+        .map_err(|e| e.with_stage(QueryStage::Info))?;
+
+        Ok(Response {
+            ping: socket.ping(),
+            ..parse_data(&data)?
+        })
+    }
+    .await
+    .map_err(|e: GDError| e.with_address(*address))
+}
+
+/// Send fetch request to server and return the response (without retry
+/// logic).
+#[cfg(feature = "tokio")]
+async fn request_data_async_impl(socket: &mut AsyncUdpSocket) -> GDResult<Vec<u8>> {
+    socket.send(&REQUEST).await?;
+
+    let received = socket.receive(None).await?;
+    consume_response_header(&mut Buffer::<BigEndian>::new(&received))?;
+
+    Ok(received)
+}
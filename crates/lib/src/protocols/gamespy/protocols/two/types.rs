@@ -1,7 +1,8 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use crate::protocols::gamespy::{VersionedPlayer, VersionedResponse};
-use crate::protocols::types::{CommonPlayer, CommonResponse, GenericPlayer};
+use crate::protocols::types::{CommonPlayer, CommonResponse, CommonTeam, GenericPlayer};
 use crate::protocols::GenericResponse;
 
 #[cfg(feature = "serde")]
@@ -24,10 +25,12 @@ pub struct Player {
 }
 
 impl CommonPlayer for Player {
-    fn as_original(&self) -> GenericPlayer { GenericPlayer::Gamespy(VersionedPlayer::Two(self)) }
+    fn as_original(&self) -> GenericPlayer<'_> { GenericPlayer::Gamespy(VersionedPlayer::Two(self)) }
 
     fn name(&self) -> &str { &self.name }
     fn score(&self) -> Option<i32> { Some(self.score.into()) }
+    fn ping(&self) -> Option<u32> { Some(self.ping.into()) }
+    fn team(&self) -> Option<u32> { Some(self.team_index.into()) }
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -42,16 +45,19 @@ pub struct Response {
     pub players_minimum: Option<u32>,
     pub players: Vec<Player>,
     pub unused_entries: HashMap<String, String>,
+    /// Round-trip time of the first request.
+    pub ping: Option<Duration>,
 }
 
 impl CommonResponse for Response {
-    fn as_original(&self) -> GenericResponse { GenericResponse::GameSpy(VersionedResponse::Two(self)) }
+    fn as_original(&self) -> GenericResponse<'_> { GenericResponse::GameSpy(VersionedResponse::Two(self)) }
 
     fn name(&self) -> Option<&str> { Some(&self.name) }
     fn map(&self) -> Option<&str> { Some(&self.map) }
     fn has_password(&self) -> Option<bool> { Some(self.has_password) }
     fn players_maximum(&self) -> u32 { self.players_maximum }
     fn players_online(&self) -> u32 { self.players_online }
+    fn ping(&self) -> Option<Duration> { self.ping }
 
     fn players(&self) -> Option<Vec<&dyn CommonPlayer>> {
         Some(
@@ -61,4 +67,29 @@ impl CommonResponse for Response {
                 .collect(),
         )
     }
+    fn teams(&self) -> Option<Vec<CommonTeam<'_>>> {
+        if self.teams.is_empty() {
+            return None;
+        }
+
+        Some(
+            self.teams
+                .iter()
+                .enumerate()
+                .map(|(index, team)| {
+                    CommonTeam {
+                        name: &team.name,
+                        score: Some(team.score.into()),
+                        // The players' team starts at 1.
+                        players: self
+                            .players
+                            .iter()
+                            .filter(|p| usize::from(p.team_index) == index + 1)
+                            .map(|p| p as &dyn CommonPlayer)
+                            .collect(),
+                    }
+                })
+                .collect(),
+        )
+    }
 }
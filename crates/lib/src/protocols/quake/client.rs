@@ -6,10 +6,14 @@ use crate::protocols::types::TimeoutSettings;
 use crate::socket::{Socket, UdpSocket};
 use crate::utils::retry_on_timeout;
 use crate::GDErrorKind::{PacketBad, TypeParse};
-use crate::{GDErrorKind, GDResult};
+use crate::{GDErrorKind, GDResult, QueryStage};
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::slice::Iter;
+use std::time::Duration;
+
+#[cfg(feature = "tokio")]
+use crate::{socket::AsyncUdpSocket, utils::retry_on_timeout_async, GDError};
 
 pub trait QuakeClient {
     type Player;
@@ -19,32 +23,43 @@ pub trait QuakeClient {
     fn parse_player_string(data: Iter<&str>) -> GDResult<Self::Player>;
 }
 
-/// Send request and return result buffer.
+/// Send request and return result buffer (along with the ping).
 /// This function will retry fetch on timeouts.
 fn get_data<Client: QuakeClient>(
     address: &SocketAddr,
     timeout_settings: &Option<TimeoutSettings>,
-) -> GDResult<Vec<u8>> {
+) -> GDResult<(Vec<u8>, Option<Duration>)> {
     let mut socket = UdpSocket::new(address, timeout_settings)?;
-    retry_on_timeout(
+    let data = retry_on_timeout(
         TimeoutSettings::get_retries_or_default(timeout_settings),
-        move || get_data_impl::<Client>(&mut socket),
+        || get_data_impl::<Client>(&mut socket),
     )
+    .map_err(|e| e.with_stage(QueryStage::Info))?;
+
+    Ok((data, socket.ping()))
 }
 
 /// Send request and return result buffer (without retry logic).
 fn get_data_impl<Client: QuakeClient>(socket: &mut UdpSocket) -> GDResult<Vec<u8>> {
-    socket.send(
-        &[
-            &[0xFF, 0xFF, 0xFF, 0xFF],
-            Client::get_send_header().as_bytes(),
-            &[0x00],
-        ]
-        .concat(),
-    )?;
+    socket.send(&request::<Client>())?;
 
     let data = socket.receive(None)?;
-    let mut bufferer = Buffer::<LittleEndian>::new(&data);
+    strip_response_header::<Client>(&data)
+}
+
+/// Build the request packet.
+pub(crate) fn request<Client: QuakeClient>() -> Vec<u8> {
+    [
+        &[0xFF, 0xFF, 0xFF, 0xFF],
+        Client::get_send_header().as_bytes(),
+        &[0x00],
+    ]
+    .concat()
+}
+
+/// Validate the header of a response packet and strip it.
+fn strip_response_header<Client: QuakeClient>(data: &[u8]) -> GDResult<Vec<u8>> {
+    let mut bufferer = Buffer::<LittleEndian>::new(data);
 
     if bufferer.read::<u32>()? != u32::MAX {
         return Err(PacketBad.context("Expected 4294967295"));
@@ -89,11 +104,13 @@ fn get_server_values(bufferer: &mut Buffer<LittleEndian>) -> GDResult<HashMap<St
 fn get_players<Client: QuakeClient>(bufferer: &mut Buffer<LittleEndian>) -> GDResult<Vec<Client::Player>> {
     let mut players: Vec<Client::Player> = Vec::new();
 
-    // this needs to be looked at again as theres no way to check if the buffer has
-    // a remaining null byte the original code was:
-    // while !bufferer.is_remaining_empty() && bufferer.remaining_data() != [0x00]
-    while !bufferer.remaining_length() == 0 {
+    while bufferer.remaining_length() > 0 {
         let data = bufferer.read_string::<Utf8Decoder>(Some([0x0A]))?;
+        // Some servers end the packet with a null byte.
+        if data.trim_matches('\0').is_empty() {
+            continue;
+        }
+
         let data_split = data.split(' ').collect::<Vec<&str>>();
         let data_iter = data_split.iter();
 
@@ -107,11 +124,33 @@ pub fn client_query<Client: QuakeClient>(
     address: &SocketAddr,
     timeout_settings: Option<TimeoutSettings>,
 ) -> GDResult<Response<Client::Player>> {
-    let data = get_data::<Client>(address, &timeout_settings)?;
-    let mut bufferer = Buffer::<LittleEndian>::new(&data);
+    get_data::<Client>(address, &timeout_settings)
+        .and_then(|(data, ping)| {
+            Ok(Response {
+                ping,
+                ..parse_data::<Client>(&data)?
+            })
+        })
+        .map_err(|e| e.with_address(*address))
+}
+
+/// Parse the (header stripped) response of a query, the errors that aren't
+/// about the players being at the info stage.
+fn parse_data<Client: QuakeClient>(data: &[u8]) -> GDResult<Response<Client::Player>> {
+    parse_stripped_response::<Client>(data).map_err(|e| e.with_stage(QueryStage::Info))
+}
+
+/// Parse a response packet.
+pub(crate) fn parse_response<Client: QuakeClient>(data: &[u8]) -> GDResult<Response<Client::Player>> {
+    parse_stripped_response::<Client>(&strip_response_header::<Client>(data)?)
+}
+
+/// Parse the (header stripped) response packet.
+fn parse_stripped_response<Client: QuakeClient>(data: &[u8]) -> GDResult<Response<Client::Player>> {
+    let mut bufferer = Buffer::<LittleEndian>::new(data);
 
     let mut server_vars = get_server_values(&mut bufferer)?;
-    let players = get_players::<Client>(&mut bufferer)?;
+    let players = get_players::<Client>(&mut bufferer).map_err(|e| e.with_stage(QueryStage::Players))?;
 
     Ok(Response {
         name: server_vars
@@ -134,12 +173,63 @@ pub fn client_query<Client: QuakeClient>(
             .remove("version")
             .or_else(|| server_vars.remove("*version")),
         unused_entries: server_vars,
+        ping: None,
     })
 }
 
+/// The async counterpart of [client_query].
+#[cfg(feature = "tokio")]
+pub async fn client_query_async<Client: QuakeClient>(
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Response<Client::Player>> {
+    async {
+        let mut socket = AsyncUdpSocket::new(address, &timeout_settings).await?;
+        let data = retry_on_timeout_async!(
+            TimeoutSettings::get_retries_or_default(&timeout_settings),
+            get_data_async_impl::<Client>(&mut socket)
+        )
+        .map_err(|e| e.with_stage(QueryStage::Info))?;
+
+        Ok(Response {
+            ping: socket.ping(),
+            ..parse_data::<Client>(&data)?
+        })
+    }
+    .await
+    .map_err(|e: GDError| e.with_address(*address))
+}
+
+/// Send request and return result buffer (without retry logic).
+#[cfg(feature = "tokio")]
+async fn get_data_async_impl<Client: QuakeClient>(socket: &mut AsyncUdpSocket) -> GDResult<Vec<u8>> {
+    socket.send(&request::<Client>()).await?;
+
+    let data = socket.receive(None).await?;
+    strip_response_header::<Client>(&data)
+}
+
 pub fn remove_wrapping_quotes<'a>(string: &&'a str) -> &'a str {
     match string.starts_with('\"') && string.ends_with('\"') {
         false => string,
         true => &string[1 .. string.len() - 1],
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocols::quake::three;
+
+    #[test]
+    fn error_context() {
+        // Receives the query without ever answering it.
+        let silent = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = silent.local_addr().unwrap();
+        let timeout_settings = TimeoutSettings::new(Some(Duration::from_millis(100)), None, None, 0).ok();
+
+        let error = three::query(&address, timeout_settings).unwrap_err();
+        assert_eq!(error.stage, Some(QueryStage::Info));
+        assert_eq!(error.address, Some(address));
+    }
+}
@@ -1,12 +1,17 @@
-use crate::protocols::quake::client::{client_query, remove_wrapping_quotes, QuakeClient};
+use crate::protocols::quake::client::{self, client_query, remove_wrapping_quotes, QuakeClient};
 use crate::protocols::quake::Response;
 use crate::protocols::types::{CommonPlayer, GenericPlayer, TimeoutSettings};
 use crate::GDErrorKind::TypeParse;
 use crate::{GDErrorKind, GDResult};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::net::SocketAddr;
 use std::slice::Iter;
+use std::time::Duration;
+
+#[cfg(feature = "tokio")]
+use crate::protocols::quake::client::client_query_async;
 
 use super::QuakePlayerType;
 
@@ -26,14 +31,29 @@ pub struct Player {
 }
 
 impl QuakePlayerType for Player {
-    fn version(response: &Response<Self>) -> super::VersionedResponse { super::VersionedResponse::One(response) }
+    fn version(response: &Response<Self>) -> super::VersionedResponse<'_> { super::VersionedResponse::One(response) }
 }
 
 impl CommonPlayer for Player {
-    fn as_original(&self) -> GenericPlayer { GenericPlayer::QuakeOne(self) }
+    fn as_original(&self) -> GenericPlayer<'_> { GenericPlayer::QuakeOne(self) }
 
     fn name(&self) -> &str { &self.name }
     fn score(&self) -> Option<i32> { Some(self.score.into()) }
+    fn ping(&self) -> Option<u32> { Some(self.ping.into()) }
+    // The time is in minutes.
+    fn time_connected(&self) -> Option<Duration> { Some(Duration::from_secs(u64::from(self.time) * 60)) }
+
+    fn extra(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("id".to_string(), self.id.to_string()),
+            ("skin".to_string(), self.skin.clone()),
+            ("color_primary".to_string(), self.color_primary.to_string()),
+            (
+                "color_secondary".to_string(),
+                self.color_secondary.to_string(),
+            ),
+        ])
+    }
 }
 
 pub(crate) struct QuakeOne;
@@ -82,6 +102,21 @@ impl QuakeClient for QuakeOne {
     }
 }
 
+/// Build the status request packet.
+pub fn request() -> Vec<u8> { client::request::<QuakeOne>() }
+
+/// Parse a status response packet.
+pub fn parse_response(data: &[u8]) -> GDResult<Response<Player>> { client::parse_response::<QuakeOne>(data) }
+
 pub fn query(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Response<Player>> {
     client_query::<QuakeOne>(address, timeout_settings)
 }
+
+/// The async counterpart of [query].
+#[cfg(feature = "tokio")]
+pub async fn query_async(
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Response<Player>> {
+    client_query_async::<QuakeOne>(address, timeout_settings).await
+}
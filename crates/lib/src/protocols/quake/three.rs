@@ -1,4 +1,4 @@
-use crate::protocols::quake::client::{client_query, QuakeClient};
+use crate::protocols::quake::client::{self, client_query, QuakeClient};
 use crate::protocols::quake::two::QuakeTwo;
 use crate::protocols::quake::Response;
 use crate::protocols::types::TimeoutSettings;
@@ -6,6 +6,9 @@ use crate::GDResult;
 use std::net::SocketAddr;
 use std::slice::Iter;
 
+#[cfg(feature = "tokio")]
+use crate::protocols::quake::client::client_query_async;
+
 pub use crate::protocols::quake::two::Player;
 
 struct QuakeThree;
@@ -19,6 +22,21 @@ impl QuakeClient for QuakeThree {
     fn parse_player_string(data: Iter<&str>) -> GDResult<Self::Player> { QuakeTwo::parse_player_string(data) }
 }
 
+/// Build the status request packet.
+pub fn request() -> Vec<u8> { client::request::<QuakeThree>() }
+
+/// Parse a status response packet.
+pub fn parse_response(data: &[u8]) -> GDResult<Response<Player>> { client::parse_response::<QuakeThree>(data) }
+
 pub fn query(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Response<Player>> {
     client_query::<QuakeThree>(address, timeout_settings)
 }
+
+/// The async counterpart of [query].
+#[cfg(feature = "tokio")]
+pub async fn query_async(
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Response<Player>> {
+    client_query_async::<QuakeThree>(address, timeout_settings).await
+}
@@ -1,4 +1,4 @@
-use crate::protocols::quake::client::{client_query, remove_wrapping_quotes, QuakeClient};
+use crate::protocols::quake::client::{self, client_query, remove_wrapping_quotes, QuakeClient};
 use crate::protocols::quake::one::QuakeOne;
 use crate::protocols::quake::Response;
 use crate::protocols::types::{CommonPlayer, GenericPlayer, TimeoutSettings};
@@ -6,9 +6,13 @@ use crate::GDErrorKind::TypeParse;
 use crate::{GDErrorKind, GDResult};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::net::SocketAddr;
 use std::slice::Iter;
 
+#[cfg(feature = "tokio")]
+use crate::protocols::quake::client::client_query_async;
+
 use super::QuakePlayerType;
 
 /// Quake 2 player data.
@@ -22,17 +26,27 @@ pub struct Player {
 }
 
 impl QuakePlayerType for Player {
-    fn version(response: &Response<Self>) -> super::VersionedResponse {
+    fn version(response: &Response<Self>) -> super::VersionedResponse<'_> {
         super::VersionedResponse::TwoAndThree(response)
     }
 }
 
 impl CommonPlayer for Player {
-    fn as_original(&self) -> GenericPlayer { GenericPlayer::QuakeTwo(self) }
+    fn as_original(&self) -> GenericPlayer<'_> { GenericPlayer::QuakeTwo(self) }
 
     fn name(&self) -> &str { &self.name }
 
     fn score(&self) -> Option<i32> { Some(self.score) }
+
+    fn ping(&self) -> Option<u32> { Some(self.ping.into()) }
+
+    fn extra(&self) -> BTreeMap<String, String> {
+        self.address
+            .clone()
+            .map(|address| ("address".to_string(), address))
+            .into_iter()
+            .collect()
+    }
 }
 
 pub(crate) struct QuakeTwo;
@@ -62,6 +76,21 @@ impl QuakeClient for QuakeTwo {
     }
 }
 
+/// Build the status request packet.
+pub fn request() -> Vec<u8> { client::request::<QuakeTwo>() }
+
+/// Parse a status response packet.
+pub fn parse_response(data: &[u8]) -> GDResult<Response<Player>> { client::parse_response::<QuakeTwo>(data) }
+
 pub fn query(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Response<Player>> {
     client_query::<QuakeTwo>(address, timeout_settings)
 }
+
+/// The async counterpart of [query].
+#[cfg(feature = "tokio")]
+pub async fn query_async(
+    address: &SocketAddr,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Response<Player>> {
+    client_query_async::<QuakeTwo>(address, timeout_settings).await
+}
@@ -1,9 +1,10 @@
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 
 use crate::protocols::{
-    types::{CommonPlayer, CommonResponse},
+    types::{CommonPlayer, CommonResponse, CommonTeam},
     GenericResponse,
 };
 
@@ -25,20 +26,33 @@ pub struct Response<P> {
     pub game_version: Option<String>,
     /// Other server entries that weren't used.
     pub unused_entries: HashMap<String, String>,
+    /// Round-trip time of the first request.
+    pub ping: Option<Duration>,
 }
 
-pub trait QuakePlayerType: Sized + CommonPlayer {
-    fn version(response: &Response<Self>) -> VersionedResponse;
+impl<P> Response<P> {
+    /// Get an unused entry, ignoring the key's case.
+    fn unused_entry(&self, key: &str) -> Option<&str> {
+        self.unused_entries
+            .iter()
+            .find(|(entry_key, _)| entry_key.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub trait QuakePlayerType: Sized + Send + Sync + CommonPlayer {
+    fn version(response: &Response<Self>) -> VersionedResponse<'_>;
 }
 
 impl<P: QuakePlayerType> CommonResponse for Response<P> {
-    fn as_original(&self) -> GenericResponse { GenericResponse::Quake(P::version(self)) }
+    fn as_original(&self) -> GenericResponse<'_> { GenericResponse::Quake(P::version(self)) }
 
     fn name(&self) -> Option<&str> { Some(&self.name) }
     fn game_version(&self) -> Option<&str> { self.game_version.as_deref() }
     fn map(&self) -> Option<&str> { Some(&self.map) }
     fn players_maximum(&self) -> u32 { self.players_maximum.into() }
     fn players_online(&self) -> u32 { self.players_online.into() }
+    fn ping(&self) -> Option<Duration> { self.ping }
 
     fn players(&self) -> Option<Vec<&dyn CommonPlayer>> {
         Some(
@@ -48,6 +62,38 @@ impl<P: QuakePlayerType> CommonResponse for Response<P> {
                 .collect(),
         )
     }
+
+    /// Only some (Quake 3) servers provide their teams, as the `Score_<Color>`
+    /// and `Players_<Color>` (the players' positions, starting at 1) entries.
+    fn teams(&self) -> Option<Vec<CommonTeam<'_>>> {
+        let teams: Vec<CommonTeam> = [("red", "Red"), ("blue", "Blue")]
+            .into_iter()
+            .filter_map(|(color, default_name)| {
+                let score = self.unused_entry(&format!("score_{color}"));
+                let members = self.unused_entry(&format!("players_{color}"));
+                if score.is_none() && members.is_none() {
+                    return None;
+                }
+
+                Some(CommonTeam {
+                    name: self
+                        .unused_entry(&format!("g_{color}team"))
+                        .filter(|name| !name.is_empty())
+                        .unwrap_or(default_name),
+                    score: score.and_then(|score| score.trim().parse().ok()),
+                    players: members
+                        .unwrap_or_default()
+                        .split_whitespace()
+                        .filter_map(|position| position.parse::<usize>().ok()?.checked_sub(1))
+                        .filter_map(|index| self.players.get(index))
+                        .map(|p| p as &dyn CommonPlayer)
+                        .collect(),
+                })
+            })
+            .collect();
+
+        (!teams.is_empty()).then_some(teams)
+    }
 }
 
 /// Versioned response type
@@ -57,3 +103,38 @@ pub enum VersionedResponse<'a> {
     One(&'a Response<crate::protocols::quake::one::Player>),
     TwoAndThree(&'a Response<crate::protocols::quake::two::Player>),
 }
+
+/// Owned versioned response type
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionedResponseOwned {
+    One(Response<crate::protocols::quake::one::Player>),
+    TwoAndThree(Response<crate::protocols::quake::two::Player>),
+}
+
+impl VersionedResponseOwned {
+    /// Get the borrowed versioned response.
+    pub const fn as_versioned(&self) -> VersionedResponse<'_> {
+        match self {
+            Self::One(response) => VersionedResponse::One(response),
+            Self::TwoAndThree(response) => VersionedResponse::TwoAndThree(response),
+        }
+    }
+
+    /// Get the response as a common one.
+    pub fn as_common(&self) -> &dyn CommonResponse {
+        match self {
+            Self::One(response) => response,
+            Self::TwoAndThree(response) => response,
+        }
+    }
+}
+
+impl From<VersionedResponse<'_>> for VersionedResponseOwned {
+    fn from(response: VersionedResponse<'_>) -> Self {
+        match response {
+            VersionedResponse::One(response) => Self::One(response.clone()),
+            VersionedResponse::TwoAndThree(response) => Self::TwoAndThree(response.clone()),
+        }
+    }
+}
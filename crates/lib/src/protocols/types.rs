@@ -6,6 +6,7 @@ use crate::protocols::{gamespy, quake, unreal2, valve};
 use crate::GDErrorKind::InvalidInput;
 use crate::GDResult;
 
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 #[cfg(feature = "serde")]
@@ -27,7 +28,7 @@ pub enum ProprietaryProtocol {
 
 /// Enumeration of all valid protocol types
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Protocol {
     Gamespy(gamespy::GameSpyVersion),
     Quake(quake::QuakeVersion),
@@ -65,6 +66,113 @@ pub enum GenericResponse<'a> {
     Eco(&'a crate::games::eco::Response),
 }
 
+/// All response types, owned (see [GenericResponse])
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenericResponseOwned {
+    GameSpy(gamespy::VersionedResponseOwned),
+    Quake(quake::VersionedResponseOwned),
+    Valve(valve::Response),
+    Unreal2(unreal2::Response),
+    #[cfg(feature = "tls")]
+    Epic(epic::Response),
+    #[cfg(feature = "games")]
+    Mindustry(crate::games::mindustry::types::ServerData),
+    #[cfg(feature = "games")]
+    Minecraft(minecraft::VersionedResponseOwned),
+    #[cfg(feature = "games")]
+    TheShip(crate::games::theship::Response),
+    #[cfg(feature = "games")]
+    FFOW(crate::games::ffow::Response),
+    #[cfg(feature = "games")]
+    JC2M(crate::games::jc2m::Response),
+    #[cfg(feature = "games")]
+    Savage2(crate::games::savage2::Response),
+    #[cfg(feature = "games")]
+    Eco(crate::games::eco::Response),
+}
+
+impl GenericResponseOwned {
+    /// Get the borrowed response type
+    pub const fn as_generic(&self) -> GenericResponse<'_> {
+        match self {
+            Self::GameSpy(response) => GenericResponse::GameSpy(response.as_versioned()),
+            Self::Quake(response) => GenericResponse::Quake(response.as_versioned()),
+            Self::Valve(response) => GenericResponse::Valve(response),
+            Self::Unreal2(response) => GenericResponse::Unreal2(response),
+            #[cfg(feature = "tls")]
+            Self::Epic(response) => GenericResponse::Epic(response),
+            #[cfg(feature = "games")]
+            Self::Mindustry(response) => GenericResponse::Mindustry(response),
+            #[cfg(feature = "games")]
+            Self::Minecraft(response) => GenericResponse::Minecraft(response.as_versioned()),
+            #[cfg(feature = "games")]
+            Self::TheShip(response) => GenericResponse::TheShip(response),
+            #[cfg(feature = "games")]
+            Self::FFOW(response) => GenericResponse::FFOW(response),
+            #[cfg(feature = "games")]
+            Self::JC2M(response) => GenericResponse::JC2M(response),
+            #[cfg(feature = "games")]
+            Self::Savage2(response) => GenericResponse::Savage2(response),
+            #[cfg(feature = "games")]
+            Self::Eco(response) => GenericResponse::Eco(response),
+        }
+    }
+
+    /// Get the response as a common one
+    pub fn as_common(&self) -> &dyn CommonResponse {
+        match self {
+            Self::GameSpy(response) => response.as_common(),
+            Self::Quake(response) => response.as_common(),
+            Self::Valve(response) => response,
+            Self::Unreal2(response) => response,
+            #[cfg(feature = "tls")]
+            Self::Epic(response) => response,
+            #[cfg(feature = "games")]
+            Self::Mindustry(response) => response,
+            #[cfg(feature = "games")]
+            Self::Minecraft(response) => response.as_common(),
+            #[cfg(feature = "games")]
+            Self::TheShip(response) => response,
+            #[cfg(feature = "games")]
+            Self::FFOW(response) => response,
+            #[cfg(feature = "games")]
+            Self::JC2M(response) => response,
+            #[cfg(feature = "games")]
+            Self::Savage2(response) => response,
+            #[cfg(feature = "games")]
+            Self::Eco(response) => response,
+        }
+    }
+}
+
+impl From<GenericResponse<'_>> for GenericResponseOwned {
+    fn from(response: GenericResponse<'_>) -> Self {
+        match response {
+            GenericResponse::GameSpy(response) => Self::GameSpy(response.into()),
+            GenericResponse::Quake(response) => Self::Quake(response.into()),
+            GenericResponse::Valve(response) => Self::Valve(response.clone()),
+            GenericResponse::Unreal2(response) => Self::Unreal2(response.clone()),
+            #[cfg(feature = "tls")]
+            GenericResponse::Epic(response) => Self::Epic(response.clone()),
+            #[cfg(feature = "games")]
+            GenericResponse::Mindustry(response) => Self::Mindustry(response.clone()),
+            #[cfg(feature = "games")]
+            GenericResponse::Minecraft(response) => Self::Minecraft(response.into()),
+            #[cfg(feature = "games")]
+            GenericResponse::TheShip(response) => Self::TheShip(response.clone()),
+            #[cfg(feature = "games")]
+            GenericResponse::FFOW(response) => Self::FFOW(response.clone()),
+            #[cfg(feature = "games")]
+            GenericResponse::JC2M(response) => Self::JC2M(response.clone()),
+            #[cfg(feature = "games")]
+            GenericResponse::Savage2(response) => Self::Savage2(response.clone()),
+            #[cfg(feature = "games")]
+            GenericResponse::Eco(response) => Self::Eco(response.clone()),
+        }
+    }
+}
+
 /// All player types
 #[cfg_attr(feature = "serde", derive(Serialize))]
 #[derive(Debug, Clone, PartialEq)]
@@ -86,18 +194,19 @@ pub enum GenericPlayer<'a> {
     Eco(&'a crate::games::eco::Player),
 }
 
-pub trait CommonResponse {
+pub trait CommonResponse: Send + Sync {
     /// Get the original response type
-    fn as_original(&self) -> GenericResponse;
+    fn as_original(&self) -> GenericResponse<'_>;
     /// Get a struct that can be stored as JSON (you don't need to override
     /// this)
-    fn as_json(&self) -> CommonResponseJson {
+    fn as_json(&self) -> CommonResponseJson<'_> {
         CommonResponseJson {
             name: self.name(),
             description: self.description(),
             game_mode: self.game_mode(),
             game_version: self.game_version(),
             has_password: self.has_password(),
+            ping: self.ping().map(|ping| ping.as_millis() as u64),
             map: self.map(),
             players_maximum: self.players_maximum(),
             players_online: self.players_online(),
@@ -105,6 +214,26 @@ pub trait CommonResponse {
             players: self
                 .players()
                 .map(|players| players.iter().map(|p| p.as_json()).collect()),
+            teams: self
+                .teams()
+                .map(|teams| teams.iter().map(CommonTeam::as_json).collect()),
+        }
+    }
+
+    /// Get an owned copy of the response (you don't need to override this)
+    fn as_owned(&self) -> CommonResponseOwned {
+        CommonResponseOwned {
+            name: self.name().map(str::to_string),
+            description: self.description().map(str::to_string),
+            game_mode: self.game_mode().map(str::to_string),
+            game_version: self.game_version().map(str::to_string),
+            map: self.map().map(str::to_string),
+            players_maximum: self.players_maximum(),
+            players_online: self.players_online(),
+            players_bots: self.players_bots(),
+            has_password: self.has_password(),
+            ping: self.ping(),
+            original: self.as_original().into(),
         }
     }
 
@@ -128,6 +257,10 @@ pub trait CommonResponse {
     fn has_password(&self) -> Option<bool> { None }
     /// Currently connected players
     fn players(&self) -> Option<Vec<&dyn CommonPlayer>> { None }
+    /// Round-trip time of the first request that got a response
+    fn ping(&self) -> Option<Duration> { None }
+    /// The teams and their players
+    fn teams(&self) -> Option<Vec<CommonTeam<'_>>> { None }
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -142,18 +275,90 @@ pub struct CommonResponseJson<'a> {
     pub players_online: u32,
     pub players_bots: Option<u32>,
     pub has_password: Option<bool>,
+    /// In milliseconds.
+    pub ping: Option<u64>,
     pub players: Option<Vec<CommonPlayerJson<'a>>>,
+    pub teams: Option<Vec<CommonTeamJson<'a>>>,
+}
+
+/// An owned copy of a [CommonResponse] (which is also one), along with the
+/// original response, that can be stored and read back.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommonResponseOwned {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub game_mode: Option<String>,
+    pub game_version: Option<String>,
+    pub map: Option<String>,
+    pub players_maximum: u32,
+    pub players_online: u32,
+    pub players_bots: Option<u32>,
+    pub has_password: Option<bool>,
+    /// Stored in milliseconds, like [CommonResponseJson::ping].
+    #[cfg_attr(feature = "serde", serde(default, with = "ping_millis"))]
+    pub ping: Option<Duration>,
+    /// The players and teams are the ones of the original response.
+    pub original: GenericResponseOwned,
+}
+
+/// (De)serialize an optional duration as milliseconds.
+#[cfg(feature = "serde")]
+mod ping_millis {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(ping: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        ping.map(|ping| ping.as_millis() as u64)
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_millis))
+    }
+}
+
+impl CommonResponse for CommonResponseOwned {
+    fn as_original(&self) -> GenericResponse<'_> { self.original.as_generic() }
+    fn as_owned(&self) -> CommonResponseOwned { self.clone() }
+
+    fn name(&self) -> Option<&str> { self.name.as_deref() }
+    fn description(&self) -> Option<&str> { self.description.as_deref() }
+    fn game_mode(&self) -> Option<&str> { self.game_mode.as_deref() }
+    fn game_version(&self) -> Option<&str> { self.game_version.as_deref() }
+    fn map(&self) -> Option<&str> { self.map.as_deref() }
+    fn players_maximum(&self) -> u32 { self.players_maximum }
+    fn players_online(&self) -> u32 { self.players_online }
+    fn players_bots(&self) -> Option<u32> { self.players_bots }
+    fn has_password(&self) -> Option<bool> { self.has_password }
+    fn ping(&self) -> Option<Duration> { self.ping }
+    fn players(&self) -> Option<Vec<&dyn CommonPlayer>> { self.original.as_common().players() }
+    fn teams(&self) -> Option<Vec<CommonTeam<'_>>> { self.original.as_common().teams() }
+}
+
+impl From<&dyn CommonResponse> for CommonResponseOwned {
+    fn from(response: &dyn CommonResponse) -> Self { response.as_owned() }
+}
+
+impl From<Box<dyn CommonResponse>> for CommonResponseOwned {
+    fn from(response: Box<dyn CommonResponse>) -> Self { response.as_owned() }
 }
 
 pub trait CommonPlayer {
     /// Get the original player type
-    fn as_original(&self) -> GenericPlayer;
+    fn as_original(&self) -> GenericPlayer<'_>;
     /// Get a struct that can be stored as JSON (you don't need to override
     /// this)
-    fn as_json(&self) -> CommonPlayerJson {
+    fn as_json(&self) -> CommonPlayerJson<'_> {
         CommonPlayerJson {
             name: self.name(),
             score: self.score(),
+            ping: self.ping(),
+            team: self.team(),
+            time_connected: self.time_connected().map(|time| time.as_secs()),
+            deaths: self.deaths(),
+            is_bot: self.is_bot(),
+            extra: self.extra(),
         }
     }
 
@@ -161,13 +366,63 @@ pub trait CommonPlayer {
     fn name(&self) -> &str;
     /// Player score
     fn score(&self) -> Option<i32> { None }
+    /// Player ping (in milliseconds) as reported by the server
+    fn ping(&self) -> Option<u32> { None }
+    /// Index of the player's team
+    fn team(&self) -> Option<u32> { None }
+    /// How long the player has been connected
+    fn time_connected(&self) -> Option<Duration> { None }
+    /// Player deaths count
+    fn deaths(&self) -> Option<u32> { None }
+    /// Whether the player is a bot
+    fn is_bot(&self) -> Option<bool> { None }
+    /// Protocol specific player data that doesn't fit the other fields
+    fn extra(&self) -> BTreeMap<String, String> { BTreeMap::new() }
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct CommonPlayerJson<'a> {
     pub name: &'a str,
     pub score: Option<i32>,
+    /// In milliseconds.
+    pub ping: Option<u32>,
+    pub team: Option<u32>,
+    /// In seconds.
+    pub time_connected: Option<u64>,
+    pub deaths: Option<u32>,
+    pub is_bot: Option<bool>,
+    pub extra: BTreeMap<String, String>,
+}
+
+/// A team and the players that are in it.
+pub struct CommonTeam<'a> {
+    /// Team name
+    pub name: &'a str,
+    /// Team score
+    pub score: Option<i32>,
+    /// The players of the team, empty if the protocol doesn't tell which
+    /// team a player is in
+    pub players: Vec<&'a dyn CommonPlayer>,
+}
+
+impl<'a> CommonTeam<'a> {
+    /// Get a struct that can be stored as JSON
+    pub fn as_json(&self) -> CommonTeamJson<'a> {
+        CommonTeamJson {
+            name: self.name,
+            score: self.score,
+            players: self.players.iter().map(|p| p.as_json()).collect(),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CommonTeamJson<'a> {
+    pub name: &'a str,
+    pub score: Option<i32>,
+    pub players: Vec<CommonPlayerJson<'a>>,
 }
 
 #[cfg(feature = "clap")]
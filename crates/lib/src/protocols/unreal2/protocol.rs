@@ -1,5 +1,6 @@
 use crate::buffer::{Buffer, StringDecoder};
 use crate::errors::GDErrorKind::PacketBad;
+use crate::errors::QueryStage;
 use crate::protocols::types::TimeoutSettings;
 use crate::socket::{Socket, UdpSocket};
 use crate::utils::{maybe_gather, retry_on_timeout};
@@ -12,6 +13,9 @@ use std::net::SocketAddr;
 use byteorder::{ByteOrder, LittleEndian};
 use encoding_rs::{UTF_16LE, WINDOWS_1252};
 
+#[cfg(feature = "tokio")]
+use crate::{socket::AsyncUdpSocket, utils::retry_on_timeout_async, GDError};
+
 /// Response packets don't seem to exceed 500 bytes, set to 1024 just to be
 /// safe.
 const PACKET_SIZE: usize = 1024;
@@ -53,8 +57,7 @@ impl Unreal2Protocol {
 
     /// Send a request packet
     fn get_request_data_impl(&mut self, packet_type: PacketKind) -> GDResult<Vec<u8>> {
-        let request = [0x79, 0, 0, 0, packet_type as u8];
-        self.socket.send(&request)?;
+        self.socket.send(&request(packet_type))?;
 
         let data = self.socket.receive(Some(PACKET_SIZE))?;
 
@@ -87,10 +90,7 @@ impl Unreal2Protocol {
     /// Send server info query.
     pub fn query_server_info(&mut self) -> GDResult<ServerInfo> {
         let data = self.get_request_data(PacketKind::ServerInfo)?;
-        let mut buffer = Buffer::<LittleEndian>::new(&data);
-        // TODO: Maybe put consume headers in individual packet parse methods
-        Self::consume_response_headers(&mut buffer, PacketKind::ServerInfo)?;
-        ServerInfo::parse(&mut buffer)
+        parse_server_info(&data)
     }
 
     /// Send mutators and rules query.
@@ -129,13 +129,8 @@ impl Unreal2Protocol {
     pub fn query_players(&mut self, server_info: Option<&ServerInfo>) -> GDResult<Players> {
         // Pre-allocate the player arrays, but don't over allocate memory if the server
         // specifies an insane number of players.
-        let num_players: Option<usize> = server_info.and_then(|i| i.num_players.try_into().ok());
-
-        let mut players = Players::with_capacity(
-            num_players
-                .unwrap_or(DEFAULT_PLAYER_PREALLOCATION)
-                .min(MAXIMUM_PLAYER_PREALLOCATION),
-        );
+        let num_players = expected_players(server_info);
+        let mut players = players_with_capacity(num_players);
 
         // Fetch first players packet (with retries)
         let mut players_data = self.get_request_data(PacketKind::Players);
@@ -166,22 +161,23 @@ impl Unreal2Protocol {
     /// Make a full server query.
     pub fn query(&mut self, gather_settings: &GatheringSettings) -> GDResult<Response> {
         // Fetch the server info, this can only handle one response packet
-        let mut server_info = self.query_server_info()?;
+        let mut server_info = self
+            .query_server_info()
+            .map_err(|e| e.with_stage(QueryStage::Info))?;
 
         let mutators_and_rules = maybe_gather!(
             gather_settings.mutators_and_rules,
             self.query_mutators_and_rules()
+                .map_err(|e| e.with_stage(QueryStage::Rules))
         )
         .unwrap_or_default();
 
-        if let Some(password) = mutators_and_rules.rules.get("GamePassword") {
-            let string = password.concat().to_lowercase();
-            server_info.password = string == "true";
-        }
+        apply_password(&mut server_info, &mutators_and_rules);
 
         let players = maybe_gather!(
             gather_settings.players,
             self.query_players(Some(&server_info))
+                .map_err(|e| e.with_stage(QueryStage::Players))
         )
         .unwrap_or_else(|| Players::with_capacity(0));
 
@@ -192,10 +188,89 @@ impl Unreal2Protocol {
             server_info,
             mutators_and_rules,
             players,
+            ping: self.socket.ping(),
         })
     }
 }
 
+/// Build a request packet.
+pub const fn request(packet_type: PacketKind) -> [u8; 5] { [0x79, 0, 0, 0, packet_type as u8] }
+
+/// Parse a server info response packet.
+pub fn parse_server_info(data: &[u8]) -> GDResult<ServerInfo> {
+    let mut buffer = Buffer::<LittleEndian>::new(data);
+    // TODO: Maybe put consume headers in individual packet parse methods
+    Unreal2Protocol::consume_response_headers(&mut buffer, PacketKind::ServerInfo)?;
+    ServerInfo::parse(&mut buffer)
+}
+
+/// Parse the mutators and rules response packets (a server can answer a
+/// single request with many).
+pub fn parse_mutators_and_rules(packets: &[Vec<u8>]) -> GDResult<MutatorsAndRules> {
+    let mut mutators_and_rules = MutatorsAndRules::default();
+
+    for data in packets {
+        let mut buffer = Buffer::<LittleEndian>::new(data);
+        Unreal2Protocol::consume_response_headers(&mut buffer, PacketKind::MutatorsAndRules)?;
+        mutators_and_rules.parse(&mut buffer)?;
+    }
+
+    Ok(mutators_and_rules)
+}
+
+/// Parse the players response packets (a server can answer a single request
+/// with many).
+pub fn parse_players(packets: &[Vec<u8>]) -> GDResult<Players> {
+    let mut players = players_with_capacity(None);
+
+    for data in packets {
+        let mut buffer = Buffer::<LittleEndian>::new(data);
+        Unreal2Protocol::consume_response_headers(&mut buffer, PacketKind::Players)?;
+        players.parse(&mut buffer)?;
+    }
+
+    Ok(players)
+}
+
+/// Parse a full response from the server info, mutators and rules and players
+/// response packets.
+pub fn parse_response(server_info: &[u8], mutators_and_rules: &[Vec<u8>], players: &[Vec<u8>]) -> GDResult<Response> {
+    let mut server_info = parse_server_info(server_info)?;
+    let mutators_and_rules = parse_mutators_and_rules(mutators_and_rules)?;
+
+    apply_password(&mut server_info, &mutators_and_rules);
+
+    Ok(Response {
+        server_info,
+        mutators_and_rules,
+        players: parse_players(players)?,
+        ping: None,
+    })
+}
+
+/// The amount of players the server info says are online.
+fn expected_players(server_info: Option<&ServerInfo>) -> Option<usize> {
+    server_info.and_then(|i| i.num_players.try_into().ok())
+}
+
+/// Pre-allocate the player arrays, but don't over allocate memory if the server
+/// specifies an insane number of players.
+fn players_with_capacity(num_players: Option<usize>) -> Players {
+    Players::with_capacity(
+        num_players
+            .unwrap_or(DEFAULT_PLAYER_PREALLOCATION)
+            .min(MAXIMUM_PLAYER_PREALLOCATION),
+    )
+}
+
+/// Set the password field of the server info from the rules (if present).
+fn apply_password(server_info: &mut ServerInfo, mutators_and_rules: &MutatorsAndRules) {
+    if let Some(password) = mutators_and_rules.rules.get("GamePassword") {
+        let string = password.concat().to_lowercase();
+        server_info.password = string == "true";
+    }
+}
+
 /// Unreal 2 string decoder
 pub struct Unreal2StringDecoder;
 impl StringDecoder for Unreal2StringDecoder {
@@ -230,10 +305,9 @@ impl StringDecoder for Unreal2StringDecoder {
 
         // If UCS2 the first byte is the masked length of the string
         let result = if ucs2 {
-            let string_data = &data[start .. start + length];
-            if string_data.len() != length {
-                return Err(PacketBad.context("Not enough data in buffer to read string"));
-            }
+            let string_data = data
+                .get(start .. start + length)
+                .ok_or_else(|| PacketBad.context("Not enough data in buffer to read string"))?;
 
             // When node decodes UCS2 it uses the UFT16LE encoding.
             // https://github.com/nodejs/node/blob/2aaa21f9f684484edb54be30589c4af0b923cdef/lib/buffer.js#L637-L645
@@ -304,9 +378,145 @@ pub fn query(
     gather_settings: &GatheringSettings,
     timeout_settings: Option<TimeoutSettings>,
 ) -> GDResult<Response> {
-    let mut client = Unreal2Protocol::new(address, timeout_settings)?;
+    Unreal2Protocol::new(address, timeout_settings)
+        .and_then(|mut client| client.query(gather_settings))
+        .map_err(|e| e.with_address(*address))
+}
+
+/// The async counterpart of [Unreal2Protocol].
+#[cfg(feature = "tokio")]
+pub(crate) struct AsyncUnreal2Protocol {
+    socket: AsyncUdpSocket,
+    retry_count: usize,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncUnreal2Protocol {
+    pub async fn new(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Self> {
+        let socket = AsyncUdpSocket::new(address, &timeout_settings).await?;
+        let retry_count = TimeoutSettings::get_retries_or_default(&timeout_settings);
+
+        Ok(Self {
+            socket,
+            retry_count,
+        })
+    }
+
+    /// Send a request packet and recieve the first response (with retries).
+    async fn get_request_data(&mut self, packet_type: PacketKind) -> GDResult<Vec<u8>> {
+        retry_on_timeout_async!(self.retry_count, self.get_request_data_impl(packet_type))
+    }
+
+    /// Send a request packet
+    async fn get_request_data_impl(&mut self, packet_type: PacketKind) -> GDResult<Vec<u8>> {
+        self.socket.send(&request(packet_type)).await?;
+        self.socket.receive(Some(PACKET_SIZE)).await
+    }
+
+    /// Send server info query.
+    pub async fn query_server_info(&mut self) -> GDResult<ServerInfo> {
+        let data = self.get_request_data(PacketKind::ServerInfo).await?;
+        parse_server_info(&data)
+    }
+
+    /// Send mutators and rules query.
+    pub async fn query_mutators_and_rules(&mut self) -> GDResult<MutatorsAndRules> {
+        // Like in the blocking implementation, we greedily handle packets until we get
+        // a timeout (or any receive error).
+        let mut mutators_and_rules = MutatorsAndRules::default();
+        {
+            let data = self.get_request_data(PacketKind::MutatorsAndRules).await?;
+            let mut buffer = Buffer::<LittleEndian>::new(&data);
+            Unreal2Protocol::consume_response_headers(&mut buffer, PacketKind::MutatorsAndRules)?;
+            mutators_and_rules.parse(&mut buffer)?
+        };
+
+        while let Ok(data) = self.socket.receive(Some(PACKET_SIZE)).await {
+            let mut buffer = Buffer::<LittleEndian>::new(&data);
 
-    client.query(gather_settings)
+            if Unreal2Protocol::consume_response_headers(&mut buffer, PacketKind::MutatorsAndRules).is_err() {
+                break;
+            }
+
+            mutators_and_rules.parse(&mut buffer)?;
+        }
+
+        Ok(mutators_and_rules)
+    }
+
+    /// Send players query.
+    pub async fn query_players(&mut self, server_info: Option<&ServerInfo>) -> GDResult<Players> {
+        let num_players = expected_players(server_info);
+        let mut players = players_with_capacity(num_players);
+
+        let mut players_data = self.get_request_data(PacketKind::Players).await;
+        while let Ok(data) = players_data {
+            let mut buffer = Buffer::<LittleEndian>::new(&data);
+
+            Unreal2Protocol::consume_response_headers(&mut buffer, PacketKind::Players)?;
+
+            players.parse(&mut buffer)?;
+
+            if let Some(num_players) = num_players {
+                if players.total_len() >= num_players {
+                    break;
+                }
+            }
+
+            players_data = self.socket.receive(Some(PACKET_SIZE)).await;
+        }
+
+        Ok(players)
+    }
+
+    /// Make a full server query.
+    pub async fn query(&mut self, gather_settings: &GatheringSettings) -> GDResult<Response> {
+        let mut server_info = self
+            .query_server_info()
+            .await
+            .map_err(|e| e.with_stage(QueryStage::Info))?;
+
+        let mutators_and_rules = maybe_gather!(
+            gather_settings.mutators_and_rules,
+            self.query_mutators_and_rules()
+                .await
+                .map_err(|e| e.with_stage(QueryStage::Rules))
+        )
+        .unwrap_or_default();
+
+        apply_password(&mut server_info, &mutators_and_rules);
+
+        let players = maybe_gather!(
+            gather_settings.players,
+            self.query_players(Some(&server_info))
+                .await
+                .map_err(|e| e.with_stage(QueryStage::Players))
+        )
+        .unwrap_or_else(|| Players::with_capacity(0));
+
+        Ok(Response {
+            server_info,
+            mutators_and_rules,
+            players,
+            ping: self.socket.ping(),
+        })
+    }
+}
+
+/// The async counterpart of [query].
+#[cfg(feature = "tokio")]
+pub async fn query_async(
+    address: &SocketAddr,
+    gather_settings: &GatheringSettings,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Response> {
+    async {
+        let mut client = AsyncUnreal2Protocol::new(address, timeout_settings).await?;
+
+        client.query(gather_settings).await
+    }
+    .await
+    .map_err(|e: GDError| e.with_address(*address))
 }
 
 // TODO: Add tests
@@ -1,12 +1,20 @@
 use crate::buffer::Buffer;
 use crate::errors::GDErrorKind::PacketBad;
-use crate::protocols::types::{CommonPlayer, CommonResponse, ExtraRequestSettings, GatherToggle, GenericPlayer};
+use crate::protocols::types::{
+    CommonPlayer,
+    CommonResponse,
+    CommonTeam,
+    ExtraRequestSettings,
+    GatherToggle,
+    GenericPlayer,
+};
 use crate::protocols::GenericResponse;
 use crate::{GDError, GDResult};
 
 use super::Unreal2StringDecoder;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
 
 use byteorder::ByteOrder;
 
@@ -104,7 +112,7 @@ impl MutatorsAndRules {
     }
 }
 
-/// Unreal 2 players and bots.
+/// Unreal 2 players, bots and teams.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Players {
@@ -112,6 +120,8 @@ pub struct Players {
     pub players: Vec<Player>,
     /// List of bots returned by server (players with 0 ping).
     pub bots: Vec<Player>,
+    /// List of teams, that some games send as players with a 0 id.
+    pub teams: Vec<Team>,
 }
 
 impl Players {
@@ -122,6 +132,7 @@ impl Players {
             players: Vec::with_capacity(capacity),
             // Allocate half as many bots as we don't expect there to be as many
             bots: Vec::with_capacity(capacity / 2),
+            teams: Vec::new(),
         }
     }
 
@@ -136,8 +147,14 @@ impl Players {
                 stats_id: buffer.read()?,
             };
 
-            // If ping is 0 the player is a bot
-            if player.ping == 0 {
+            // Real players and bots always have an id
+            if player.id == 0 {
+                self.teams.push(Team {
+                    name: player.name,
+                    score: player.score,
+                });
+            } else if player.ping == 0 {
+                // If ping is 0 the player is a bot
                 self.bots.push(player);
             } else {
                 self.players.push(player);
@@ -167,7 +184,26 @@ impl CommonPlayer for Player {
 
     fn score(&self) -> Option<i32> { Some(self.score) }
 
-    fn as_original(&self) -> GenericPlayer { GenericPlayer::Unreal2(self) }
+    fn ping(&self) -> Option<u32> { Some(self.ping) }
+
+    fn is_bot(&self) -> Option<bool> { Some(self.ping == 0) }
+
+    fn extra(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("id".to_string(), self.id.to_string()),
+            ("stats_id".to_string(), self.stats_id.to_string()),
+        ])
+    }
+
+    fn as_original(&self) -> GenericPlayer<'_> { GenericPlayer::Unreal2(self) }
+}
+
+/// Unreal 2 team info.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Team {
+    pub name: String,
+    pub score: i32,
 }
 
 /// Unreal 2 response.
@@ -177,10 +213,12 @@ pub struct Response {
     pub server_info: ServerInfo,
     pub mutators_and_rules: MutatorsAndRules,
     pub players: Players,
+    /// Round-trip time of the first request.
+    pub ping: Option<Duration>,
 }
 
 impl CommonResponse for Response {
-    fn as_original(&self) -> GenericResponse { GenericResponse::Unreal2(self) }
+    fn as_original(&self) -> GenericResponse<'_> { GenericResponse::Unreal2(self) }
 
     fn name(&self) -> Option<&str> { Some(&self.server_info.name) }
 
@@ -194,15 +232,41 @@ impl CommonResponse for Response {
 
     fn has_password(&self) -> Option<bool> { Some(self.server_info.password) }
 
+    fn players_bots(&self) -> Option<u32> { Some(self.players.bots.len() as u32) }
+
+    fn ping(&self) -> Option<Duration> { self.ping }
+
     fn players(&self) -> Option<Vec<&dyn crate::protocols::types::CommonPlayer>> {
         Some(
             self.players
                 .players
                 .iter()
+                .chain(&self.players.bots)
                 .map(|player| player as _)
                 .collect(),
         )
     }
+
+    fn teams(&self) -> Option<Vec<CommonTeam<'_>>> {
+        if self.players.teams.is_empty() {
+            return None;
+        }
+
+        Some(
+            self.players
+                .teams
+                .iter()
+                .map(|team| {
+                    CommonTeam {
+                        name: &team.name,
+                        score: Some(team.score),
+                        // Which team a player is in isn't sent.
+                        players: Vec::new(),
+                    }
+                })
+                .collect(),
+        )
+    }
 }
 
 /// What data to gather, purely used only with the query function.
@@ -1,9 +1,12 @@
 /// The implementation.
 pub mod protocol;
+/// Query many servers from a single socket.
+pub mod scanner;
 /// All types used by the implementation.
 pub mod types;
 
 pub use protocol::*;
+pub use scanner::*;
 pub use types::*;
 
 /// Generate a module containing a query function for a valve game.
@@ -74,6 +77,19 @@ macro_rules! game_query_fn {
 
             Ok(crate::protocols::valve::game::Response::new_from_valve_response(valve_response))
         }
+
+        #[doc = "The async counterpart of [query]."]
+        #[cfg(feature = "tokio")]
+        pub async fn query_async(address: &std::net::IpAddr, port: Option<u16>) -> crate::GDResult<crate::protocols::valve::game::Response> {
+            let valve_response = crate::protocols::valve::query_async(
+                &std::net::SocketAddr::new(*address, port.unwrap_or($default_port)),
+                $engine,
+                Some($gathering_settings),
+                None,
+            ).await?;
+
+            Ok(crate::protocols::valve::game::Response::new_from_valve_response(valve_response))
+        }
     };
 }
 
@@ -20,8 +20,9 @@ use crate::{
     },
     socket::{Socket, UdpSocket},
     utils::{maybe_gather, retry_on_timeout, u8_lower_upper},
-    GDErrorKind::{BadGame, Decompress, UnknownEnumCast},
+    GDErrorKind::{BadGame, Decompress, PacketBad, UnknownEnumCast},
     GDResult,
+    QueryStage,
 };
 
 use bzip2_rs::decoder::Decoder;
@@ -31,6 +32,10 @@ use crate::protocols::valve::Packet;
 use byteorder::LittleEndian;
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::time::Duration;
+
+#[cfg(feature = "tokio")]
+use crate::{socket::AsyncUdpSocket, utils::retry_on_timeout_async, GDError};
 
 #[derive(Debug)]
 #[allow(dead_code)] //remove this later on
@@ -117,6 +122,77 @@ impl SplitPacket {
     }
 }
 
+/// Reassembles the (possibly split) datagrams of a response into a packet.
+pub struct PacketAssembler<'a> {
+    engine: &'a Engine,
+    protocol: u8,
+    chunk_packets: Vec<SplitPacket>,
+}
+
+impl<'a> PacketAssembler<'a> {
+    /// `protocol` is the one reported by the server info (0 when requesting
+    /// it).
+    pub const fn new(engine: &'a Engine, protocol: u8) -> Self {
+        Self {
+            engine,
+            protocol,
+            chunk_packets: Vec::new(),
+        }
+    }
+
+    /// Feed a received datagram, returns the packet once all of its parts have
+    /// been received.
+    pub fn push(&mut self, data: &[u8]) -> GDResult<Option<Packet>> {
+        let mut buffer = Buffer::<LittleEndian>::new(data);
+
+        let header: u8 = buffer.read()?;
+        buffer.move_cursor(-1)?;
+        if header != 0xFE {
+            if !self.chunk_packets.is_empty() {
+                return Err(PacketBad.context("Expected a split packet"));
+            }
+
+            return Packet::new_from_bufferer(&mut buffer).map(Some);
+        }
+
+        // the packet is split
+        self.chunk_packets
+            .push(SplitPacket::new(self.engine, self.protocol, &mut buffer)?);
+        if self.chunk_packets.len() < self.chunk_packets[0].total as usize {
+            return Ok(None);
+        }
+
+        // the parts can arrive in any order
+        let mut chunk_packets = std::mem::take(&mut self.chunk_packets);
+        chunk_packets.sort_by_key(|a| a.number);
+
+        let mut main_packet = chunk_packets.remove(0);
+        for chunk_packet in chunk_packets {
+            main_packet.payload.extend(chunk_packet.payload);
+        }
+
+        let payload = main_packet.get_payload()?;
+        Packet::new_from_bufferer(&mut Buffer::<LittleEndian>::new(&payload)).map(Some)
+    }
+}
+
+/// Build a request packet with its default payload.
+pub fn request(kind: Request) -> Vec<u8> { Packet::new(kind as u8, kind.get_default_payload()).to_bytes() }
+
+/// Build the packet to send again after the server answered with a challenge
+/// (`0x41`), `challenge` being the payload of the challenge packet.
+pub fn challenge_request(kind: u8, challenge: Vec<u8>) -> Vec<u8> {
+    const INFO: u8 = Request::Info as u8;
+    Packet::new(
+        kind,
+        match kind {
+            INFO => [Request::Info.get_default_payload(), challenge].concat(),
+            _ => challenge,
+        },
+    )
+    .to_bytes()
+}
+
 pub(crate) struct ValveProtocol {
     socket: UdpSocket,
     retry_count: usize,
@@ -138,35 +214,17 @@ impl ValveProtocol {
         })
     }
 
-    fn receive(&mut self, engine: &Engine, protocol: u8, buffer_size: usize) -> GDResult<Packet> {
-        let data = self.socket.receive(Some(buffer_size))?;
-        let mut buffer = Buffer::<LittleEndian>::new(&data);
-
-        let header: u8 = buffer.read()?;
-        buffer.move_cursor(-1)?;
-        if header == 0xFE {
-            // the packet is split
-            let mut main_packet = SplitPacket::new(engine, protocol, &mut buffer)?;
-            let mut chunk_packets = Vec::with_capacity((main_packet.total - 1) as usize);
-
-            for _ in 1 .. main_packet.total {
-                let new_data = self.socket.receive(Some(buffer_size))?;
-                buffer = Buffer::<LittleEndian>::new(&new_data);
-                let chunk_packet = SplitPacket::new(engine, protocol, &mut buffer)?;
-                chunk_packets.push(chunk_packet);
-            }
+    /// The round-trip time of the first request.
+    pub fn ping(&self) -> Option<Duration> { self.socket.ping() }
 
-            chunk_packets.sort_by(|a, b| a.number.cmp(&b.number));
+    fn receive(&mut self, engine: &Engine, protocol: u8, buffer_size: usize) -> GDResult<Packet> {
+        let mut assembler = PacketAssembler::new(engine, protocol);
 
-            for chunk_packet in chunk_packets {
-                main_packet.payload.extend(chunk_packet.payload);
+        loop {
+            let data = self.socket.receive(Some(buffer_size))?;
+            if let Some(packet) = assembler.push(&data)? {
+                return Ok(packet);
             }
-
-            let payload = main_packet.get_payload()?; // Creating a non-temporary value here
-            let mut new_packet_buffer = Buffer::<LittleEndian>::new(&payload); // Using the non-temporary value here
-            Ok(Packet::new_from_bufferer(&mut new_packet_buffer)?)
-        } else {
-            Packet::new_from_bufferer(&mut buffer)
         }
     }
 
@@ -197,232 +255,262 @@ impl ValveProtocol {
         let mut packet = self.receive(engine, protocol, PACKET_SIZE)?;
         while packet.kind == 0x41 {
             // 'A'
-            let challenge = packet.payload;
-
-            const INFO: u8 = Request::Info as u8;
-            let challenge_packet = Packet::new(
-                kind,
-                match kind {
-                    INFO => [Request::Info.get_default_payload(), challenge].concat(),
-                    _ => challenge,
-                },
-            )
-            .to_bytes();
-
-            self.socket.send(&challenge_packet)?;
+            self.socket
+                .send(&challenge_request(kind, packet.payload))
+                .map_err(|e| e.with_stage(QueryStage::Challenge))?;
 
-            packet = self.receive(engine, protocol, PACKET_SIZE)?;
+            packet = self
+                .receive(engine, protocol, PACKET_SIZE)
+                .map_err(|e| e.with_stage(QueryStage::Challenge))?;
         }
 
         Ok(packet.payload)
     }
 
-    fn get_goldsrc_server_info(buffer: &mut Buffer<LittleEndian>) -> GDResult<ServerInfo> {
-        let _header: u8 = buffer.read()?; //get the header (useless info)
-        let _address: String = buffer.read_string::<Utf8Decoder>(None)?; //get the server address (useless info)
-        let name = buffer.read_string::<Utf8Decoder>(None)?;
-        let map = buffer.read_string::<Utf8Decoder>(None)?;
-        let folder = buffer.read_string::<Utf8Decoder>(None)?;
-        let game_mode = buffer.read_string::<Utf8Decoder>(None)?;
-        let players = buffer.read()?;
-        let max_players = buffer.read()?;
-        let protocol = buffer.read()?;
-        let server_type = match buffer.read::<u8>()? {
-            68 => Server::Dedicated,    //'D'
-            76 => Server::NonDedicated, //'L'
-            80 => Server::TV,           //'P'
-            _ => Err(UnknownEnumCast)?,
-        };
-        let environment_type = match buffer.read::<u8>()? {
-            76 => Environment::Linux,   //'L'
-            87 => Environment::Windows, //'W'
-            _ => Err(UnknownEnumCast)?,
-        };
-        let has_password = buffer.read::<u8>()? == 1;
-        let is_mod = buffer.read::<u8>()? == 1;
-        let mod_data = match is_mod {
-            false => None,
-            true => {
-                Some(ModData {
-                    link: buffer.read_string::<Utf8Decoder>(None)?,
-                    download_link: buffer.read_string::<Utf8Decoder>(None)?,
-                    version: buffer.read()?,
-                    size: buffer.read()?,
-                    multiplayer_only: buffer.read::<u8>()? == 1,
-                    has_own_dll: buffer.read::<u8>()? == 1,
-                })
-            }
-        };
-        let vac_secured = buffer.read::<u8>()? == 1;
-        let bots = buffer.read::<u8>()?;
-
-        Ok(ServerInfo {
-            protocol_version: protocol,
-            name,
-            map,
-            folder,
-            game_mode,
-            appid: 0, // not present in the obsolete response
-            players_online: players,
-            players_maximum: max_players,
-            players_bots: bots,
-            server_type,
-            environment_type,
-            has_password,
-            vac_secured,
-            the_ship: None,
-            game_version: String::new(), // a version field only for the mod
-            extra_data: None,
-            is_mod,
-            mod_data,
-        })
-    }
-
     /// Get the server information's.
     fn get_server_info(&mut self, engine: &Engine) -> GDResult<ServerInfo> {
-        let data = self.get_kind_request_data(engine, 0, Request::Info)?;
-        let mut buffer = Buffer::<LittleEndian>::new(&data);
-
-        if let Engine::GoldSrc(force) = engine {
-            if *force {
-                return Self::get_goldsrc_server_info(&mut buffer);
-            }
-        }
-
-        let protocol = buffer.read()?;
-        let name = buffer.read_string::<Utf8Decoder>(None)?;
-        let map = buffer.read_string::<Utf8Decoder>(None)?;
-        let folder = buffer.read_string::<Utf8Decoder>(None)?;
-        let game_mode = buffer.read_string::<Utf8Decoder>(None)?;
-        let mut appid = buffer.read::<u16>()? as u32;
-        let players = buffer.read()?;
-        let max_players = buffer.read()?;
-        let bots = buffer.read()?;
-        let server_type = Server::from_gldsrc(buffer.read()?)?;
-        let environment_type = Environment::from_gldsrc(buffer.read()?)?;
-        let has_password = buffer.read::<u8>()? == 1;
-        let vac_secured = buffer.read::<u8>()? == 1;
-        let the_ship = match *engine == Engine::new(2400) {
-            false => None,
-            true => {
-                Some(TheShip {
-                    mode: buffer.read()?,
-                    witnesses: buffer.read()?,
-                    duration: buffer.read()?,
-                })
-            }
-        };
-        let game_version = buffer.read_string::<Utf8Decoder>(None)?;
-        let extra_data = match buffer.read::<u8>() {
-            Err(_) => None,
-            Ok(value) => {
-                Some(ExtraData {
-                    port: match (value & 0x80) > 0 {
-                        false => None,
-                        true => Some(buffer.read()?),
-                    },
-                    steam_id: match (value & 0x10) > 0 {
-                        false => None,
-                        true => Some(buffer.read()?),
-                    },
-                    tv_port: match (value & 0x40) > 0 {
-                        false => None,
-                        true => Some(buffer.read()?),
-                    },
-                    tv_name: match (value & 0x40) > 0 {
-                        false => None,
-                        true => Some(buffer.read_string::<Utf8Decoder>(None)?),
-                    },
-                    keywords: match (value & 0x20) > 0 {
-                        false => None,
-                        true => Some(buffer.read_string::<Utf8Decoder>(None)?),
-                    },
-                    game_id: match (value & 0x01) > 0 {
-                        false => None,
-                        true => {
-                            let gid = buffer.read()?;
-                            appid = (gid & ((1 << 24) - 1)) as u32;
-
-                            Some(gid)
-                        }
-                    },
-                })
-            }
-        };
-
-        Ok(ServerInfo {
-            protocol_version: protocol,
-            name,
-            map,
-            folder,
-            game_mode,
-            appid,
-            players_online: players,
-            players_maximum: max_players,
-            players_bots: bots,
-            server_type,
-            environment_type,
-            has_password,
-            vac_secured,
-            the_ship,
-            game_version,
-            extra_data,
-            is_mod: false,
-            mod_data: None,
-        })
+        self.get_kind_request_data(engine, 0, Request::Info)
+            .and_then(|data| parse_server_info(engine, &data))
+            .map_err(|e| e.with_stage(QueryStage::Info))
     }
 
     /// Get the server player's.
     fn get_server_players(&mut self, engine: &Engine, protocol: u8) -> GDResult<Vec<ServerPlayer>> {
-        let data = self.get_kind_request_data(engine, protocol, Request::Players)?;
-        let mut buffer = Buffer::<LittleEndian>::new(&data);
+        self.get_kind_request_data(engine, protocol, Request::Players)
+            .and_then(|data| parse_server_players(engine, &data))
+            .map_err(|e| e.with_stage(QueryStage::Players))
+    }
 
-        let count = buffer.read::<u8>()? as usize;
-        let mut players: Vec<ServerPlayer> = Vec::with_capacity(count);
+    /// Get the server's rules.
+    fn get_server_rules(&mut self, engine: &Engine, protocol: u8) -> GDResult<HashMap<String, String>> {
+        self.get_kind_request_data(engine, protocol, Request::Rules)
+            .and_then(|data| parse_server_rules(engine, &data))
+            .map_err(|e| e.with_stage(QueryStage::Rules))
+    }
+}
 
-        for _ in 0 .. count {
-            buffer.move_cursor(1)?; //skip the index byte
+fn parse_goldsrc_server_info(buffer: &mut Buffer<LittleEndian>) -> GDResult<ServerInfo> {
+    let _header: u8 = buffer.read()?; //get the header (useless info)
+    let _address: String = buffer.read_string::<Utf8Decoder>(None)?; //get the server address (useless info)
+    let name = buffer.read_string::<Utf8Decoder>(None)?;
+    let map = buffer.read_string::<Utf8Decoder>(None)?;
+    let folder = buffer.read_string::<Utf8Decoder>(None)?;
+    let game_mode = buffer.read_string::<Utf8Decoder>(None)?;
+    let players = buffer.read()?;
+    let max_players = buffer.read()?;
+    let protocol = buffer.read()?;
+    let server_type = match buffer.read::<u8>()? {
+        68 => Server::Dedicated,    //'D'
+        76 => Server::NonDedicated, //'L'
+        80 => Server::TV,           //'P'
+        _ => Err(UnknownEnumCast)?,
+    };
+    let environment_type = match buffer.read::<u8>()? {
+        76 => Environment::Linux,   //'L'
+        87 => Environment::Windows, //'W'
+        _ => Err(UnknownEnumCast)?,
+    };
+    let has_password = buffer.read::<u8>()? == 1;
+    let is_mod = buffer.read::<u8>()? == 1;
+    let mod_data = match is_mod {
+        false => None,
+        true => {
+            Some(ModData {
+                link: buffer.read_string::<Utf8Decoder>(None)?,
+                download_link: buffer.read_string::<Utf8Decoder>(None)?,
+                version: buffer.read()?,
+                size: buffer.read()?,
+                multiplayer_only: buffer.read::<u8>()? == 1,
+                has_own_dll: buffer.read::<u8>()? == 1,
+            })
+        }
+    };
+    let vac_secured = buffer.read::<u8>()? == 1;
+    let bots = buffer.read::<u8>()?;
+
+    Ok(ServerInfo {
+        protocol_version: protocol,
+        name,
+        map,
+        folder,
+        game_mode,
+        appid: 0, // not present in the obsolete response
+        players_online: players,
+        players_maximum: max_players,
+        players_bots: bots,
+        server_type,
+        environment_type,
+        has_password,
+        vac_secured,
+        the_ship: None,
+        game_version: String::new(), // a version field only for the mod
+        extra_data: None,
+        is_mod,
+        mod_data,
+    })
+}
 
-            players.push(ServerPlayer {
-                name: buffer.read_string::<Utf8Decoder>(None)?,
-                score: buffer.read()?,
+/// Parse the payload of an `A2S_INFO` response.
+pub fn parse_server_info(engine: &Engine, data: &[u8]) -> GDResult<ServerInfo> {
+    let mut buffer = Buffer::<LittleEndian>::new(data);
+
+    if let Engine::GoldSrc(force) = engine {
+        if *force {
+            return parse_goldsrc_server_info(&mut buffer);
+        }
+    }
+
+    let protocol = buffer.read()?;
+    let name = buffer.read_string::<Utf8Decoder>(None)?;
+    let map = buffer.read_string::<Utf8Decoder>(None)?;
+    let folder = buffer.read_string::<Utf8Decoder>(None)?;
+    let game_mode = buffer.read_string::<Utf8Decoder>(None)?;
+    let mut appid = buffer.read::<u16>()? as u32;
+    let players = buffer.read()?;
+    let max_players = buffer.read()?;
+    let bots = buffer.read()?;
+    let server_type = Server::from_gldsrc(buffer.read()?)?;
+    let environment_type = Environment::from_gldsrc(buffer.read()?)?;
+    let has_password = buffer.read::<u8>()? == 1;
+    let vac_secured = buffer.read::<u8>()? == 1;
+    let the_ship = match *engine == Engine::new(2400) {
+        false => None,
+        true => {
+            Some(TheShip {
+                mode: buffer.read()?,
+                witnesses: buffer.read()?,
                 duration: buffer.read()?,
-                deaths: match *engine == Engine::new(2400) {
+            })
+        }
+    };
+    let game_version = buffer.read_string::<Utf8Decoder>(None)?;
+    let extra_data = match buffer.read::<u8>() {
+        Err(_) => None,
+        Ok(value) => {
+            Some(ExtraData {
+                port: match (value & 0x80) > 0 {
+                    false => None,
+                    true => Some(buffer.read()?),
+                },
+                steam_id: match (value & 0x10) > 0 {
                     false => None,
                     true => Some(buffer.read()?),
                 },
-                money: match *engine == Engine::new(2400) {
+                tv_port: match (value & 0x40) > 0 {
                     false => None,
                     true => Some(buffer.read()?),
                 },
-            });
+                tv_name: match (value & 0x40) > 0 {
+                    false => None,
+                    true => Some(buffer.read_string::<Utf8Decoder>(None)?),
+                },
+                keywords: match (value & 0x20) > 0 {
+                    false => None,
+                    true => Some(buffer.read_string::<Utf8Decoder>(None)?),
+                },
+                game_id: match (value & 0x01) > 0 {
+                    false => None,
+                    true => {
+                        let gid = buffer.read()?;
+                        appid = (gid & ((1 << 24) - 1)) as u32;
+
+                        Some(gid)
+                    }
+                },
+            })
         }
+    };
+
+    Ok(ServerInfo {
+        protocol_version: protocol,
+        name,
+        map,
+        folder,
+        game_mode,
+        appid,
+        players_online: players,
+        players_maximum: max_players,
+        players_bots: bots,
+        server_type,
+        environment_type,
+        has_password,
+        vac_secured,
+        the_ship,
+        game_version,
+        extra_data,
+        is_mod: false,
+        mod_data: None,
+    })
+}
+
+/// Parse the payload of an `A2S_PLAYER` response.
+pub fn parse_server_players(engine: &Engine, data: &[u8]) -> GDResult<Vec<ServerPlayer>> {
+    let mut buffer = Buffer::<LittleEndian>::new(data);
+
+    let count = buffer.read::<u8>()? as usize;
+    let mut players: Vec<ServerPlayer> = Vec::with_capacity(count);
 
-        Ok(players)
+    for _ in 0 .. count {
+        buffer.move_cursor(1)?; //skip the index byte
+
+        players.push(ServerPlayer {
+            name: buffer.read_string::<Utf8Decoder>(None)?,
+            score: buffer.read()?,
+            duration: buffer.read()?,
+            deaths: match *engine == Engine::new(2400) {
+                false => None,
+                true => Some(buffer.read()?),
+            },
+            money: match *engine == Engine::new(2400) {
+                false => None,
+                true => Some(buffer.read()?),
+            },
+        });
     }
 
-    /// Get the server's rules.
-    fn get_server_rules(&mut self, engine: &Engine, protocol: u8) -> GDResult<HashMap<String, String>> {
-        let data = self.get_kind_request_data(engine, protocol, Request::Rules)?;
-        let mut buffer = Buffer::<LittleEndian>::new(&data);
+    Ok(players)
+}
 
-        let count = buffer.read::<u16>()? as usize;
-        let mut rules: HashMap<String, String> = HashMap::with_capacity(count);
+/// Parse the payload of an `A2S_RULES` response.
+pub fn parse_server_rules(engine: &Engine, data: &[u8]) -> GDResult<HashMap<String, String>> {
+    let mut buffer = Buffer::<LittleEndian>::new(data);
 
-        for _ in 0 .. count {
-            let name = buffer.read_string::<Utf8Decoder>(None)?;
-            let value = buffer.read_string::<Utf8Decoder>(None)?;
+    let count = buffer.read::<u16>()? as usize;
+    let mut rules: HashMap<String, String> = HashMap::with_capacity(count);
 
-            rules.insert(name, value);
-        }
+    for _ in 0 .. count {
+        let name = buffer.read_string::<Utf8Decoder>(None)?;
+        let value = buffer.read_string::<Utf8Decoder>(None)?;
 
-        if *engine == Engine::new(632_360) {
-            // ROR2
-            rules.remove("Test");
-        }
+        rules.insert(name, value);
+    }
 
-        Ok(rules)
+    if *engine == Engine::new(632_360) {
+        // ROR2
+        rules.remove("Test");
     }
+
+    Ok(rules)
+}
+
+/// Parse a full response from the payloads of the `A2S_INFO`, `A2S_PLAYER`
+/// and `A2S_RULES` responses (the last two being optional).
+pub fn parse_response(
+    engine: &Engine,
+    info: &[u8],
+    players: Option<&[u8]>,
+    rules: Option<&[u8]>,
+) -> GDResult<Response> {
+    Ok(Response {
+        info: parse_server_info(engine, info)?,
+        players: players
+            .map(|data| parse_server_players(engine, data))
+            .transpose()?,
+        rules: rules
+            .map(|data| parse_server_rules(engine, data))
+            .transpose()?,
+        ping: None,
+    })
 }
 
 /// Query a server by providing the address, the port, the app, gather and
@@ -437,20 +525,13 @@ pub fn query(
     timeout_settings: Option<TimeoutSettings>,
 ) -> GDResult<Response> {
     let response_gather_settings = gather_settings.unwrap_or_default();
-    get_response(address, engine, response_gather_settings, timeout_settings)
+    get_response(address, engine, response_gather_settings, timeout_settings).map_err(|e| e.with_address(*address))
 }
 
-fn get_response(
-    address: &SocketAddr,
-    engine: Engine,
-    gather_settings: GatheringSettings,
-    timeout_settings: Option<TimeoutSettings>,
-) -> GDResult<Response> {
-    let mut client = ValveProtocol::new(address, timeout_settings)?;
-
-    let info = client.get_server_info(&engine)?;
-
-    if let Engine::Source(Some(appids)) = &engine {
+/// Check that the queried server is running one of the app ids of the engine
+/// (if any were specified).
+fn check_app_id(engine: &Engine, info: &ServerInfo, gather_settings: &GatheringSettings) -> GDResult<()> {
+    if let Engine::Source(Some(appids)) = engine {
         let mut is_specified_id = false;
 
         if appids.0 == info.appid {
@@ -466,6 +547,20 @@ fn get_response(
         }
     }
 
+    Ok(())
+}
+
+fn get_response(
+    address: &SocketAddr,
+    engine: Engine,
+    gather_settings: GatheringSettings,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Response> {
+    let mut client = ValveProtocol::new(address, timeout_settings)?;
+
+    let info = client.get_server_info(&engine)?;
+    check_app_id(&engine, &info, &gather_settings)?;
+
     let protocol = info.protocol_version;
 
     Ok(Response {
@@ -478,5 +573,147 @@ fn get_response(
             gather_settings.rules,
             client.get_server_rules(&engine, protocol)
         ),
+        ping: client.socket.ping(),
     })
 }
+
+/// The async counterpart of [ValveProtocol].
+#[cfg(feature = "tokio")]
+pub(crate) struct AsyncValveProtocol {
+    socket: AsyncUdpSocket,
+    retry_count: usize,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncValveProtocol {
+    pub async fn new(address: &SocketAddr, timeout_settings: Option<TimeoutSettings>) -> GDResult<Self> {
+        let socket = AsyncUdpSocket::new(address, &timeout_settings).await?;
+        let retry_count = TimeoutSettings::get_retries_or_default(&timeout_settings);
+
+        Ok(Self {
+            socket,
+            retry_count,
+        })
+    }
+
+    /// The round-trip time of the first request.
+    pub const fn ping(&self) -> Option<Duration> { self.socket.ping() }
+
+    async fn receive(&mut self, engine: &Engine, protocol: u8, buffer_size: usize) -> GDResult<Packet> {
+        let mut assembler = PacketAssembler::new(engine, protocol);
+
+        loop {
+            let data = self.socket.receive(Some(buffer_size)).await?;
+            if let Some(packet) = assembler.push(&data)? {
+                return Ok(packet);
+            }
+        }
+    }
+
+    async fn get_kind_request_data(&mut self, engine: &Engine, protocol: u8, kind: Request) -> GDResult<Vec<u8>> {
+        self.get_request_data(engine, protocol, kind as u8, kind.get_default_payload())
+            .await
+    }
+
+    /// Ask for a specific request only.
+    /// This function will retry fetch on timeouts.
+    pub async fn get_request_data(
+        &mut self,
+        engine: &Engine,
+        protocol: u8,
+        kind: u8,
+        payload: Vec<u8>,
+    ) -> GDResult<Vec<u8>> {
+        retry_on_timeout_async!(
+            self.retry_count,
+            self.get_request_data_impl(engine, protocol, kind, payload.clone())
+        )
+    }
+
+    /// Ask for a specific request only (without retry logic).
+    async fn get_request_data_impl(
+        &mut self,
+        engine: &Engine,
+        protocol: u8,
+        kind: u8,
+        payload: Vec<u8>,
+    ) -> GDResult<Vec<u8>> {
+        self.socket
+            .send(&Packet::new(kind, payload).to_bytes())
+            .await?;
+
+        let mut packet = self.receive(engine, protocol, PACKET_SIZE).await?;
+        while packet.kind == 0x41 {
+            // 'A'
+            self.socket
+                .send(&challenge_request(kind, packet.payload))
+                .await
+                .map_err(|e| e.with_stage(QueryStage::Challenge))?;
+
+            packet = self
+                .receive(engine, protocol, PACKET_SIZE)
+                .await
+                .map_err(|e| e.with_stage(QueryStage::Challenge))?;
+        }
+
+        Ok(packet.payload)
+    }
+
+    /// Get the server information's.
+    async fn get_server_info(&mut self, engine: &Engine) -> GDResult<ServerInfo> {
+        self.get_kind_request_data(engine, 0, Request::Info)
+            .await
+            .and_then(|data| parse_server_info(engine, &data))
+            .map_err(|e| e.with_stage(QueryStage::Info))
+    }
+
+    /// Get the server player's.
+    async fn get_server_players(&mut self, engine: &Engine, protocol: u8) -> GDResult<Vec<ServerPlayer>> {
+        self.get_kind_request_data(engine, protocol, Request::Players)
+            .await
+            .and_then(|data| parse_server_players(engine, &data))
+            .map_err(|e| e.with_stage(QueryStage::Players))
+    }
+
+    /// Get the server's rules.
+    async fn get_server_rules(&mut self, engine: &Engine, protocol: u8) -> GDResult<HashMap<String, String>> {
+        self.get_kind_request_data(engine, protocol, Request::Rules)
+            .await
+            .and_then(|data| parse_server_rules(engine, &data))
+            .map_err(|e| e.with_stage(QueryStage::Rules))
+    }
+}
+
+/// The async counterpart of [query].
+#[cfg(feature = "tokio")]
+pub async fn query_async(
+    address: &SocketAddr,
+    engine: Engine,
+    gather_settings: Option<GatheringSettings>,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Response> {
+    let gather_settings = gather_settings.unwrap_or_default();
+    async {
+        let mut client = AsyncValveProtocol::new(address, timeout_settings).await?;
+
+        let info = client.get_server_info(&engine).await?;
+        check_app_id(&engine, &info, &gather_settings)?;
+
+        let protocol = info.protocol_version;
+
+        Ok(Response {
+            info,
+            players: maybe_gather!(
+                gather_settings.players,
+                client.get_server_players(&engine, protocol).await
+            ),
+            rules: maybe_gather!(
+                gather_settings.rules,
+                client.get_server_rules(&engine, protocol).await
+            ),
+            ping: client.socket.ping(),
+        })
+    }
+    .await
+    .map_err(|e: GDError| e.with_address(*address))
+}
@@ -0,0 +1,292 @@
+use crate::{
+    protocols::{
+        types::TimeoutSettings,
+        valve::{challenge_request, parse_server_info, request, Engine, PacketAssembler, Request, ServerInfo},
+    },
+    socket::UdpMultiSocket,
+    GDErrorKind::PacketReceive,
+    GDResult,
+};
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+const PACKET_SIZE: usize = 6144;
+
+/// The state of a server that hasn't finished answering yet.
+struct Peer<'a> {
+    assembler: PacketAssembler<'a>,
+    retries_left: usize,
+    /// When the current try times out, None means never.
+    deadline: Option<Instant>,
+}
+
+/// Query the info (`A2S_INFO`) of many servers using a single socket, use this
+/// if you want to keep the same socket between scans.
+pub struct Scanner {
+    socket: UdpMultiSocket,
+    read_timeout: Option<Duration>,
+    retries: usize,
+}
+
+impl Scanner {
+    /// Bind a new socket, `ipv6` being whether the servers to scan are IPv6
+    /// ones.
+    ///
+    /// The read timeout and retry count of the timeout settings apply to every
+    /// server on its own, an unanswered request being sent again up to
+    /// `retries` times.
+    pub fn new(ipv6: bool, timeout_settings: Option<TimeoutSettings>) -> GDResult<Self> {
+        Ok(Self {
+            socket: UdpMultiSocket::new(ipv6, &timeout_settings)?,
+            read_timeout: TimeoutSettings::get_read_and_write_or_defaults(&timeout_settings).0,
+            retries: TimeoutSettings::get_retries_or_default(&timeout_settings),
+        })
+    }
+
+    fn next_deadline(&self) -> Option<Instant> { self.read_timeout.map(|timeout| Instant::now() + timeout) }
+
+    /// Scan the servers, returning the result of every (unique) address in
+    /// the order they finished.
+    pub fn scan(&mut self, engine: &Engine, addresses: &[SocketAddr]) -> Vec<(SocketAddr, GDResult<ServerInfo>)> {
+        let info_request = request(Request::Info);
+
+        let mut results = Vec::with_capacity(addresses.len());
+        let mut peers: HashMap<SocketAddr, Peer> = HashMap::with_capacity(addresses.len());
+
+        let mut seen = HashSet::with_capacity(addresses.len());
+        for address in addresses {
+            if !seen.insert(address) {
+                continue;
+            }
+
+            match self.socket.send_to(address, &info_request) {
+                Ok(()) => {
+                    peers.insert(
+                        *address,
+                        Peer {
+                            assembler: PacketAssembler::new(engine, 0),
+                            retries_left: self.retries,
+                            deadline: self.next_deadline(),
+                        },
+                    );
+                }
+                Err(e) => results.push((*address, Err(e))),
+            }
+        }
+
+        while !peers.is_empty() {
+            // Wait at most until the closest deadline.
+            let now = Instant::now();
+            let wait = peers
+                .values()
+                .filter_map(|peer| peer.deadline)
+                .min()
+                .map(|deadline| {
+                    deadline
+                        .saturating_duration_since(now)
+                        .max(Duration::from_millis(1))
+                });
+
+            match self.socket.receive_from(Some(PACKET_SIZE), wait) {
+                Ok(Some((packet, source))) => {
+                    // Replies from addresses that aren't (or aren't anymore) being scanned are
+                    // ignored.
+                    let Some(peer) = peers.get_mut(&source) else {
+                        continue;
+                    };
+                    peer.deadline = self.next_deadline();
+
+                    let result = match peer.assembler.push(&packet) {
+                        Ok(None) => continue,
+                        Ok(Some(packet)) if packet.kind == 0x41 => {
+                            // 'A'
+                            peer.assembler = PacketAssembler::new(engine, 0);
+                            match self.socket.send_to(
+                                &source,
+                                &challenge_request(Request::Info as u8, packet.payload),
+                            ) {
+                                Ok(()) => continue,
+                                Err(e) => Err(e),
+                            }
+                        }
+                        Ok(Some(packet)) => parse_server_info(engine, &packet.payload),
+                        Err(e) => Err(e),
+                    };
+
+                    peers.remove(&source);
+                    results.push((source, result));
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    let message = e.source.map_or_else(
+                        || "The socket failed".to_string(),
+                        |source| source.to_string(),
+                    );
+                    results.extend(
+                        peers
+                            .drain()
+                            .map(|(address, _)| (address, Err(PacketReceive.context(message.clone())))),
+                    );
+                }
+            }
+
+            let now = Instant::now();
+            let timed_out: Vec<SocketAddr> = peers
+                .iter()
+                .filter(|(_, peer)| peer.deadline.map_or(false, |deadline| deadline <= now))
+                .map(|(address, _)| *address)
+                .collect();
+
+            for address in timed_out {
+                let Some(peer) = peers.get_mut(&address) else {
+                    continue;
+                };
+
+                let result = if peer.retries_left == 0 {
+                    Err(PacketReceive.context("The server didn't answer in time"))
+                } else {
+                    peer.retries_left -= 1;
+                    peer.assembler = PacketAssembler::new(engine, 0);
+                    peer.deadline = self.next_deadline();
+                    match self.socket.send_to(&address, &info_request) {
+                        Ok(()) => continue,
+                        Err(e) => Err(e),
+                    }
+                };
+
+                peers.remove(&address);
+                results.push((address, result));
+            }
+        }
+
+        results
+    }
+}
+
+/// Query the info of many servers that use the same engine from a single
+/// socket (see [Scanner]), the addresses must all be either IPv4 or IPv6 ones.
+pub fn scan(
+    addresses: &[SocketAddr],
+    engine: &Engine,
+    timeout_settings: Option<TimeoutSettings>,
+) -> GDResult<Vec<(SocketAddr, GDResult<ServerInfo>)>> {
+    let ipv6 = addresses.first().map_or(false, SocketAddr::is_ipv6);
+    let mut scanner = Scanner::new(ipv6, timeout_settings)?;
+
+    Ok(scanner.scan(engine, addresses))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::valve_info;
+    use std::net::UdpSocket;
+    use std::thread;
+
+    fn settings(retries: usize) -> Option<TimeoutSettings> {
+        TimeoutSettings::new(Some(Duration::from_millis(200)), None, None, retries).ok()
+    }
+
+    /// Answers the first request with a challenge and the challenged one with a
+    /// split response.
+    fn challenging_server() -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let mut buffer = [0; 1400];
+            let (size, client) = socket.recv_from(&mut buffer).unwrap();
+            assert_eq!(buffer[.. size], request(Request::Info));
+            socket
+                .send_to(b"\xFF\xFF\xFF\xFFA\x01\x02\x03\x04", client)
+                .unwrap();
+
+            let (size, client) = socket.recv_from(&mut buffer).unwrap();
+            assert_eq!(
+                buffer[.. size],
+                challenge_request(Request::Info as u8, vec![1, 2, 3, 4])
+            );
+
+            let packet = valve_info("Challenging");
+            let (first, second) = packet.split_at(20);
+            for (number, payload) in [(1, second), (0, first)] {
+                let chunk = [
+                    b"\xFE\xFF\xFF\xFF".as_slice(),
+                    &7u32.to_le_bytes(),
+                    &[2, number],
+                    &1248u16.to_le_bytes(),
+                    payload,
+                ]
+                .concat();
+                socket.send_to(&chunk, client).unwrap();
+            }
+        });
+
+        address
+    }
+
+    /// Ignores the first `ignored` requests, then answers directly.
+    fn slow_server(ignored: usize) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let mut buffer = [0; 1400];
+            for _ in 0 .. ignored {
+                socket.recv_from(&mut buffer).unwrap();
+            }
+
+            let (_, client) = socket.recv_from(&mut buffer).unwrap();
+            socket.send_to(&valve_info("Slow"), client).unwrap();
+        });
+
+        address
+    }
+
+    #[test]
+    fn scan_many() {
+        let challenging = challenging_server();
+        let slow = slow_server(1);
+        // Bound but never answering.
+        let silent_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let silent = silent_socket.local_addr().unwrap();
+
+        let results = scan(
+            &[challenging, slow, silent, challenging],
+            &Engine::new(440),
+            settings(1),
+        )
+        .unwrap();
+        assert_eq!(results.len(), 3);
+
+        let result = |address| &results.iter().find(|(a, _)| *a == address).unwrap().1;
+        assert_eq!(result(challenging).as_ref().unwrap().name, "Challenging");
+        assert_eq!(result(slow).as_ref().unwrap().name, "Slow");
+        assert!(matches!(result(silent), Err(e) if e.kind == PacketReceive));
+
+        // The silent server was asked twice (once more because of the retry).
+        silent_socket
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .unwrap();
+        let mut buffer = [0; 1400];
+        for _ in 0 .. 2 {
+            silent_socket.recv_from(&mut buffer).unwrap();
+        }
+        assert!(silent_socket.recv_from(&mut buffer).is_err());
+    }
+
+    #[test]
+    fn scan_without_retries() {
+        let slow = slow_server(1);
+
+        let results = scan(&[slow], &Engine::new(440), settings(0)).unwrap();
+        assert!(matches!(&results[0], (address, Err(e)) if *address == slow && e.kind == PacketReceive));
+    }
+
+    #[test]
+    fn scan_nothing() {
+        assert!(scan(&[], &Engine::new(440), None).unwrap().is_empty());
+    }
+}
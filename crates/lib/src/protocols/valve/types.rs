@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 
 use crate::protocols::types::{CommonPlayer, CommonResponse, ExtraRequestSettings, GatherToggle, GenericPlayer};
 use crate::GDErrorKind::UnknownEnumCast;
@@ -55,10 +56,12 @@ pub struct Response {
     pub info: ServerInfo,
     pub players: Option<Vec<ServerPlayer>>,
     pub rules: Option<HashMap<String, String>>,
+    /// Round-trip time of the first request.
+    pub ping: Option<Duration>,
 }
 
 impl CommonResponse for Response {
-    fn as_original(&self) -> GenericResponse { GenericResponse::Valve(self) }
+    fn as_original(&self) -> GenericResponse<'_> { GenericResponse::Valve(self) }
 
     fn name(&self) -> Option<&str> { Some(&self.info.name) }
     fn game_mode(&self) -> Option<&str> { Some(&self.info.game_mode) }
@@ -68,6 +71,7 @@ impl CommonResponse for Response {
     fn players_online(&self) -> u32 { self.info.players_online.into() }
     fn players_bots(&self) -> Option<u32> { Some(self.info.players_bots.into()) }
     fn has_password(&self) -> Option<bool> { Some(self.info.has_password) }
+    fn ping(&self) -> Option<Duration> { self.ping }
 
     fn players(&self) -> Option<Vec<&dyn CommonPlayer>> {
         self.players
@@ -135,9 +139,24 @@ pub struct ServerPlayer {
 }
 
 impl CommonPlayer for ServerPlayer {
-    fn as_original(&self) -> GenericPlayer { GenericPlayer::Valve(self) }
+    fn as_original(&self) -> GenericPlayer<'_> { GenericPlayer::Valve(self) }
     fn name(&self) -> &str { &self.name }
     fn score(&self) -> Option<i32> { Some(self.score) }
+    fn time_connected(&self) -> Option<Duration> { duration_from_secs(self.duration) }
+    fn deaths(&self) -> Option<u32> { self.deaths }
+
+    fn extra(&self) -> BTreeMap<String, String> {
+        self.money
+            .map(|money| ("money".to_string(), money.to_string()))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Convert a player's duration, which is None if it isn't a valid one.
+pub(crate) fn duration_from_secs(secs: f32) -> Option<Duration> {
+    // Also false for NaN.
+    (secs >= 0. && secs < u32::MAX as f32).then(|| Duration::from_secs_f32(secs))
 }
 
 /// Only present for [the ship](https://developer.valvesoftware.com/wiki/The_Ship).
@@ -194,9 +213,12 @@ pub(crate) fn get_optional_extracted_data(data: Option<ExtraData>) -> ExtractedD
     }
 }
 
-#[derive(Debug, Clone)]
-pub(crate) struct Packet {
+/// A (reassembled) packet, see [PacketAssembler](super::PacketAssembler).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
     pub header: u32,
+    /// The response kind, `0x41` (`A`) is a challenge which must be answered
+    /// with a [challenge_request](super::challenge_request).
     pub kind: u8,
     pub payload: Vec<u8>,
 }
@@ -210,7 +232,7 @@ impl Packet {
         }
     }
 
-    pub fn new_from_bufferer(buffer: &mut Buffer<LittleEndian>) -> GDResult<Self> {
+    pub(crate) fn new_from_bufferer(buffer: &mut Buffer<LittleEndian>) -> GDResult<Self> {
         Ok(Self {
             header: buffer.read::<u32>()?,
             kind: buffer.read::<u8>()?,
@@ -229,9 +251,9 @@ impl Packet {
 }
 
 /// The type of the request, see the [protocol](https://developer.valvesoftware.com/wiki/Server_queries).
-#[derive(Eq, PartialEq, Copy, Clone)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
 #[repr(u8)]
-pub(crate) enum Request {
+pub enum Request {
     /// Known as `A2S_INFO`
     Info = 0x54,
     /// Known as `A2S_PLAYERS`
@@ -332,6 +354,7 @@ pub mod game {
     use super::{Server, ServerPlayer};
     use crate::protocols::valve::types::get_optional_extracted_data;
     use std::collections::HashMap;
+    use std::time::Duration;
 
     #[cfg(feature = "serde")]
     use serde::{Deserialize, Serialize};
@@ -400,6 +423,8 @@ pub mod game {
         pub keywords: Option<String>,
         /// Server's rules.
         pub rules: HashMap<String, String>,
+        /// Round-trip time of the first request.
+        pub ping: Option<Duration>,
     }
 
     impl Response {
@@ -431,6 +456,7 @@ pub mod game {
                 tv_name,
                 keywords,
                 rules: response.rules.unwrap_or_default(),
+                ping: response.ping,
             }
         }
     }
@@ -5,10 +5,16 @@ use crate::{
 };
 
 use std::{
-    io::{Read, Write},
+    io::{ErrorKind, Read, Write},
     net::{self, SocketAddr},
+    time::{Duration, Instant},
 };
 
+#[cfg(feature = "tokio")]
+use crate::GDErrorKind;
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
 const DEFAULT_PACKET_SIZE: usize = 1024;
 
 /// A trait defining the basic functionalities of a network socket.
@@ -51,6 +57,13 @@ pub trait Socket {
     /// A result containing received data or an error.
     fn receive(&mut self, size: Option<usize>) -> GDResult<Vec<u8>>;
 
+    /// Get the round-trip time of the first request that got a response.
+    ///
+    /// # Returns
+    /// The time between the last send and the first receive, None if nothing
+    /// was received yet.
+    fn ping(&self) -> Option<Duration>;
+
     /// Get the remote port of the socket.
     ///
     /// # Returns
@@ -61,15 +74,38 @@ pub trait Socket {
     ///
     /// # Returns
     /// The local SocketAddr.
+    #[cfg_attr(not(feature = "packet_capture"), allow(dead_code))]
     fn local_addr(&self) -> std::io::Result<SocketAddr>;
 }
 
+/// Measures the round-trip time of the first request that got a response.
+#[derive(Debug, Clone, Copy, Default)]
+struct PingTimer {
+    sent: Option<Instant>,
+    ping: Option<Duration>,
+}
+
+impl PingTimer {
+    fn sent(&mut self) {
+        if self.ping.is_none() {
+            self.sent = Some(Instant::now());
+        }
+    }
+
+    fn received(&mut self) {
+        if self.ping.is_none() {
+            self.ping = self.sent.map(|sent| sent.elapsed());
+        }
+    }
+}
+
 /// Implementation of a TCP socket.
 pub struct TcpSocketImpl {
     /// The underlying TCP socket stream.
     socket: net::TcpStream,
     /// The address of the remote host.
     address: SocketAddr,
+    ping_timer: PingTimer,
 }
 
 impl Socket for TcpSocketImpl {
@@ -82,6 +118,7 @@ impl Socket for TcpSocketImpl {
         let socket = Self {
             socket: socket.map_err(|e| SocketConnect.context(e))?,
             address: *address,
+            ping_timer: PingTimer::default(),
         };
 
         socket.apply_timeout(timeout_settings)?;
@@ -99,18 +136,30 @@ impl Socket for TcpSocketImpl {
 
     fn send(&mut self, data: &[u8]) -> GDResult<()> {
         self.socket.write(data).map_err(|e| PacketSend.context(e))?;
+        self.ping_timer.sent();
         Ok(())
     }
 
     fn receive(&mut self, size: Option<usize>) -> GDResult<Vec<u8>> {
         let mut buf = Vec::with_capacity(size.unwrap_or(DEFAULT_PACKET_SIZE));
-        self.socket
-            .read_to_end(&mut buf)
-            .map_err(|e| PacketReceive.context(e))?;
+        let mut chunk = [0; DEFAULT_PACKET_SIZE];
+        loop {
+            match self.socket.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read) => {
+                    // The ping ends with the first bytes, not with the stream.
+                    self.ping_timer.received();
+                    buf.extend_from_slice(&chunk[.. read]);
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(PacketReceive.context(e)),
+            }
+        }
 
         Ok(buf)
     }
 
+    fn ping(&self) -> Option<Duration> { self.ping_timer.ping }
     fn port(&self) -> u16 { self.address.port() }
     fn local_addr(&self) -> std::io::Result<SocketAddr> { self.socket.local_addr() }
 }
@@ -121,6 +170,7 @@ pub struct UdpSocketImpl {
     socket: net::UdpSocket,
     /// The address of the remote host.
     address: SocketAddr,
+    ping_timer: PingTimer,
 }
 
 impl Socket for UdpSocketImpl {
@@ -130,6 +180,7 @@ impl Socket for UdpSocketImpl {
         let socket = Self {
             socket,
             address: *address,
+            ping_timer: PingTimer::default(),
         };
 
         socket.apply_timeout(timeout_settings)?;
@@ -149,6 +200,7 @@ impl Socket for UdpSocketImpl {
         self.socket
             .send_to(data, self.address)
             .map_err(|e| PacketSend.context(e))?;
+        self.ping_timer.sent();
 
         Ok(())
     }
@@ -159,10 +211,12 @@ impl Socket for UdpSocketImpl {
             .socket
             .recv_from(&mut buf)
             .map_err(|e| PacketReceive.context(e))?;
+        self.ping_timer.received();
 
         Ok(buf[.. number_of_bytes_received].to_vec())
     }
 
+    fn ping(&self) -> Option<Duration> { self.ping_timer.ping }
     fn port(&self) -> u16 { self.address.port() }
     fn local_addr(&self) -> std::io::Result<SocketAddr> { self.socket.local_addr() }
 }
@@ -177,6 +231,254 @@ pub(crate) type UdpSocket = crate::capture::socket::CapturedUdpSocket;
 #[cfg(feature = "packet_capture")]
 pub(crate) type TcpSocket = crate::capture::socket::CapturedTcpSocket;
 
+/// A UDP socket that talks to many remote hosts at once (unlike
+/// [UdpSocketImpl] that is tied to a single one), to scan servers.
+pub struct UdpMultiSocket {
+    /// The underlying UDP socket.
+    socket: net::UdpSocket,
+}
+
+impl UdpMultiSocket {
+    /// Create a new socket, `ipv6` being whether the remote hosts are IPv6
+    /// ones.
+    ///
+    /// # Arguments
+    /// * `ipv6` - Whether to bind an IPv6 socket.
+    /// * `timeout_settings` - Optional timeout settings, only the write timeout
+    ///   being used (see [UdpMultiSocket::receive_from]).
+    ///
+    /// # Returns
+    /// A result containing the socket instance or an error.
+    pub fn new(ipv6: bool, timeout_settings: &Option<TimeoutSettings>) -> GDResult<Self> {
+        let socket =
+            net::UdpSocket::bind(if ipv6 { "[::]:0" } else { "0.0.0.0:0" }).map_err(|e| SocketBind.context(e))?;
+
+        let (_, write) = TimeoutSettings::get_read_and_write_or_defaults(timeout_settings);
+        socket.set_write_timeout(write).unwrap(); // unwrapping because TimeoutSettings::new
+                                                  // checks if these are 0 and throws an error
+
+        Ok(Self { socket })
+    }
+
+    /// Send data to a remote host.
+    ///
+    /// # Arguments
+    /// * `address` - The address to send the data to.
+    /// * `data` - Data to be sent.
+    ///
+    /// # Returns
+    /// A result indicating success or error in sending data.
+    pub fn send_to(&mut self, address: &SocketAddr, data: &[u8]) -> GDResult<()> {
+        #[cfg(feature = "packet_capture")]
+        crate::capture::socket::capture_udp(
+            crate::capture::packet::Direction::Send,
+            address,
+            &self
+                .socket
+                .local_addr()
+                .map_err(|e| PacketSend.context(e))?,
+            data,
+        )?;
+
+        self.socket
+            .send_to(data, address)
+            .map_err(|e| PacketSend.context(e))?;
+
+        Ok(())
+    }
+
+    /// Receive a datagram from any remote host.
+    ///
+    /// # Arguments
+    /// * `size` - Optional size of data to receive.
+    /// * `timeout` - How long to wait at most, None waiting indefinitely (must
+    ///   not be 0).
+    ///
+    /// # Returns
+    /// A result containing the received data and the address it came from,
+    /// None if nothing came before the timeout, or an error.
+    pub fn receive_from(
+        &mut self,
+        size: Option<usize>,
+        timeout: Option<Duration>,
+    ) -> GDResult<Option<(Vec<u8>, SocketAddr)>> {
+        self.socket
+            .set_read_timeout(timeout)
+            .map_err(|e| PacketReceive.context(e))?;
+
+        let mut buf: Vec<u8> = vec![0; size.unwrap_or(DEFAULT_PACKET_SIZE)];
+        let (number_of_bytes_received, source) = match self.socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => return Ok(None),
+            // An ICMP port unreachable from some host (reported on Windows).
+            Err(e) if e.kind() == ErrorKind::ConnectionReset => return Ok(None),
+            Err(e) => return Err(PacketReceive.context(e)),
+        };
+        buf.truncate(number_of_bytes_received);
+
+        #[cfg(feature = "packet_capture")]
+        crate::capture::socket::capture_udp(
+            crate::capture::packet::Direction::Receive,
+            &source,
+            &self
+                .socket
+                .local_addr()
+                .map_err(|e| PacketReceive.context(e))?,
+            &buf,
+        )?;
+
+        Ok(Some((buf, source)))
+    }
+}
+
+/// Run an IO future, mapping an elapsed timeout (or any IO error) to the
+/// given error kind, passing None will wait indefinitely.
+#[cfg(feature = "tokio")]
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    kind: GDErrorKind,
+    future: impl std::future::Future<Output = std::io::Result<T>>,
+) -> GDResult<T> {
+    match timeout {
+        None => future.await.map_err(|e| kind.context(e)),
+        Some(timeout) => {
+            tokio::time::timeout(timeout, future)
+                .await
+                .map_err(|e| kind.clone().context(e))?
+                .map_err(|e| kind.context(e))
+        }
+    }
+}
+
+/// Asynchronous implementation of a TCP socket (the counterpart of
+/// [TcpSocketImpl]).
+#[cfg(feature = "tokio")]
+pub struct AsyncTcpSocket {
+    /// The underlying TCP socket stream.
+    socket: tokio::net::TcpStream,
+    /// The address of the remote host.
+    address: SocketAddr,
+    /// The read and write timeouts.
+    timeouts: (Option<Duration>, Option<Duration>),
+    ping_timer: PingTimer,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncTcpSocket {
+    /// Create a new socket and connect to the remote address.
+    pub async fn new(address: &SocketAddr, timeout_settings: &Option<TimeoutSettings>) -> GDResult<Self> {
+        let socket = with_timeout(
+            TimeoutSettings::get_connect_or_default(timeout_settings),
+            SocketConnect,
+            tokio::net::TcpStream::connect(address),
+        )
+        .await?;
+
+        Ok(Self {
+            socket,
+            address: *address,
+            timeouts: TimeoutSettings::get_read_and_write_or_defaults(timeout_settings),
+            ping_timer: PingTimer::default(),
+        })
+    }
+
+    /// Send data over the socket.
+    pub async fn send(&mut self, data: &[u8]) -> GDResult<()> {
+        with_timeout(self.timeouts.1, PacketSend, self.socket.write_all(data)).await?;
+        self.ping_timer.sent();
+
+        Ok(())
+    }
+
+    /// Receive data from the socket (until the remote closes the stream).
+    pub async fn receive(&mut self, size: Option<usize>) -> GDResult<Vec<u8>> {
+        let mut buf = Vec::with_capacity(size.unwrap_or(DEFAULT_PACKET_SIZE));
+        let (socket, ping_timer) = (&mut self.socket, &mut self.ping_timer);
+        with_timeout(self.timeouts.0, PacketReceive, async {
+            let mut chunk = [0; DEFAULT_PACKET_SIZE];
+            loop {
+                let read = socket.read(&mut chunk).await?;
+                if read == 0 {
+                    return Ok(());
+                }
+
+                // The ping ends with the first bytes, not with the stream.
+                ping_timer.received();
+                buf.extend_from_slice(&chunk[.. read]);
+            }
+        })
+        .await?;
+
+        Ok(buf)
+    }
+
+    /// Get the round-trip time of the first request that got a response.
+    pub const fn ping(&self) -> Option<Duration> { self.ping_timer.ping }
+
+    /// Get the remote port of the socket.
+    pub fn port(&self) -> u16 { self.address.port() }
+}
+
+/// Asynchronous implementation of a UDP socket (the counterpart of
+/// [UdpSocketImpl]).
+#[cfg(feature = "tokio")]
+pub struct AsyncUdpSocket {
+    /// The underlying UDP socket.
+    socket: tokio::net::UdpSocket,
+    /// The address of the remote host.
+    address: SocketAddr,
+    /// The read and write timeouts.
+    timeouts: (Option<Duration>, Option<Duration>),
+    ping_timer: PingTimer,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncUdpSocket {
+    /// Create a new socket that will talk to the remote address.
+    pub async fn new(address: &SocketAddr, timeout_settings: &Option<TimeoutSettings>) -> GDResult<Self> {
+        let socket = tokio::net::UdpSocket::bind("0.0.0.0:0")
+            .await
+            .map_err(|e| SocketBind.context(e))?;
+
+        Ok(Self {
+            socket,
+            address: *address,
+            timeouts: TimeoutSettings::get_read_and_write_or_defaults(timeout_settings),
+            ping_timer: PingTimer::default(),
+        })
+    }
+
+    /// Send data over the socket.
+    pub async fn send(&mut self, data: &[u8]) -> GDResult<()> {
+        with_timeout(
+            self.timeouts.1,
+            PacketSend,
+            self.socket.send_to(data, self.address),
+        )
+        .await?;
+        self.ping_timer.sent();
+
+        Ok(())
+    }
+
+    /// Receive a datagram from the socket.
+    pub async fn receive(&mut self, size: Option<usize>) -> GDResult<Vec<u8>> {
+        let mut buf: Vec<u8> = vec![0; size.unwrap_or(DEFAULT_PACKET_SIZE)];
+        let (number_of_bytes_received, _) = with_timeout(
+            self.timeouts.0,
+            PacketReceive,
+            self.socket.recv_from(&mut buf),
+        )
+        .await?;
+        self.ping_timer.received();
+
+        Ok(buf[.. number_of_bytes_received].to_vec())
+    }
+
+    /// Get the round-trip time of the first request that got a response.
+    pub const fn ping(&self) -> Option<Duration> { self.ping_timer.ping }
+}
+
 #[cfg(test)]
 mod tests {
     use std::thread;
@@ -215,6 +517,28 @@ mod tests {
         assert_eq!(message, &received_message[..]);
     }
 
+    #[test]
+    fn test_tcp_socket_ping() {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let bound_address = listener.local_addr().unwrap();
+        let server_thread = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0; 4];
+            stream.read_exact(&mut buf).unwrap();
+            stream.write_all(b"po").unwrap();
+            // The end of the response is slow to come.
+            thread::sleep(Duration::from_millis(500));
+            stream.write_all(b"ng").unwrap();
+        });
+
+        let mut socket = TcpSocket::new(&bound_address, &None).unwrap();
+        socket.send(b"ping").unwrap();
+        assert_eq!(socket.receive(None).unwrap(), b"pong");
+        assert!(socket.ping().unwrap() < Duration::from_millis(500));
+
+        server_thread.join().expect("server thread panicked");
+    }
+
     #[test]
     fn test_udp_socket_send_and_receive() {
         // Spawn a thread to run the server
@@ -245,4 +569,87 @@ mod tests {
 
         assert_eq!(message, &received_message[..]);
     }
+
+    #[test]
+    fn test_udp_socket_ping() {
+        let socket = net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let bound_address = socket.local_addr().unwrap();
+        let server_thread = thread::spawn(move || {
+            let mut buf = [0; 1024];
+            for _ in 0 .. 2 {
+                let (_, src_addr) = socket.recv_from(&mut buf).unwrap();
+                socket.send_to(b"pong", src_addr).unwrap();
+            }
+        });
+
+        let mut socket = UdpSocket::new(&bound_address, &None).unwrap();
+        assert_eq!(socket.ping(), None);
+
+        socket.send(b"ping").unwrap();
+        socket.receive(None).unwrap();
+        let ping = socket.ping();
+        assert!(ping.is_some());
+
+        // Only the first exchange is timed.
+        socket.send(b"ping").unwrap();
+        socket.receive(None).unwrap();
+        assert_eq!(socket.ping(), ping);
+
+        server_thread.join().expect("server thread panicked");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_async_tcp_socket_send_and_receive() {
+        // Spawn a thread to run the server
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let bound_address = listener.local_addr().unwrap();
+        let server_thread = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0; 13];
+            stream.read_exact(&mut buf).unwrap();
+            stream.write_all(&buf).unwrap();
+        });
+
+        // Create an async TCP socket and send a message to the server
+        let mut socket = AsyncTcpSocket::new(&bound_address, &None).await.unwrap();
+        let message = b"hello, world!";
+        socket.send(message).await.unwrap();
+
+        // Receive the response from the server (the server closes the stream)
+        let received_message = socket.receive(None).await.unwrap();
+
+        server_thread.join().expect("server thread panicked");
+
+        assert_eq!(message, &received_message[..]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_async_udp_socket_send_receive_and_timeout() {
+        // Spawn a thread to run the server, which only answers once
+        let socket = net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let bound_address = socket.local_addr().unwrap();
+        let server_thread = thread::spawn(move || {
+            let mut buf = [0; 1024];
+            let (size, src_addr) = socket.recv_from(&mut buf).unwrap();
+            socket.send_to(&buf[.. size], src_addr).unwrap();
+        });
+
+        let timeout_settings = TimeoutSettings::new(Some(std::time::Duration::from_millis(200)), None, None, 0).ok();
+        let mut socket = AsyncUdpSocket::new(&bound_address, &timeout_settings)
+            .await
+            .unwrap();
+        let message = b"hello, world!";
+        socket.send(message).await.unwrap();
+
+        let received_message = socket.receive(None).await.unwrap();
+        assert_eq!(message, &received_message[..]);
+
+        server_thread.join().expect("server thread panicked");
+
+        // Nothing else will be sent, so the read timeout has to elapse
+        let error = socket.receive(None).await.unwrap_err();
+        assert_eq!(error, PacketReceive.into());
+    }
 }
@@ -29,6 +29,31 @@ pub fn retry_on_timeout<T>(mut retry_count: usize, mut fetch: impl FnMut() -> GD
     Err(last_err)
 }
 
+/// The async counterpart of [retry_on_timeout], `fetch` should be an
+/// expression resolving to a future, it is evaluated again on every try.
+#[cfg(feature = "tokio")]
+macro_rules! retry_on_timeout_async {
+    ($retry_count: expr, $fetch: expr) => {{
+        let mut retry_count: usize = $retry_count + 1;
+        let mut last_err = crate::GDErrorKind::PacketReceive.context("Retry count was 0");
+        loop {
+            if retry_count == 0 {
+                break Err(last_err);
+            }
+
+            last_err = match $fetch.await {
+                Ok(r) => break Ok(r),
+                Err(e) if e.kind == crate::GDErrorKind::PacketReceive || e.kind == crate::GDErrorKind::PacketSend => e,
+                Err(e) => break Err(e),
+            };
+            retry_count -= 1;
+        }
+    }};
+}
+
+#[cfg(feature = "tokio")]
+pub(crate) use retry_on_timeout_async;
+
 /// Run gather_fn based on the value of gather_toggle.
 ///
 /// # Parameters
@@ -0,0 +1,16 @@
+//! Packets shared by the integration and unit tests.
+
+/// The Source `A2S_INFO` response of a Team Fortress server (on ctf_2fort)
+/// named `name`.
+pub fn valve_info(name: &str) -> Vec<u8> {
+    [
+        b"\xFF\xFF\xFF\xFFI".as_slice(),
+        &[17],
+        name.as_bytes(),
+        b"\0ctf_2fort\0tf\0Team Fortress\0",
+        &440u16.to_le_bytes(),
+        &[5, 24, 1, b'd', b'l', 0, 1],
+        b"7370160\0",
+    ]
+    .concat()
+}
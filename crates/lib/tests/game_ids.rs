@@ -1,11 +1,26 @@
 #![cfg(all(test, feature = "game_defs"))]
 
-use gamedig::GAMES;
+use gamedig::{ALIASES, GAMES};
 
 use gamedig_id_tests::test_game_name_rules;
 
 #[test]
 fn check_definitions_match_name_rules() {
-    let wrong = test_game_name_rules(GAMES.entries().map(|(id, game)| (id.to_owned(), game.name)));
+    let wrong = test_game_name_rules(
+        GAMES
+            .entries()
+            .map(|(id, game)| (id.to_owned(), game.name.as_ref())),
+    );
     assert!(wrong.is_empty());
 }
+
+#[test]
+fn check_aliases_point_to_games() {
+    for (alias, id) in ALIASES.entries() {
+        assert!(!GAMES.contains_key(alias), "{alias} is a game id");
+        assert!(GAMES.contains_key(id), "{alias} points to the unknown {id}");
+        assert!(alias
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()));
+    }
+}
@@ -0,0 +1,519 @@
+//! Parse recorded packets using the request builders and response parsers,
+//! without any sockets.
+
+use gamedig::protocols::types::{CommonResponse, CommonResponseOwned};
+use gamedig::protocols::GenericResponse;
+use gamedig::protocols::{gamespy, quake, unreal2, valve};
+use gamedig::QueryStage;
+use std::time::Duration;
+
+#[path = "common/packets.rs"]
+mod packets;
+
+#[test]
+fn valve_requests() {
+    assert_eq!(
+        valve::request(valve::Request::Info),
+        b"\xFF\xFF\xFF\xFFTSource Engine Query\0"
+    );
+    assert_eq!(
+        valve::request(valve::Request::Players),
+        b"\xFF\xFF\xFF\xFFU\xFF\xFF\xFF\xFF"
+    );
+    assert_eq!(
+        valve::challenge_request(valve::Request::Info as u8, vec![1, 2, 3, 4]),
+        b"\xFF\xFF\xFF\xFFTSource Engine Query\0\x01\x02\x03\x04"
+    );
+}
+
+#[test]
+fn valve_single_packet() {
+    let engine = valve::Engine::new(440);
+    let mut assembler = valve::PacketAssembler::new(&engine, 0);
+
+    let packet = assembler
+        .push(&packets::valve_info("My Server"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(packet.kind, b'I');
+
+    let info = valve::parse_server_info(&engine, &packet.payload).unwrap();
+    assert_eq!(info.protocol_version, 17);
+    assert_eq!(info.name, "My Server");
+    assert_eq!(info.map, "ctf_2fort");
+    assert_eq!(info.appid, 440);
+    assert_eq!(info.players_online, 5);
+    assert_eq!(info.players_maximum, 24);
+    assert_eq!(info.players_bots, 1);
+    assert_eq!(info.server_type, valve::Server::Dedicated);
+    assert_eq!(info.environment_type, valve::Environment::Linux);
+    assert!(!info.has_password);
+    assert!(info.vac_secured);
+    assert_eq!(info.game_version, "7370160");
+    assert!(info.extra_data.is_none());
+}
+
+#[test]
+fn valve_split_packet() {
+    let engine = valve::Engine::new(440);
+    let mut assembler = valve::PacketAssembler::new(&engine, 17);
+
+    let packet = packets::valve_info("My Server");
+    let (first, second) = packet.split_at(20);
+    let chunk = |number: u8, payload: &[u8]| {
+        [
+            b"\xFE\xFF\xFF\xFF".as_slice(),
+            &7u32.to_le_bytes(),
+            &[2, number],
+            &1248u16.to_le_bytes(),
+            payload,
+        ]
+        .concat()
+    };
+
+    assert!(assembler.push(&chunk(0, first)).unwrap().is_none());
+    let reassembled = assembler.push(&chunk(1, second)).unwrap().unwrap();
+
+    assert_eq!(reassembled.payload, packet[5 ..]);
+}
+
+#[test]
+fn valve_response() {
+    let engine = valve::Engine::new(440);
+    let info = &packets::valve_info("My Server")[5 ..];
+    let players = [
+        [1].as_slice(),
+        &[0],
+        b"Bob\0",
+        &12i32.to_le_bytes(),
+        &61.5f32.to_le_bytes(),
+    ]
+    .concat();
+    let rules = [
+        2u16.to_le_bytes().as_slice(),
+        b"mp_timelimit\x0030\0",
+        b"sv_cheats\x000\0",
+    ]
+    .concat();
+
+    let response = valve::parse_response(&engine, info, Some(&players), Some(&rules)).unwrap();
+
+    let common = response.players().unwrap();
+    assert_eq!(
+        common[0].time_connected(),
+        Some(Duration::from_secs_f32(61.5))
+    );
+    assert_eq!(common[0].as_json().time_connected, Some(61));
+    assert_eq!(common[0].deaths(), None);
+
+    let players = response.players.unwrap();
+    assert_eq!(players.len(), 1);
+    assert_eq!(players[0].name, "Bob");
+    assert_eq!(players[0].score, 12);
+    assert_eq!(players[0].duration, 61.5);
+
+    let rules = response.rules.unwrap();
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules["mp_timelimit"], "30");
+    assert_eq!(rules["sv_cheats"], "0");
+}
+
+#[test]
+fn valve_bad_packet() {
+    let engine = valve::Engine::new(440);
+
+    assert!(valve::parse_server_info(&engine, &[17, b'a']).is_err());
+}
+
+#[test]
+fn gamespy_one() {
+    assert_eq!(gamespy::one::STATUS_REQUEST, b"\\status\\xserverquery");
+
+    let mut collector = gamespy::one::ServerValuesCollector::default();
+    assert!(!collector
+        .push(b"\\hostname\\My Server\\mapname\\DM-Deck16\\gametype\\DeathMatch\\queryid\\7.1")
+        .unwrap());
+    assert!(collector
+        .push(b"\\gamever\\436\\maxplayers\\16\\password\\0\\player_0\\Bob\\ping_0\\40\\frags_0\\3\\final\\\\queryid\\7.2")
+        .unwrap());
+
+    let response = gamespy::one::parse_response(collector.into_values()).unwrap();
+    assert_eq!(response.name, "My Server");
+    assert_eq!(response.map, "DM-Deck16");
+    assert_eq!(response.game_mode, "DeathMatch");
+    assert_eq!(response.game_version, "436");
+    assert_eq!(response.players_maximum, 16);
+    assert_eq!(response.players_online, 1);
+    assert!(!response.has_password);
+    assert_eq!(response.players[0].name, "Bob");
+    assert_eq!(response.players[0].ping, 40);
+    assert_eq!(response.players[0].score, 3);
+}
+
+#[test]
+fn gamespy_one_wrong_query_id() {
+    let mut collector = gamespy::one::ServerValuesCollector::default();
+    collector
+        .push(b"\\hostname\\My Server\\queryid\\7.1")
+        .unwrap();
+
+    assert!(collector
+        .push(b"\\mapname\\DM-Deck16\\queryid\\8.2")
+        .is_err());
+}
+
+#[test]
+fn gamespy_two() {
+    let packet = [
+        b"\0\0\0\0\x01".as_slice(),
+        b"hostname\0My Server\0mapname\0Island\0password\x000\0maxplayers\x0032\0numplayers\x001\0",
+        b"\0\0",
+        b"\x01player_\0score_\0ping_\0team_\0\0",
+        b"Bob\x005\x0030\x001\0",
+        b"\0\x01team_t\0score_t\0\0",
+        b"Red\x0010\0",
+    ]
+    .concat();
+
+    let response = gamespy::two::parse_response(&packet).unwrap();
+    assert_eq!(response.name, "My Server");
+    assert_eq!(response.map, "Island");
+    assert!(!response.has_password);
+    assert_eq!(response.players_maximum, 32);
+    assert_eq!(response.players_online, 1);
+    assert_eq!(response.players[0].name, "Bob");
+    assert_eq!(response.players[0].score, 5);
+    assert_eq!(response.players[0].ping, 30);
+    assert_eq!(response.players[0].team_index, 1);
+    let players = response.players().unwrap();
+    assert_eq!(players[0].ping(), Some(30));
+    assert_eq!(players[0].team(), Some(1));
+    assert_eq!(response.teams[0].name, "Red");
+    assert_eq!(response.teams[0].score, 10);
+
+    let teams = response.teams().unwrap();
+    assert_eq!(teams[0].name, "Red");
+    assert_eq!(teams[0].score, Some(10));
+    assert_eq!(teams[0].players[0].name(), "Bob");
+}
+
+#[test]
+fn gamespy_two_bad_header() {
+    assert!(gamespy::two::parse_response(b"\0\0\0\0\x02hostname\0My Server\0").is_err());
+}
+
+#[test]
+fn gamespy_three() {
+    assert_eq!(
+        gamespy::three::handshake_request(),
+        b"\xFE\xFD\x09\0\0\0\x01"
+    );
+
+    let challenge = gamespy::three::parse_handshake_response(b"\x09\0\0\0\x0112345\0").unwrap();
+    assert_eq!(challenge, Some(12345));
+    assert_eq!(
+        gamespy::three::data_request(challenge, gamespy::three::DEFAULT_PAYLOAD),
+        [
+            b"\xFE\xFD\0\0\0\0\x01".as_slice(),
+            &12345i32.to_be_bytes(),
+            &gamespy::three::DEFAULT_PAYLOAD,
+        ]
+        .concat()
+    );
+
+    let mut collector = gamespy::three::PacketCollector::new(false);
+    let packets = collector
+        .push(
+            &[
+                b"\0\0\0\0\x01splitnum\0\x80\0".as_slice(),
+                b"hostname\0My Server\0mapname\0Crossroads\0gametype\0ctf\0gamever\x001.0\0",
+                b"password\x000\0maxplayers\x0016\0numplayers\x002\0\0",
+            ]
+            .concat(),
+        )
+        .unwrap()
+        .unwrap();
+
+    let response = gamespy::three::parse_response(&packets).unwrap();
+    assert_eq!(response.name, "My Server");
+    assert_eq!(response.map, "Crossroads");
+    assert_eq!(response.game_mode, "ctf");
+    assert_eq!(response.game_version, "1.0");
+    assert!(!response.has_password);
+    assert_eq!(response.players_maximum, 16);
+    assert_eq!(response.players_online, 2);
+}
+
+#[test]
+fn gamespy_three_wrong_session() {
+    assert!(gamespy::three::parse_handshake_response(b"\x09\0\0\0\x0212345\0").is_err());
+}
+
+#[test]
+fn quake_one() {
+    assert_eq!(quake::one::request(), b"\xFF\xFF\xFF\xFFstatus\0");
+
+    let response = quake::one::parse_response(
+        b"\xFF\xFF\xFF\xFFn\\hostname\\My Server\\map\\e1m1\\maxclients\\8\\*version\\2.40\n",
+    )
+    .unwrap();
+    assert_eq!(response.name, "My Server");
+    assert_eq!(response.map, "e1m1");
+    assert_eq!(response.players_maximum, 8);
+    assert_eq!(response.game_version.as_deref(), Some("2.40"));
+}
+
+#[test]
+fn quake_players() {
+    // Ending with a null byte, like some servers do.
+    let response = quake::three::parse_response(
+        b"\xFF\xFF\xFF\xFFstatusResponse\n\\sv_hostname\\My Server\\mapname\\q3dm17\\sv_maxclients\\16\n7 50 \"Bob\"\n2 30 \"Alice\"\n\0",
+    )
+    .unwrap();
+    assert_eq!(response.players_online, 2);
+    assert_eq!(response.players[0].name, "Bob");
+    assert_eq!(response.players[0].score, 7);
+    assert_eq!(response.players[0].ping, 50);
+    assert_eq!(response.players[1].name, "Alice");
+
+    // A player without a ping.
+    let error = quake::three::parse_response(
+        b"\xFF\xFF\xFF\xFFstatusResponse\n\\sv_hostname\\My Server\\mapname\\q3dm17\\sv_maxclients\\16\n7\n",
+    )
+    .unwrap_err();
+    assert_eq!(error.stage, Some(QueryStage::Players));
+}
+
+#[test]
+fn quake_three() {
+    assert_eq!(quake::three::request(), b"\xFF\xFF\xFF\xFFgetstatus\0");
+
+    let response = quake::three::parse_response(
+        b"\xFF\xFF\xFF\xFFstatusResponse\n\\sv_hostname\\My Server\\mapname\\q3dm17\\sv_maxclients\\16\n",
+    )
+    .unwrap();
+    assert_eq!(response.name, "My Server");
+    assert_eq!(response.map, "q3dm17");
+    assert_eq!(response.players_maximum, 16);
+    assert!(response.teams().is_none());
+
+    let response = quake::three::parse_response(
+        b"\xFF\xFF\xFF\xFFstatusResponse\n\\sv_hostname\\My Server\\mapname\\q3dm17\\sv_maxclients\\16\\g_redteam\\Reds\\Score_Red\\3\\Score_Blue\\1\\Players_Red\\2 \\Players_Blue\\1 \n7 50 \"Bob\"\n2 30 \"Alice\"\n",
+    )
+    .unwrap();
+    assert_eq!(response.players_online, 2);
+    let teams = response.teams().unwrap();
+    assert_eq!(teams.len(), 2);
+    assert_eq!(teams[0].name, "Reds");
+    assert_eq!(teams[0].score, Some(3));
+    assert_eq!(teams[0].players[0].name(), "Alice");
+    assert_eq!(teams[1].name, "Blue");
+    assert_eq!(teams[1].score, Some(1));
+    assert_eq!(teams[1].players[0].name(), "Bob");
+
+    // The response header of another version
+    assert!(quake::three::parse_response(b"\xFF\xFF\xFF\xFFn\\sv_hostname\\My Server\n").is_err());
+}
+
+#[test]
+fn owned_response() {
+    let response = quake::three::parse_response(
+        b"\xFF\xFF\xFF\xFFstatusResponse\n\\sv_hostname\\My Server\\mapname\\q3dm17\\sv_maxclients\\16\\Score_Red\\3\\Players_Red\\1\n7 50 \"Bob\"\n",
+    )
+    .unwrap();
+    let boxed: Box<dyn CommonResponse> = Box::new(response.clone());
+
+    let mut owned = CommonResponseOwned::from(boxed);
+    assert_eq!(owned.as_json(), response.as_json());
+    assert_eq!(
+        owned.as_original(),
+        GenericResponse::Quake(quake::VersionedResponse::TwoAndThree(&response))
+    );
+
+    owned.ping = Some(Duration::from_millis(25));
+    assert_eq!(owned.as_json().ping, Some(25));
+
+    #[cfg(feature = "serde")]
+    {
+        let stored = serde_json::to_string(&owned).unwrap();
+        // In milliseconds, like the ping of the JSON response.
+        assert!(stored.contains(r#""ping":25,"#));
+        let read: CommonResponseOwned = serde_json::from_str(&stored).unwrap();
+        assert_eq!(read, owned);
+        assert_eq!(read.teams().unwrap()[0].players[0].name(), "Bob");
+    }
+}
+
+fn unreal2_string(value: &str) -> Vec<u8> { [&[value.len() as u8 + 1], value.as_bytes(), &[0]].concat() }
+
+#[test]
+fn unreal2() {
+    assert_eq!(
+        unreal2::request(unreal2::PacketKind::Players),
+        [0x79, 0, 0, 0, 2]
+    );
+
+    let server_info = [
+        [0x80, 0, 0, 0, 0].as_slice(),
+        &1u32.to_le_bytes(),
+        &unreal2_string("127.0.0.1"),
+        &7777u32.to_le_bytes(),
+        &7778u32.to_le_bytes(),
+        &unreal2_string("My Server"),
+        &unreal2_string("DM-Rankin"),
+        &unreal2_string("xDeathMatch"),
+        &2u32.to_le_bytes(),
+        &16u32.to_le_bytes(),
+    ]
+    .concat();
+    let mutators_and_rules = [
+        [0x80, 0, 0, 0, 1].as_slice(),
+        &unreal2_string("Mutator"),
+        &unreal2_string("MutInstaGib"),
+        &unreal2_string("GamePassword"),
+        &unreal2_string("True"),
+    ]
+    .concat();
+    let player = |id: u32, name: &str, ping: u32| {
+        [
+            [0x80, 0, 0, 0, 2].as_slice(),
+            &id.to_le_bytes(),
+            &unreal2_string(name),
+            &ping.to_le_bytes(),
+            &10i32.to_le_bytes(),
+            &0u32.to_le_bytes(),
+        ]
+        .concat()
+    };
+
+    let response = unreal2::parse_response(
+        &server_info,
+        &[mutators_and_rules],
+        &[
+            player(1, "Bob", 50),
+            player(2, "Bot", 0),
+            player(0, "Red Team", 0),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(response.server_info.name, "My Server");
+    assert_eq!(response.server_info.map, "DM-Rankin");
+    assert_eq!(response.server_info.game_type, "xDeathMatch");
+    assert_eq!(response.server_info.game_port, 7777);
+    assert_eq!(response.server_info.num_players, 2);
+    assert_eq!(response.server_info.max_players, 16);
+    assert!(response.server_info.password);
+    assert!(response.mutators_and_rules.mutators.contains("MutInstaGib"));
+    assert_eq!(response.players.players.len(), 1);
+    assert_eq!(response.players.players[0].name, "Bob");
+    assert_eq!(response.players.bots.len(), 1);
+    assert_eq!(response.players.bots[0].name, "Bot");
+
+    assert_eq!(response.players.teams[0].name, "Red Team");
+
+    assert_eq!(response.players_bots(), Some(1));
+    let players: Vec<_> = response
+        .players()
+        .unwrap()
+        .iter()
+        .map(|player| (player.name().to_string(), player.is_bot()))
+        .collect();
+    assert_eq!(
+        players,
+        [
+            ("Bob".to_string(), Some(false)),
+            ("Bot".to_string(), Some(true))
+        ]
+    );
+    let teams = response.teams().unwrap();
+    assert_eq!(teams[0].name, "Red Team");
+    assert_eq!(teams[0].score, Some(10));
+    assert!(teams[0].players.is_empty());
+    assert_eq!(response.as_json().teams.unwrap()[0].name, "Red Team");
+}
+
+#[test]
+fn unreal2_unexpected_packet_kind() {
+    assert!(unreal2::parse_server_info(&[0x80, 0, 0, 0, 2, 0, 0, 0, 0]).is_err());
+}
+
+#[cfg(feature = "games")]
+fn varint(mut value: usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+    bytes
+}
+
+#[cfg(feature = "games")]
+#[test]
+fn minecraft_java() {
+    use gamedig::games::minecraft::{protocol, RequestSettings};
+
+    let [handshake, status, ping] = protocol::java_requests(
+        &RequestSettings::new_just_hostname("localhost".to_string()),
+        25565,
+    )
+    .unwrap();
+    assert_eq!(
+        handshake,
+        [
+            b"\x13\0\xFF\xFF\xFF\xFF\x0F\x09localhost".as_slice(),
+            &25565u16.to_le_bytes(),
+            &[1]
+        ]
+        .concat()
+    );
+    assert_eq!(status, [1, 0]);
+    assert_eq!(ping, [1, 1]);
+
+    let json = r#"{"version":{"name":"1.20.4","protocol":765},"players":{"max":20,"online":1,"sample":[{"name":"Bob","id":"4566e69f-c907-48ee-8d71-d7ba5aa00d20"}]},"description":{"text":"A Minecraft Server"}}"#;
+    let payload = [[0].as_slice(), &varint(json.len()), json.as_bytes()].concat();
+    let packet = [varint(payload.len()), payload].concat();
+
+    let response = protocol::parse_java_response(&packet).unwrap();
+    assert_eq!(response.game_version, "1.20.4");
+    assert_eq!(response.protocol_version, 765);
+    assert_eq!(response.players_maximum, 20);
+    assert_eq!(response.players_online, 1);
+    assert_eq!(response.players.unwrap()[0].name, "Bob");
+}
+
+#[cfg(feature = "games")]
+#[test]
+fn minecraft_bedrock() {
+    use gamedig::games::minecraft::{protocol, GameMode};
+
+    let status = "MCPE;My Server;630;1.20.50;3;10;13253860892328930865;Bedrock level;Survival;1;19132;19133;";
+    let packet = [
+        [0x1c].as_slice(),
+        &protocol::BEDROCK_STATUS_REQUEST[1 .. 9],
+        &[0; 8],
+        &protocol::BEDROCK_STATUS_REQUEST[9 .. 25],
+        &(status.len() as u16).to_be_bytes(),
+        status.as_bytes(),
+    ]
+    .concat();
+
+    let response = protocol::parse_bedrock_response(&packet).unwrap();
+    assert_eq!(response.edition, "MCPE");
+    assert_eq!(response.name, "My Server");
+    assert_eq!(response.version_name, "1.20.50");
+    assert_eq!(response.players_online, 3);
+    assert_eq!(response.players_maximum, 10);
+    assert_eq!(response.map.as_deref(), Some("Bedrock level"));
+    assert_eq!(response.game_mode, Some(GameMode::Survival));
+}
+
+#[cfg(feature = "games")]
+#[test]
+fn minecraft_legacy() {
+    use gamedig::games::minecraft::{protocol, LegacyGroup};
+
+    assert_eq!(protocol::legacy_request(LegacyGroup::VB1_8), [0xFE]);
+    assert_eq!(protocol::legacy_request(LegacyGroup::V1_4), [0xFE, 0x01]);
+}